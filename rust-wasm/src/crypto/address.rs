@@ -0,0 +1,410 @@
+// Cryptocurrency addresses from secp256k1 keys: Ethereum (Keccak-256, EIP-55 checksum casing),
+// Bitcoin P2PKH / P2SH (Base58Check), P2WPKH / P2WSH (Bech32) and P2TR (Bech32m, BIP-86 key-path
+// tweak), WIF private keys, and Ethereum ABI function selectors / event topics.
+use bech32::{FromBase32, ToBase32, Variant, u5};
+use k256::elliptic_curve::PrimeField;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{ProjectivePoint, PublicKey, Scalar, SecretKey};
+use serde_json::{Value, json};
+
+use crate::encoding::base58;
+use crate::hash::{keccak256, ripemd160, sha256};
+
+struct Network {
+    name: &'static str,
+    p2pkh: u8,
+    p2sh: u8,
+    wif: u8,
+    hrp: &'static str,
+}
+
+const NETWORKS: [Network; 3] = [
+    Network { name: "mainnet", p2pkh: 0x00, p2sh: 0x05, wif: 0x80, hrp: "bc" },
+    Network { name: "testnet", p2pkh: 0x6f, p2sh: 0xc4, wif: 0xef, hrp: "tb" },
+    // Regtest shares the testnet Base58 versions
+    Network { name: "regtest", p2pkh: 0x6f, p2sh: 0xc4, wif: 0xef, hrp: "bcrt" },
+];
+
+fn network(name: &str) -> Result<&'static Network, String> {
+    let name = match name.to_ascii_lowercase().as_str() {
+        "" | "bitcoin" | "main" => "mainnet".to_string(),
+        "test" | "signet" => "testnet".to_string(),
+        other => other.to_string(),
+    };
+    NETWORKS
+        .iter()
+        .find(|n| n.name == name)
+        .ok_or_else(|| format!("unknown network: {} (use mainnet, testnet or regtest)", name))
+}
+
+/// SEC1 public key (33 or 65 bytes) or the 64-byte x || y form used by Ethereum
+fn parse_public_key(key: &[u8]) -> Result<PublicKey, String> {
+    let bytes = if key.len() == 64 { [&[0x04], key].concat() } else { key.to_vec() };
+    PublicKey::from_sec1_bytes(&bytes).map_err(|_| "invalid secp256k1 public key".to_string())
+}
+
+// ============================================================================
+// Ethereum
+// ============================================================================
+
+/// EIP-55: a hex letter is upper case when the matching nibble of
+/// Keccak-256(lowercase hex address) is 8 or more
+fn checksum_case(hex_address: &str) -> String {
+    let lower = hex_address.to_ascii_lowercase();
+    let hash = keccak256::digest(lower.as_bytes());
+    let cased: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| if (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f >= 8 { c.to_ascii_uppercase() } else { c })
+        .collect();
+    format!("0x{}", cased)
+}
+
+/// Last 20 bytes of Keccak-256 of the uncompressed key without its 0x04 prefix
+pub fn ethereum_address(public: &PublicKey) -> String {
+    let point = public.to_encoded_point(false);
+    let hash = keccak256::digest(&point.as_bytes()[1..]);
+    checksum_case(&hex::encode(&hash[12..]))
+}
+
+fn ethereum_hex(address: &str) -> Result<&str, String> {
+    let hex_part = address.trim().strip_prefix("0x").or_else(|| address.trim().strip_prefix("0X")).unwrap_or(address.trim());
+    if hex_part.len() != 40 || !hex_part.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err("an Ethereum address is 40 hex digits".to_string());
+    }
+    Ok(hex_part)
+}
+
+/// EIP-55 checksum casing of an Ethereum address
+pub fn eip55(address: &str) -> Result<String, String> {
+    Ok(checksum_case(ethereum_hex(address)?))
+}
+
+/// Canonical ABI type: default sizes (uint -> uint256), tuples recursively, names and
+/// `indexed` / data locations dropped
+fn canonical_type(param: &str) -> Result<String, String> {
+    let param = param.trim();
+    if let Some(inner) = param.strip_prefix('(').or_else(|| param.strip_prefix("tuple(")) {
+        let close = matching_paren(inner).ok_or_else(|| format!("unbalanced parentheses in: {}", param))?;
+        let suffix: String = inner[close + 1..].chars().take_while(|c| "[]0123456789".contains(*c)).collect();
+        return Ok(format!("({}){}", canonical_params(&inner[..close])?, suffix));
+    }
+    let ty = param.split_whitespace().next().ok_or("empty parameter type")?;
+    let (base, dims) = ty.split_at(ty.find('[').unwrap_or(ty.len()));
+    if base.is_empty() || !base.bytes().all(|c| c.is_ascii_alphanumeric()) || !dims.chars().all(|c| "[]0123456789".contains(c)) {
+        return Err(format!("invalid parameter type: {}", ty));
+    }
+    let base = match base {
+        "uint" => "uint256",
+        "int" => "int256",
+        "byte" => "bytes1",
+        "fixed" => "fixed128x18",
+        "ufixed" => "ufixed128x18",
+        other => other,
+    };
+    Ok(format!("{}{}", base, dims))
+}
+
+/// Index of the parenthesis closing an already opened one
+fn matching_paren(text: &str) -> Option<usize> {
+    let mut depth = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn canonical_params(params: &str) -> Result<String, String> {
+    if params.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let mut types = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(canonical_type(&params[start..i])?);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    types.push(canonical_type(&params[start..])?);
+    Ok(types.join(","))
+}
+
+/// Function selector (first 4 bytes) and event topic (all 32 bytes) of Keccak-256 over the
+/// canonical signature; accepts Solidity-style declarations such as
+/// `event Transfer(address indexed from, address indexed to, uint value)`
+pub fn abi_signature(signature: &str) -> Result<String, String> {
+    let text = signature.trim().trim_end_matches(';');
+    let text = text.strip_prefix("function ").or_else(|| text.strip_prefix("event ")).or_else(|| text.strip_prefix("error ")).unwrap_or(text);
+    let open = text.find('(').ok_or("signature must look like name(type,...)")?;
+    let name = text[..open].trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+        return Err(format!("invalid function or event name: {}", name));
+    }
+    let close = matching_paren(&text[open + 1..]).ok_or("unbalanced parentheses in signature")? + open + 1;
+    let canonical = format!("{}({})", name, canonical_params(&text[open + 1..close])?);
+    let hash = keccak256::digest(canonical.as_bytes());
+    Ok(json!({
+        "signature": canonical,
+        "selector": format!("0x{}", hex::encode(&hash[..4])),
+        "topic": format!("0x{}", hex::encode(hash)),
+    })
+    .to_string())
+}
+
+// ============================================================================
+// Bitcoin
+// ============================================================================
+
+fn segwit_encode(hrp: &str, version: u8, program: &[u8]) -> String {
+    let variant = if version == 0 { Variant::Bech32 } else { Variant::Bech32m };
+    let mut data = vec![u5::try_from_u8(version).expect("witness version below 32")];
+    data.extend(program.to_base32());
+    bech32::encode(hrp, data, variant).expect("valid hrp")
+}
+
+/// BIP-341 tagged hash: SHA-256(SHA-256(tag) || SHA-256(tag) || data)
+fn tagged_hash(tag: &str, data: &[u8]) -> [u8; 32] {
+    let tag = sha256::digest(tag.as_bytes());
+    sha256::digest(&[&tag[..], &tag[..], data].concat())
+}
+
+/// BIP-86 output key: x(P + H_TapTweak(x(P)) * G) with P lifted to even y (no script tree)
+fn taproot_output_key(public: &PublicKey) -> Result<[u8; 32], String> {
+    let compressed = public.to_encoded_point(true);
+    let x = &compressed.as_bytes()[1..];
+    let point = if compressed.as_bytes()[0] == 0x03 { -public.to_projective() } else { public.to_projective() };
+    let tweak: Option<Scalar> = Scalar::from_repr(tagged_hash("TapTweak", x).into()).into();
+    let tweak = tweak.ok_or("taproot tweak is out of range")?;
+    let output = (point + ProjectivePoint::GENERATOR * tweak).to_affine();
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&output.to_encoded_point(true).as_bytes()[1..]);
+    Ok(key)
+}
+
+/// P2SH and P2WSH addresses of a redeem / witness script; returns JSON
+pub fn script_addresses(script: &[u8], network_name: &str) -> Result<String, String> {
+    if script.is_empty() {
+        return Err("script is empty".to_string());
+    }
+    let net = network(network_name)?;
+    let witness_program = sha256::digest(script);
+    let nested = [&[0x00, 0x20], &witness_program[..]].concat();
+    Ok(json!({
+        "network": net.name,
+        "p2sh": base58::check_encode(&[&[net.p2sh], &ripemd160::hash160(script)[..]].concat()),
+        "p2wsh": segwit_encode(net.hrp, 0, &witness_program),
+        "p2sh_p2wsh": base58::check_encode(&[&[net.p2sh], &ripemd160::hash160(&nested)[..]].concat()),
+    })
+    .to_string())
+}
+
+/// WIF: Base58Check of version || key, plus 0x01 when the key is used compressed
+pub fn wif_encode(private: &[u8], compressed: bool, network_name: &str) -> Result<String, String> {
+    SecretKey::from_slice(private).map_err(|_| "invalid secp256k1 private key".to_string())?;
+    let net = network(network_name)?;
+    let mut payload = [&[net.wif], private].concat();
+    if compressed {
+        payload.push(0x01);
+    }
+    Ok(base58::check_encode(&payload))
+}
+
+/// Decode a WIF key; returns JSON with the private key, compression flag, network and addresses
+pub fn wif_decode(wif: &str) -> Result<String, String> {
+    let payload = base58::check_decode(wif)?;
+    let compressed = match payload.len() {
+        33 => false,
+        34 if payload[33] == 0x01 => true,
+        _ => return Err("invalid WIF length".to_string()),
+    };
+    let net = NETWORKS.iter().find(|n| n.wif == payload[0]).ok_or_else(|| format!("unknown WIF version byte: 0x{:02x}", payload[0]))?;
+    let secret = SecretKey::from_slice(&payload[1..33]).map_err(|_| "invalid secp256k1 private key".to_string())?;
+    let public = secret.public_key();
+    let p2pkh_key = public.to_encoded_point(compressed);
+    Ok(json!({
+        "private_key": hex::encode(&payload[1..33]),
+        "compressed": compressed,
+        "network": net.name,
+        "public_key": hex::encode(p2pkh_key.as_bytes()),
+        "p2pkh": base58::check_encode(&[&[net.p2pkh], &ripemd160::hash160(p2pkh_key.as_bytes())[..]].concat()),
+    })
+    .to_string())
+}
+
+/// Every address type for a secp256k1 key: 32 bytes is a private key (WIF added),
+/// 33 / 65 bytes a SEC1 public key, 64 bytes an uncompressed x || y key
+pub fn addresses(key: &[u8], network_name: &str) -> Result<String, String> {
+    let net = network(network_name)?;
+    let (public, private) = if key.len() == 32 {
+        let secret = SecretKey::from_slice(key).map_err(|_| "invalid secp256k1 private key".to_string())?;
+        (secret.public_key(), Some(key))
+    } else {
+        (parse_public_key(key)?, None)
+    };
+    let compressed = public.to_encoded_point(true);
+    let uncompressed = public.to_encoded_point(false);
+    let key_hash = ripemd160::hash160(compressed.as_bytes());
+    let nested = [&[0x00, 0x14], &key_hash[..]].concat();
+    let mut out = json!({
+        "network": net.name,
+        "public_key": hex::encode(compressed.as_bytes()),
+        "public_key_uncompressed": hex::encode(uncompressed.as_bytes()),
+        "ethereum": ethereum_address(&public),
+        "p2pkh": base58::check_encode(&[&[net.p2pkh], &key_hash[..]].concat()),
+        "p2pkh_uncompressed": base58::check_encode(&[&[net.p2pkh], &ripemd160::hash160(uncompressed.as_bytes())[..]].concat()),
+        "p2sh_p2wpkh": base58::check_encode(&[&[net.p2sh], &ripemd160::hash160(&nested)[..]].concat()),
+        "p2wpkh": segwit_encode(net.hrp, 0, &key_hash),
+        "p2tr": segwit_encode(net.hrp, 1, &taproot_output_key(&public)?),
+    });
+    if let Some(private) = private {
+        out["wif"] = json!(wif_encode(private, true, net.name)?);
+        out["wif_uncompressed"] = json!(wif_encode(private, false, net.name)?);
+    }
+    Ok(out.to_string())
+}
+
+fn validate_ethereum(address: &str) -> Result<Value, String> {
+    let hex_part = ethereum_hex(address)?;
+    let checksummed = checksum_case(hex_part);
+    let mixed = hex_part.bytes().any(|c| c.is_ascii_lowercase()) && hex_part.bytes().any(|c| c.is_ascii_uppercase());
+    if mixed && checksummed[2..] != *hex_part {
+        return Err("invalid EIP-55 checksum".to_string());
+    }
+    Ok(json!({ "chain": "ethereum", "type": "account", "checksummed": mixed, "address": checksummed }))
+}
+
+fn validate_segwit(address: &str) -> Result<Value, String> {
+    let (hrp, data, variant) = bech32::decode(address).map_err(|e| format!("invalid Bech32 address: {}", e))?;
+    let net = NETWORKS.iter().find(|n| n.hrp == hrp).ok_or_else(|| format!("unknown address prefix: {}", hrp))?;
+    let (version, program) = data.split_first().ok_or("missing witness version")?;
+    let version = version.to_u8();
+    let program = Vec::<u8>::from_base32(program).map_err(|_| "invalid witness program padding".to_string())?;
+    if version > 16 {
+        return Err(format!("invalid witness version: {}", version));
+    }
+    if (version == 0) != (variant == Variant::Bech32) {
+        return Err(format!("witness version {} must use {}", version, if version == 0 { "Bech32" } else { "Bech32m" }));
+    }
+    let kind = match (version, program.len()) {
+        (0, 20) => "p2wpkh".to_string(),
+        (0, 32) => "p2wsh".to_string(),
+        (0, n) => return Err(format!("a version 0 witness program is 20 or 32 bytes, not {}", n)),
+        (1, 32) => "p2tr".to_string(),
+        (_, 2..=40) => format!("witness_v{}", version),
+        (_, n) => return Err(format!("invalid witness program length: {}", n)),
+    };
+    Ok(json!({
+        "chain": "bitcoin",
+        "type": kind,
+        "network": net.name,
+        "witness_version": version,
+        "program": hex::encode(program),
+    }))
+}
+
+fn validate_base58(address: &str) -> Result<Value, String> {
+    let payload = base58::check_decode(address)?;
+    if payload.len() != 21 {
+        return Err(format!("a Base58Check address holds 21 bytes, not {}", payload.len()));
+    }
+    let (kind, net) = NETWORKS
+        .iter()
+        .find_map(|n| match payload[0] {
+            v if v == n.p2pkh => Some(("p2pkh", n)),
+            v if v == n.p2sh => Some(("p2sh", n)),
+            _ => None,
+        })
+        .ok_or_else(|| format!("unknown address version byte: 0x{:02x}", payload[0]))?;
+    // Base58 cannot tell testnet from regtest
+    Ok(json!({ "chain": "bitcoin", "type": kind, "network": net.name, "hash": hex::encode(&payload[1..]) }))
+}
+
+/// Detect and validate an Ethereum, Base58Check or SegWit address; returns JSON with
+/// valid, chain, type, network and the decoded payload, or the error
+pub fn validate(address: &str) -> String {
+    let address = address.trim();
+    let result = if address.starts_with("0x") || address.starts_with("0X") {
+        validate_ethereum(address)
+    } else if address.contains('1') && NETWORKS.iter().any(|n| address.to_ascii_lowercase().starts_with(&format!("{}1", n.hrp))) {
+        validate_segwit(address)
+    } else {
+        validate_base58(address)
+    };
+    match result {
+        Ok(mut info) => {
+            info["valid"] = json!(true);
+            info
+        }
+        Err(e) => json!({ "valid": false, "error": e }),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(json: &str, name: &str) -> String {
+        serde_json::from_str::<Value>(json).unwrap()[name].as_str().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn test_bitcoin_addresses() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let info = addresses(&one, "mainnet").unwrap();
+        assert_eq!(field(&info, "p2pkh"), "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH");
+        assert_eq!(field(&info, "p2pkh_uncompressed"), "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        assert_eq!(field(&info, "p2sh_p2wpkh"), "3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN");
+        assert_eq!(field(&info, "p2wpkh"), "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+        assert_eq!(field(&info, "wif"), "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn");
+        assert_eq!(field(&info, "wif_uncompressed"), "5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf");
+        assert_eq!(field(&info, "ethereum"), "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf");
+        assert_eq!(field(&wif_decode("5HpHagT65TZzG1PH3CSu63k8DbpvD8s5ip4nEB3kEsreAnchuDf").unwrap(), "p2pkh"), "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm");
+        // BIP-86 m/86'/0'/0'/0/0 of the "abandon ... about" mnemonic
+        let bip86 = hex::decode("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115").unwrap();
+        assert_eq!(field(&addresses(&bip86, "mainnet").unwrap(), "p2tr"), "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr");
+        assert_eq!(field(&addresses(&one, "testnet").unwrap(), "p2tr"), "tb1pmfr3p9j00pfxjh0zmgp99y8zftmd3s5pmedqhyptwy6lm87hf5ssk79hv2");
+    }
+
+    #[test]
+    fn test_ethereum() {
+        assert_eq!(eip55("0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap(), "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(eip55("D1220A0CF47C7B9BE7A2E6BA89F429762E7B9ADB").unwrap(), "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb");
+        let transfer = abi_signature("transfer(address,uint256)").unwrap();
+        assert_eq!(field(&transfer, "selector"), "0xa9059cbb");
+        let event = abi_signature("event Transfer(address indexed from, address indexed to, uint value);").unwrap();
+        assert_eq!(field(&event, "signature"), "Transfer(address,address,uint256)");
+        assert_eq!(field(&event, "topic"), "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+        assert_eq!(field(&abi_signature("f((uint a, bytes b)[] items, int[2])").unwrap(), "signature"), "f((uint256,bytes)[],int256[2])");
+    }
+
+    #[test]
+    fn test_validate() {
+        let info: Value = serde_json::from_str(&validate("bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr")).unwrap();
+        assert_eq!((info["valid"].as_bool(), info["type"].as_str()), (Some(true), Some("p2tr")));
+        assert_eq!(field(&validate("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"), "type"), "p2pkh");
+        assert_eq!(field(&validate("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"), "chain"), "ethereum");
+        assert_eq!(field(&validate("0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d35A"), "error"), "invalid EIP-55 checksum");
+        assert_eq!(field(&validate("mrCDrCybB6J1vRfbwM5hemdJz73FwDBC8r"), "network"), "testnet");
+        assert_eq!(field(&validate("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"), "type"), "p2wpkh");
+        assert!(field(&validate("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t5"), "error").starts_with("invalid Bech32"));
+        // BIP-350: a version 0 program with a Bech32m checksum
+        assert_eq!(field(&validate("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh"), "error"), "witness version 0 must use Bech32");
+    }
+}
@@ -69,3 +69,22 @@ pub fn decrypt_auto_iv(combined: &[u8], key_text: &str) -> Result<String, String
     
     decrypt(ciphertext, key_text, &iv_arr)
 }
+
+// Encrypts binary data with a binary key
+pub fn encrypt_bytes(plaintext: &[u8], key: &[u8; 24], iv: &[u8; 16]) -> Vec<u8> {
+    let cipher = Aes192CbcEnc::new(key.into(), iv.into());
+    let mut buf = plaintext.to_vec();
+    buf.resize(buf.len() + 16, 0u8);
+    let out = cipher.encrypt_padded_mut::<Pkcs7>(&mut buf, plaintext.len()).expect("encrypt failed");
+    out.to_vec()
+}
+
+// Decrypts binary data with a binary key
+pub fn decrypt_bytes(ciphertext: &[u8], key: &[u8; 24], iv: &[u8; 16]) -> Result<Vec<u8>, String> {
+    let cipher = Aes192CbcDec::new(key.into(), iv.into());
+    let mut buf = ciphertext.to_vec();
+    match cipher.decrypt_padded_mut::<Pkcs7>(&mut buf) {
+        Ok(bytes) => Ok(bytes.to_vec()),
+        Err(_) => Err("decrypt error".to_string()),
+    }
+}
@@ -70,26 +70,35 @@ pub fn decrypt_auto_iv(combined: &[u8], key_text: &str) -> Result<String, String
     decrypt(ciphertext, key_text, &iv_arr)
 }
 
-// Helper function that encrypts with binary key
-fn encrypt_with_key_bytes(plaintext: &str, key: &[u8; 32], iv: &[u8; 16]) -> Vec<u8> {
+// Encrypts binary data with a binary key
+pub fn encrypt_bytes(plaintext: &[u8], key: &[u8; 32], iv: &[u8; 16]) -> Vec<u8> {
     let cipher = Aes256CbcEnc::new(key.into(), iv.into());
-    let mut buf = plaintext.as_bytes().to_vec();
+    let mut buf = plaintext.to_vec();
     buf.resize(buf.len() + 16, 0u8);
-    let len = plaintext.as_bytes().len();
-    let out = cipher.encrypt_padded_mut::<Pkcs7>(&mut buf, len).expect("encrypt failed");
+    let out = cipher.encrypt_padded_mut::<Pkcs7>(&mut buf, plaintext.len()).expect("encrypt failed");
     out.to_vec()
 }
 
-// Helper function that decrypts with binary key
-fn decrypt_with_key_bytes(ciphertext: &[u8], key: &[u8; 32], iv: &[u8; 16]) -> Result<String, String> {
+// Decrypts binary data with a binary key
+pub fn decrypt_bytes(ciphertext: &[u8], key: &[u8; 32], iv: &[u8; 16]) -> Result<Vec<u8>, String> {
     let cipher = Aes256CbcDec::new(key.into(), iv.into());
     let mut buf = ciphertext.to_vec();
     match cipher.decrypt_padded_mut::<Pkcs7>(&mut buf) {
-        Ok(bytes) => String::from_utf8(bytes.to_vec()).map_err(|_| "not utf-8".to_string()),
+        Ok(bytes) => Ok(bytes.to_vec()),
         Err(_) => Err("decrypt error".to_string()),
     }
 }
 
+// Helper function that encrypts with binary key
+fn encrypt_with_key_bytes(plaintext: &str, key: &[u8; 32], iv: &[u8; 16]) -> Vec<u8> {
+    encrypt_bytes(plaintext.as_bytes(), key, iv)
+}
+
+// Helper function that decrypts with binary key
+fn decrypt_with_key_bytes(ciphertext: &[u8], key: &[u8; 32], iv: &[u8; 16]) -> Result<String, String> {
+    String::from_utf8(decrypt_bytes(ciphertext, key, iv)?).map_err(|_| "not utf-8".to_string())
+}
+
 // Full auto version: takes passphrase + salt, derives key, generates IV, and prepends both salt and IV
 pub fn encrypt_with_passphrase(plaintext: &str, passphrase: &str, salt: &[u8]) -> Vec<u8> {
     let key = super::pbkdf2_key::derive_aes256_key(passphrase, salt);
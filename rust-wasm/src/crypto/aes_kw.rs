@@ -0,0 +1,134 @@
+// AES Key Wrap (RFC 3394) and AES Key Wrap with Padding (RFC 5649)
+// The KEK length selects the AES variant: 16, 24 or 32 bytes
+use aes_kw::{Error, KekAes128, KekAes192, KekAes256};
+
+fn map_err(e: Error) -> String {
+    match e {
+        Error::IntegrityCheckFailed => "integrity check failed".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn kek_len_error(len: usize) -> String {
+    format!("invalid KEK length: {} bytes (expected 16, 24 or 32)", len)
+}
+
+// RFC 3394: key data must be a multiple of 8 bytes and at least 16 bytes
+pub fn wrap(kek: &[u8], key_data: &[u8]) -> Result<Vec<u8>, String> {
+    if key_data.len() < 16 || !key_data.len().is_multiple_of(8) {
+        return Err("key data must be at least 16 bytes and a multiple of 8 bytes".to_string());
+    }
+    match kek.len() {
+        16 => KekAes128::new(kek.into()).wrap_vec(key_data),
+        24 => KekAes192::new(kek.into()).wrap_vec(key_data),
+        32 => KekAes256::new(kek.into()).wrap_vec(key_data),
+        n => return Err(kek_len_error(n)),
+    }
+    .map_err(map_err)
+}
+
+pub fn unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    if wrapped.len() < 24 || !wrapped.len().is_multiple_of(8) {
+        return Err("wrapped key must be at least 24 bytes and a multiple of 8 bytes".to_string());
+    }
+    match kek.len() {
+        16 => KekAes128::new(kek.into()).unwrap_vec(wrapped),
+        24 => KekAes192::new(kek.into()).unwrap_vec(wrapped),
+        32 => KekAes256::new(kek.into()).unwrap_vec(wrapped),
+        n => return Err(kek_len_error(n)),
+    }
+    .map_err(map_err)
+}
+
+// RFC 5649: key data of any non-zero length
+pub fn wrap_with_padding(kek: &[u8], key_data: &[u8]) -> Result<Vec<u8>, String> {
+    if key_data.is_empty() {
+        return Err("key data is empty".to_string());
+    }
+    match kek.len() {
+        16 => KekAes128::new(kek.into()).wrap_with_padding_vec(key_data),
+        24 => KekAes192::new(kek.into()).wrap_with_padding_vec(key_data),
+        32 => KekAes256::new(kek.into()).wrap_with_padding_vec(key_data),
+        n => return Err(kek_len_error(n)),
+    }
+    .map_err(map_err)
+}
+
+pub fn unwrap_with_padding(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, String> {
+    if wrapped.len() < 16 || !wrapped.len().is_multiple_of(8) {
+        return Err("wrapped key must be at least 16 bytes and a multiple of 8 bytes".to_string());
+    }
+    match kek.len() {
+        16 => KekAes128::new(kek.into()).unwrap_with_padding_vec(wrapped),
+        24 => KekAes192::new(kek.into()).unwrap_with_padding_vec(wrapped),
+        32 => KekAes256::new(kek.into()).unwrap_with_padding_vec(wrapped),
+        n => return Err(kek_len_error(n)),
+    }
+    .map_err(map_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    #[test]
+    fn test_rfc3394_128_kek_128_key() {
+        let kek = h("000102030405060708090A0B0C0D0E0F");
+        let key = h("00112233445566778899AABBCCDDEEFF");
+        let wrapped = wrap(&kek, &key).unwrap();
+        assert_eq!(hex::encode(&wrapped), "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5");
+        assert_eq!(unwrap(&kek, &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn test_rfc3394_256_kek_256_key() {
+        let kek = h("000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F");
+        let key = h("00112233445566778899AABBCCDDEEFF000102030405060708090A0B0C0D0E0F");
+        let wrapped = wrap(&kek, &key).unwrap();
+        assert_eq!(
+            hex::encode(&wrapped),
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21"
+        );
+        assert_eq!(unwrap(&kek, &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn test_rfc5649_vectors() {
+        let kek = h("5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8");
+        let key = h("c37b7e6492584340bed12207808941155068f738");
+        let wrapped = wrap_with_padding(&kek, &key).unwrap();
+        assert_eq!(
+            hex::encode(&wrapped),
+            "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a"
+        );
+        assert_eq!(unwrap_with_padding(&kek, &wrapped).unwrap(), key);
+
+        let key = h("466f7250617369");
+        let wrapped = wrap_with_padding(&kek, &key).unwrap();
+        assert_eq!(hex::encode(&wrapped), "afbeb0f07dfbf5419200f2ccb50bb24f");
+        assert_eq!(unwrap_with_padding(&kek, &wrapped).unwrap(), key);
+    }
+
+    #[test]
+    fn test_integrity_check_failure() {
+        let kek = h("000102030405060708090A0B0C0D0E0F");
+        let mut wrapped = wrap(&kek, &h("00112233445566778899AABBCCDDEEFF")).unwrap();
+        wrapped[3] ^= 0x01;
+        assert_eq!(unwrap(&kek, &wrapped).unwrap_err(), "integrity check failed");
+
+        let wrong_kek = h("0F0E0D0C0B0A09080706050403020100");
+        let wrapped = wrap_with_padding(&kek, b"secret").unwrap();
+        assert_eq!(unwrap_with_padding(&wrong_kek, &wrapped).unwrap_err(), "integrity check failed");
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        assert!(wrap(&[0u8; 15], &[0u8; 16]).is_err());
+        assert!(wrap(&[0u8; 16], &[0u8; 12]).is_err());
+        assert!(unwrap(&[0u8; 16], &[0u8; 16]).is_err());
+    }
+}
@@ -0,0 +1,527 @@
+// age v1 file encryption (https://age-encryption.org/v1)
+//
+// Header:  "age-encryption.org/v1\n", one "-> " stanza per recipient wrapping the 16-byte
+//          file key, then "--- " + HMAC-SHA256 over the header (key = HKDF(file key, "header")).
+// Payload: [NONCE16] followed by 64 KiB ChaCha20-Poly1305 chunks (STREAM, nonce = [COUNTER88 BE][LAST1])
+//          under HKDF-SHA256(file key, salt = nonce, "payload").
+// Stanzas: "X25519" (ephemeral share, keys in Bech32 "age1..." / "AGE-SECRET-KEY-1...")
+//          and "scrypt" (passphrase, must be the only stanza).
+// Armor:   PEM-style "AGE ENCRYPTED FILE" block with 64-column Base64.
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine;
+use bech32::{FromBase32, ToBase32, Variant};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::ChaCha20Poly1305;
+use getrandom::getrandom;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const VERSION_LINE: &str = "age-encryption.org/v1";
+const X25519_LABEL: &[u8] = b"age-encryption.org/v1/X25519";
+const SCRYPT_LABEL: &[u8] = b"age-encryption.org/v1/scrypt";
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+const RECIPIENT_HRP: &str = "age";
+const IDENTITY_HRP: &str = "age-secret-key-";
+
+const FILE_KEY_BYTES: usize = 16;
+const PAYLOAD_NONCE_BYTES: usize = 16;
+const CHUNK_SIZE: usize = 64 * 1024;
+const TAG_BYTES: usize = 16;
+const COLUMNS: usize = 64;
+
+pub const DEFAULT_WORK_FACTOR: u8 = 18;
+// Upper bound accepted when decrypting, so a hostile file cannot demand unbounded memory
+pub const MAX_WORK_FACTOR: u8 = 22;
+
+struct Stanza {
+    tag: String,
+    args: Vec<String>,
+    body: Vec<u8>,
+}
+
+// A parsed file: `covered` is the MAC input (everything up to and including "---")
+struct ParsedFile<'a> {
+    stanzas: Vec<Stanza>,
+    covered: &'a [u8],
+    mac: Vec<u8>,
+    payload: &'a [u8],
+}
+
+// ---------------------------------------------------------------------------
+// Keys
+// ---------------------------------------------------------------------------
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    getrandom(&mut bytes).expect("random generation failed");
+    bytes
+}
+
+fn bech32_decode(text: &str, hrp: &str, what: &str) -> Result<[u8; 32], String> {
+    let (found_hrp, data, variant) = bech32::decode(text).map_err(|_| format!("invalid {}", what))?;
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|_| format!("invalid {}", what))?;
+    if found_hrp != hrp || variant != Variant::Bech32 || bytes.len() != 32 {
+        return Err(format!("invalid {}", what));
+    }
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+fn encode_recipient(public: &PublicKey) -> String {
+    bech32::encode(RECIPIENT_HRP, public.as_bytes().to_base32(), Variant::Bech32).expect("valid hrp")
+}
+
+fn parse_recipient(text: &str) -> Result<PublicKey, String> {
+    Ok(PublicKey::from(bech32_decode(text.trim(), RECIPIENT_HRP, "recipient")?))
+}
+
+fn parse_identity(text: &str) -> Result<StaticSecret, String> {
+    Ok(StaticSecret::from(bech32_decode(text.trim(), IDENTITY_HRP, "identity")?))
+}
+
+// Returns (identity "AGE-SECRET-KEY-1...", recipient "age1...")
+pub fn keygen() -> (String, String) {
+    let secret = StaticSecret::from(random_bytes::<32>());
+    let identity = bech32::encode(IDENTITY_HRP, secret.to_bytes().to_base32(), Variant::Bech32)
+        .expect("valid hrp")
+        .to_uppercase();
+    (identity, encode_recipient(&PublicKey::from(&secret)))
+}
+
+pub fn recipient_from_identity(identity: &str) -> Result<String, String> {
+    Ok(encode_recipient(&PublicKey::from(&parse_identity(identity)?)))
+}
+
+// ---------------------------------------------------------------------------
+// Stanzas
+// ---------------------------------------------------------------------------
+
+fn hkdf(ikm: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), ikm).expand(info, &mut out).expect("32 bytes is a valid length");
+    out
+}
+
+// File keys are wrapped with ChaCha20-Poly1305 under an all-zero nonce (each wrap key is used once)
+fn wrap_file_key(key: &[u8; 32], file_key: &[u8]) -> Vec<u8> {
+    ChaCha20Poly1305::new(key.into()).encrypt(&[0u8; 12].into(), file_key).expect("wrapping cannot fail")
+}
+
+fn unwrap_file_key(key: &[u8; 32], body: &[u8]) -> Option<[u8; FILE_KEY_BYTES]> {
+    if body.len() != FILE_KEY_BYTES + TAG_BYTES {
+        return None;
+    }
+    let file_key = ChaCha20Poly1305::new(key.into()).decrypt(&[0u8; 12].into(), body).ok()?;
+    let mut out = [0u8; FILE_KEY_BYTES];
+    out.copy_from_slice(&file_key);
+    Some(out)
+}
+
+fn x25519_stanza(recipient: &PublicKey, file_key: &[u8]) -> Result<Stanza, String> {
+    let ephemeral = StaticSecret::from(random_bytes::<32>());
+    let share = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(recipient);
+    if !shared.was_contributory() {
+        return Err("invalid recipient".to_string());
+    }
+    let mut salt = share.as_bytes().to_vec();
+    salt.extend_from_slice(recipient.as_bytes());
+    let key = hkdf(shared.as_bytes(), &salt, X25519_LABEL);
+    Ok(Stanza { tag: "X25519".to_string(), args: vec![STANDARD_NO_PAD.encode(share.as_bytes())], body: wrap_file_key(&key, file_key) })
+}
+
+fn x25519_unwrap(stanza: &Stanza, identity: &StaticSecret) -> Result<Option<[u8; FILE_KEY_BYTES]>, String> {
+    if stanza.args.len() != 1 {
+        return Err("invalid X25519 stanza".to_string());
+    }
+    let share = decode_b64(&stanza.args[0])?;
+    if share.len() != 32 {
+        return Err("invalid X25519 stanza".to_string());
+    }
+    let mut share_bytes = [0u8; 32];
+    share_bytes.copy_from_slice(&share);
+    let share = PublicKey::from(share_bytes);
+    let shared = identity.diffie_hellman(&share);
+    if !shared.was_contributory() {
+        return Err("invalid X25519 stanza".to_string());
+    }
+    let mut salt = share.as_bytes().to_vec();
+    salt.extend_from_slice(PublicKey::from(identity).as_bytes());
+    Ok(unwrap_file_key(&hkdf(shared.as_bytes(), &salt, X25519_LABEL), &stanza.body))
+}
+
+fn scrypt_key(passphrase: &[u8], salt: &[u8], work_factor: u8) -> Result<[u8; 32], String> {
+    let params = scrypt::Params::new(work_factor, 8, 1, 32).map_err(|_| "invalid work factor".to_string())?;
+    let mut full_salt = SCRYPT_LABEL.to_vec();
+    full_salt.extend_from_slice(salt);
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase, &full_salt, &params, &mut key).map_err(|_| "scrypt failed".to_string())?;
+    Ok(key)
+}
+
+fn scrypt_stanza(passphrase: &[u8], work_factor: u8, file_key: &[u8]) -> Result<Stanza, String> {
+    let salt = random_bytes::<16>();
+    let key = scrypt_key(passphrase, &salt, work_factor)?;
+    Ok(Stanza {
+        tag: "scrypt".to_string(),
+        args: vec![STANDARD_NO_PAD.encode(salt), work_factor.to_string()],
+        body: wrap_file_key(&key, file_key),
+    })
+}
+
+fn scrypt_unwrap(stanza: &Stanza, passphrase: &[u8], max_work_factor: u8) -> Result<[u8; FILE_KEY_BYTES], String> {
+    if stanza.args.len() != 2 {
+        return Err("invalid scrypt stanza".to_string());
+    }
+    let salt = decode_b64(&stanza.args[0])?;
+    let log_n = &stanza.args[1];
+    if salt.len() != 16 || log_n.is_empty() || log_n.starts_with('0') || !log_n.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("invalid scrypt stanza".to_string());
+    }
+    let work_factor: u8 = log_n.parse().map_err(|_| "invalid scrypt stanza".to_string())?;
+    if work_factor > max_work_factor {
+        return Err(format!("scrypt work factor {} exceeds the limit of {}", work_factor, max_work_factor));
+    }
+    let key = scrypt_key(passphrase, &salt, work_factor)?;
+    unwrap_file_key(&key, &stanza.body).ok_or_else(|| "incorrect passphrase".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// Header
+// ---------------------------------------------------------------------------
+
+fn decode_b64(text: &str) -> Result<Vec<u8>, String> {
+    STANDARD_NO_PAD.decode(text).map_err(|_| "invalid base64 in header".to_string())
+}
+
+fn header_mac(file_key: &[u8], header_without_mac: &[u8]) -> Vec<u8> {
+    let key = hkdf(file_key, &[], b"header");
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&key).expect("any key length");
+    mac.update(header_without_mac);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn write_header(stanzas: &[Stanza], file_key: &[u8]) -> Vec<u8> {
+    let mut header = format!("{}\n", VERSION_LINE);
+    for stanza in stanzas {
+        header.push_str("-> ");
+        header.push_str(&stanza.tag);
+        for arg in &stanza.args {
+            header.push(' ');
+            header.push_str(arg);
+        }
+        header.push('\n');
+        // The body always ends with a line shorter than 64 columns (possibly empty)
+        let body = STANDARD_NO_PAD.encode(&stanza.body);
+        let mut rest = body.as_str();
+        loop {
+            let (line, tail) = rest.split_at(rest.len().min(COLUMNS));
+            header.push_str(line);
+            header.push('\n');
+            rest = tail;
+            if line.len() < COLUMNS {
+                break;
+            }
+        }
+    }
+    header.push_str("---");
+    let mac = header_mac(file_key, header.as_bytes());
+    header.push(' ');
+    header.push_str(&STANDARD_NO_PAD.encode(mac));
+    header.push('\n');
+    header.into_bytes()
+}
+
+// Splits the data after the "--- <mac>" line
+fn parse_header(data: &[u8]) -> Result<ParsedFile<'_>, String> {
+    let invalid = || "invalid age header".to_string();
+    let mut pos = 0;
+    let next_line = |pos: &mut usize| -> Result<&str, String> {
+        let end = data[*pos..].iter().position(|&b| b == b'\n').ok_or_else(invalid)? + *pos;
+        let line = std::str::from_utf8(&data[*pos..end]).map_err(|_| invalid())?;
+        *pos = end + 1;
+        Ok(line)
+    };
+
+    if next_line(&mut pos)? != VERSION_LINE {
+        return Err("not an age v1 file".to_string());
+    }
+    let mut stanzas = Vec::new();
+    loop {
+        let line_start = pos;
+        let line = next_line(&mut pos)?;
+        if let Some(mac) = line.strip_prefix("--- ") {
+            let mac = decode_b64(mac)?;
+            if mac.len() != 32 {
+                return Err(invalid());
+            }
+            return Ok(ParsedFile { stanzas, covered: &data[..line_start + 3], mac, payload: &data[pos..] });
+        }
+        let mut words = line.strip_prefix("-> ").ok_or_else(invalid)?.split(' ');
+        let tag = words.next().filter(|t| !t.is_empty()).ok_or_else(invalid)?.to_string();
+        let args: Vec<String> = words.map(|w| w.to_string()).collect();
+        if args.iter().any(|a| a.is_empty()) {
+            return Err(invalid());
+        }
+        let mut body = String::new();
+        loop {
+            let line = next_line(&mut pos)?;
+            if line.len() > COLUMNS {
+                return Err(invalid());
+            }
+            body.push_str(line);
+            if line.len() < COLUMNS {
+                break;
+            }
+        }
+        stanzas.push(Stanza { tag, args, body: decode_b64(&body)? });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Payload
+// ---------------------------------------------------------------------------
+
+fn chunk_nonce(counter: u64, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&counter.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn encrypt_payload(file_key: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    let nonce = random_bytes::<PAYLOAD_NONCE_BYTES>();
+    let cipher = ChaCha20Poly1305::new(&hkdf(file_key, &nonce, b"payload").into());
+    let chunks: Vec<&[u8]> = if plaintext.is_empty() { vec![&[][..]] } else { plaintext.chunks(CHUNK_SIZE).collect() };
+
+    let mut out = Vec::with_capacity(PAYLOAD_NONCE_BYTES + plaintext.len() + chunks.len() * TAG_BYTES);
+    out.extend_from_slice(&nonce);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let nonce = chunk_nonce(i as u64, i + 1 == chunks.len());
+        out.extend_from_slice(&cipher.encrypt(&nonce.into(), *chunk).expect("chunk encryption cannot fail"));
+    }
+    out
+}
+
+fn decrypt_payload(file_key: &[u8], payload: &[u8]) -> Result<Vec<u8>, String> {
+    if payload.len() < PAYLOAD_NONCE_BYTES + TAG_BYTES {
+        return Err("payload truncated".to_string());
+    }
+    let (nonce, body) = payload.split_at(PAYLOAD_NONCE_BYTES);
+    let cipher = ChaCha20Poly1305::new(&hkdf(file_key, nonce, b"payload").into());
+    let chunks: Vec<&[u8]> = body.chunks(CHUNK_SIZE + TAG_BYTES).collect();
+
+    let mut out = Vec::with_capacity(body.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let last = i + 1 == chunks.len();
+        if chunk.len() < TAG_BYTES || (last && i > 0 && chunk.len() == TAG_BYTES) {
+            return Err("payload truncated".to_string());
+        }
+        let plaintext = cipher
+            .decrypt(&chunk_nonce(i as u64, last).into(), *chunk)
+            .map_err(|_| "payload authentication failed".to_string())?;
+        out.extend_from_slice(&plaintext);
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// Armor
+// ---------------------------------------------------------------------------
+
+pub fn armor(data: &[u8]) -> String {
+    let encoded = STANDARD.encode(data);
+    let mut out = format!("{}\n", ARMOR_BEGIN);
+    for line in encoded.as_bytes().chunks(COLUMNS) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        out.push('\n');
+    }
+    out.push_str(ARMOR_END);
+    out.push('\n');
+    out
+}
+
+fn dearmor(text: &str) -> Result<Vec<u8>, String> {
+    let inner = text
+        .trim()
+        .strip_prefix(ARMOR_BEGIN)
+        .and_then(|t| t.strip_suffix(ARMOR_END))
+        .ok_or_else(|| "invalid armor".to_string())?;
+    let compact: String = inner.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    STANDARD.decode(compact).map_err(|_| "invalid armor".to_string())
+}
+
+// Binary files are used as-is, armored ones are decoded first
+fn binary(data: &[u8]) -> Result<Vec<u8>, String> {
+    let start = data.iter().position(|b| !b.is_ascii_whitespace()).unwrap_or(0);
+    if data[start..].starts_with(ARMOR_BEGIN.as_bytes()) {
+        let text = std::str::from_utf8(data).map_err(|_| "invalid armor".to_string())?;
+        dearmor(text)
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Encrypt / decrypt
+// ---------------------------------------------------------------------------
+
+fn seal(stanzas: &[Stanza], file_key: &[u8], plaintext: &[u8], armored: bool) -> Vec<u8> {
+    let mut out = write_header(stanzas, file_key);
+    out.extend_from_slice(&encrypt_payload(file_key, plaintext));
+    if armored { armor(&out).into_bytes() } else { out }
+}
+
+pub fn encrypt_to_recipients(plaintext: &[u8], recipients: &[&str], armored: bool) -> Result<Vec<u8>, String> {
+    if recipients.is_empty() {
+        return Err("no recipients".to_string());
+    }
+    let file_key = random_bytes::<FILE_KEY_BYTES>();
+    let stanzas = recipients
+        .iter()
+        .map(|r| x25519_stanza(&parse_recipient(r)?, &file_key))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(seal(&stanzas, &file_key, plaintext, armored))
+}
+
+pub fn encrypt_with_passphrase(plaintext: &[u8], passphrase: &str, work_factor: u8, armored: bool) -> Result<Vec<u8>, String> {
+    if passphrase.is_empty() {
+        return Err("passphrase must not be empty".to_string());
+    }
+    let file_key = random_bytes::<FILE_KEY_BYTES>();
+    let stanza = scrypt_stanza(passphrase.as_bytes(), work_factor, &file_key)?;
+    Ok(seal(&[stanza], &file_key, plaintext, armored))
+}
+
+// Verify the header MAC, then decrypt the payload
+fn open(file: &ParsedFile, file_key: [u8; FILE_KEY_BYTES]) -> Result<Vec<u8>, String> {
+    let expected = header_mac(&file_key, file.covered);
+    if expected.iter().zip(&file.mac).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+        return Err("header MAC mismatch".to_string());
+    }
+    decrypt_payload(&file_key, file.payload)
+}
+
+pub fn decrypt_with_identities(data: &[u8], identities: &[&str]) -> Result<Vec<u8>, String> {
+    let data = binary(data)?;
+    let file = parse_header(&data)?;
+    if file.stanzas.iter().any(|s| s.tag == "scrypt") {
+        return Err("file is passphrase-encrypted".to_string());
+    }
+    let identities = identities.iter().map(|i| parse_identity(i)).collect::<Result<Vec<_>, String>>()?;
+    for stanza in file.stanzas.iter().filter(|s| s.tag == "X25519") {
+        for identity in &identities {
+            if let Some(file_key) = x25519_unwrap(stanza, identity)? {
+                return open(&file, file_key);
+            }
+        }
+    }
+    Err("no identity matched any recipient".to_string())
+}
+
+pub fn decrypt_with_passphrase(data: &[u8], passphrase: &str, max_work_factor: u8) -> Result<Vec<u8>, String> {
+    let data = binary(data)?;
+    let file = parse_header(&data)?;
+    let stanza = match file.stanzas.as_slice() {
+        [stanza] if stanza.tag == "scrypt" => stanza,
+        _ if file.stanzas.iter().any(|s| s.tag == "scrypt") => return Err("scrypt stanza must be the only stanza".to_string()),
+        _ => return Err("file is not passphrase-encrypted".to_string()),
+    };
+    let file_key = scrypt_unwrap(stanza, passphrase.as_bytes(), max_work_factor)?;
+    open(&file, file_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Files produced by the rage `age` crate (0.11); grease stanzas included
+    const IDENTITY: &str = "AGE-SECRET-KEY-1TLATM5G8ZSDX7LR774QKGD56RYJ49E74VPVEWA4DWZFDR609VKWSN7AH2R";
+    const RECIPIENT: &str = "age138zf08m0etrq7kr60h9av9ye4gq5m25v52653eluxejl72s8gdtszt0cck";
+    const OTHER_IDENTITY: &str = "AGE-SECRET-KEY-16KJQ4DTD3FQR9RHG60L7EP2N3N9SN9WA43DRD799R9A924KQG63QN0JM90";
+    const X25519_FILE: &str = "6167652d656e6372797074696f6e2e6f72672f76310a2d3e2058323535313920653761556d6f55506177615557394d625a66562f64626137726333787770697577655253456b3856717a770a306176425341362b77434a673556645531356c777473544b64542b48347a58306f6c32314c6a557864764d0a2d3e205a2d677265617365202f5e49206c20472165350a4474796c7366564d66534e515255393555737a464f5130453547474b5a4f66417a37546867774e772f674f79465a524f455a79545969752b666b4750505155790a4149616e306b7772676e424d68756259727438792b394668637430574c49476f6741394b6c497a6b4d41346d624c2b313637424948770a2d2d2d20317a7144435a33794230567a7168734f42334c33396b677a6a71486d674b58333868674878443531777a550a75af8654479ef18be07234f82766c26d6238450e597a9fd733a93e474b830a0869b33b0525a3fc2fde5db81d2c43";
+    const MULTI_RECIPIENT_FILE: &str = "6167652d656e6372797074696f6e2e6f72672f76310a2d3e20583235353139204832332b724b75646c4645375937306b37782f703241486b627a7a66324c475045694a52443848615230450a6f4b314a755a45534b4f754338587a56586f4442344671764976367333444d4767306670653077624471300a2d3e205832353531392057763834656662576d646751762f54757a42694b6e33736172466a70786436426e68796a4d54722b3553730a36372b4450583769466c4c6a382b38773351356c79436149566c70414434496e494d762f473754336657410a2d3e2036386157375d2d6772656173652034713368203f3770670a50494d54716631553641336969306c47345a4a694b7263766458754f534d5433483531594f6c4c35374a4a62524248564241565151774f70494f307a697659790a6c6d69484c736d5255626b6143576c665876630a2d2d2d20354c336835697a476457346a596c43477350773274426e4844394f6268535343725651544f7a69413454300ad4f3730dc086ef385250bbdf506ceb1026ec86292daa168a27a43a6ce66812e69df666a4dea72d65a08ec5475910";
+    // Passphrase "correct horse", work factor 10
+    const SCRYPT_FILE: &str = "6167652d656e6372797074696f6e2e6f72672f76310a2d3e20736372797074207336436e4d4932584a4c4376377a5465344c674461412031300a51774c582b6e30795532396f783462476a476c6e3957476355752f664870743041376b776464412f7679380a2d2d2d206a457353454831302b396e336a615238642b67504a71794141576f6f6e657a784f374e75387135425446410ad224ed198eb97f6bc22bb200fa090c8cb0849f2d21c1a8c4b5b62bcdcc4103a66ef8bd7748ee500cfba4a5b997ecf2ad7c8acc0f";
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(recipient_from_identity(IDENTITY).unwrap(), RECIPIENT);
+        let (identity, recipient) = keygen();
+        assert!(identity.starts_with("AGE-SECRET-KEY-1"));
+        assert_eq!(recipient_from_identity(&identity).unwrap(), recipient);
+        assert!(recipient_from_identity(RECIPIENT).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_age_files() {
+        let x25519 = hex::decode(X25519_FILE).unwrap();
+        assert_eq!(decrypt_with_identities(&x25519, &[IDENTITY]).unwrap(), b"hello from age");
+        assert_eq!(decrypt_with_identities(&x25519, &[OTHER_IDENTITY]).unwrap_err(), "no identity matched any recipient");
+
+        let multi = hex::decode(MULTI_RECIPIENT_FILE).unwrap();
+        assert_eq!(decrypt_with_identities(&multi, &[IDENTITY]).unwrap(), b"two recipients");
+        assert_eq!(decrypt_with_identities(&multi, &[OTHER_IDENTITY]).unwrap(), b"two recipients");
+
+        let scrypt = hex::decode(SCRYPT_FILE).unwrap();
+        assert_eq!(decrypt_with_passphrase(&scrypt, "correct horse", MAX_WORK_FACTOR).unwrap(), b"passphrase protected");
+        assert_eq!(decrypt_with_passphrase(&scrypt, "wrong", MAX_WORK_FACTOR).unwrap_err(), "incorrect passphrase");
+        assert!(decrypt_with_passphrase(&scrypt, "correct horse", 9).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_armored_age_file() {
+        let armored = "-----BEGIN AGE ENCRYPTED FILE-----\n\
+                       YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IFgyNTUxOSBLK3hkOGZKbG52Ymg4d0xZ\n\
+                       UFJlSzRMWi91RXNhNjRzSkdRRXZEMmVzenlJCkw3dDBHNFllZFlvK1N6ajBGN2dS\n\
+                       Z3NKZDVGN2RvbTh5TmcwelVOLzdyQ0kKLT4ga3hOLWdyZWFzZSAyPCB1TkVVd05s\n\
+                       PApMMW1RZGt3VG5Yb3R4ejBueEk0dXN3V3AyTkNvRDZVVjU0aFBuc3ViYmdDL3pJ\n\
+                       MW5TRytQamFSbHFSMGFQYXUzCmxBMTBXNUh3NFVQNzdpRkdQSHY0YUlwazNzYVlS\n\
+                       dwotLS0gb3VZSUUvbVdFd1NjRUhsei9yaTVLUklnaXF6a3FMdHhuRGsxM1ZiZlo4\n\
+                       Zwq2dfhDIiy/vBwl5cPw4hzlBFUx7gWNyJ4CrkckQ1mIwN5NxwImdBb3p5O2rIeE\n\
+                       fag=\n\
+                       -----END AGE ENCRYPTED FILE-----\n";
+        assert_eq!(decrypt_with_identities(armored.as_bytes(), &[IDENTITY]).unwrap(), b"armored age file");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let (identity, recipient) = keygen();
+        for len in [0, 1, CHUNK_SIZE, CHUNK_SIZE + 1, 2 * CHUNK_SIZE] {
+            let plaintext: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let file = encrypt_to_recipients(&plaintext, &[RECIPIENT, &recipient], false).unwrap();
+            assert_eq!(decrypt_with_identities(&file, &[&identity]).unwrap(), plaintext, "{}", len);
+        }
+        let armored = encrypt_to_recipients(b"armor", &[RECIPIENT], true).unwrap();
+        assert!(armored.starts_with(ARMOR_BEGIN.as_bytes()));
+        assert_eq!(decrypt_with_identities(&armored, &[IDENTITY]).unwrap(), b"armor");
+
+        let file = encrypt_with_passphrase(b"secret", "pw", 10, true).unwrap();
+        assert_eq!(decrypt_with_passphrase(&file, "pw", MAX_WORK_FACTOR).unwrap(), b"secret");
+        assert_eq!(decrypt_with_identities(&file, &[IDENTITY]).unwrap_err(), "file is passphrase-encrypted");
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let mut file = hex::decode(X25519_FILE).unwrap();
+        let last = file.len() - 1;
+        file[last] ^= 1;
+        assert_eq!(decrypt_with_identities(&file, &[IDENTITY]).unwrap_err(), "payload authentication failed");
+
+        // Changing a grease stanza argument breaks the header MAC
+        let text = hex::decode(X25519_FILE).unwrap();
+        let pos = text.windows(6).position(|w| w == b"grease").unwrap();
+        let mut file = text.clone();
+        file[pos - 2] ^= 1;
+        assert_eq!(decrypt_with_identities(&file, &[IDENTITY]).unwrap_err(), "header MAC mismatch");
+
+        // Dropping the final chunk of a two-chunk file
+        let two_chunks = encrypt_to_recipients(&vec![0u8; CHUNK_SIZE + 5], &[RECIPIENT], false).unwrap();
+        let truncated = &two_chunks[..two_chunks.len() - 21];
+        assert!(decrypt_with_identities(truncated, &[IDENTITY]).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+// BIP-32 hierarchical deterministic secp256k1 keys: master key from a seed, private and
+// public child derivation along paths such as m/44'/60'/0'/0/0, and extended keys
+// (xprv / xpub, tprv / tpub, yprv / ypub, zprv / zpub) serialised with Base58Check.
+use k256::elliptic_curve::PrimeField;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{ProjectivePoint, PublicKey, Scalar, SecretKey};
+use serde_json::{Value, json};
+
+use super::bip39;
+use crate::encoding::base58;
+use crate::hash::{hmac, ripemd160};
+
+pub const HARDENED: u32 = 0x8000_0000;
+const SERIALIZED_BYTES: usize = 78;
+
+/// (private prefix, private version, public version); SLIP-132 y / z prefixes mark BIP-49 / BIP-84
+const VERSIONS: [(&str, u32, u32); 4] = [
+    ("xprv", 0x0488_ADE4, 0x0488_B21E),
+    ("tprv", 0x0435_8394, 0x0435_87CF),
+    ("yprv", 0x049D_7878, 0x049D_7CB2),
+    ("zprv", 0x04B2_430C, 0x04B2_4746),
+];
+
+#[derive(Clone)]
+pub enum KeyKind {
+    Private(SecretKey),
+    Public(PublicKey),
+}
+
+#[derive(Clone)]
+pub struct ExtendedKey {
+    /// Index into VERSIONS
+    network: usize,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub key: KeyKind,
+}
+
+impl ExtendedKey {
+    /// Master key: HMAC-SHA512("Bitcoin seed", seed); version: xprv | tprv | yprv | zprv
+    pub fn master(seed: &[u8], version: &str) -> Result<ExtendedKey, String> {
+        if !(16..=64).contains(&seed.len()) {
+            return Err("seed must be 16 to 64 bytes".to_string());
+        }
+        let network = VERSIONS
+            .iter()
+            .position(|(name, _, _)| *name == version)
+            .ok_or(format!("unknown extended key version: {}", version))?;
+        let i = hmac::mac("sha512", b"Bitcoin seed", seed)?;
+        let key = SecretKey::from_slice(&i[..32]).map_err(|_| "seed gives an invalid master key; use another seed".to_string())?;
+        Ok(ExtendedKey {
+            network,
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code: i[32..].try_into().expect("64-byte HMAC"),
+            key: KeyKind::Private(key),
+        })
+    }
+
+    /// Parse a Base58Check extended key
+    pub fn parse(text: &str) -> Result<ExtendedKey, String> {
+        let data = base58::check_decode(text)?;
+        if data.len() != SERIALIZED_BYTES {
+            return Err(format!("extended keys are {} bytes, got {}", SERIALIZED_BYTES, data.len()));
+        }
+        let version = u32::from_be_bytes(data[..4].try_into().expect("4 bytes"));
+        let (network, private) = VERSIONS
+            .iter()
+            .enumerate()
+            .find_map(|(n, &(_, private, public))| match version {
+                v if v == private => Some((n, true)),
+                v if v == public => Some((n, false)),
+                _ => None,
+            })
+            .ok_or(format!("unknown extended key version: {:08x}", version))?;
+        let depth = data[4];
+        let parent_fingerprint: [u8; 4] = data[5..9].try_into().expect("4 bytes");
+        let child_number = u32::from_be_bytes(data[9..13].try_into().expect("4 bytes"));
+        if depth == 0 && (parent_fingerprint != [0; 4] || child_number != 0) {
+            return Err("master key with a parent fingerprint or child number".to_string());
+        }
+        let key_data = &data[45..];
+        let key = if private {
+            if key_data[0] != 0 {
+                return Err("private key data must start with 0x00".to_string());
+            }
+            KeyKind::Private(SecretKey::from_slice(&key_data[1..]).map_err(|_| "invalid private key".to_string())?)
+        } else {
+            KeyKind::Public(PublicKey::from_sec1_bytes(key_data).map_err(|_| "invalid public key".to_string())?)
+        };
+        Ok(ExtendedKey { network, depth, parent_fingerprint, child_number, chain_code: data[13..45].try_into().expect("32 bytes"), key })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        match &self.key {
+            KeyKind::Private(k) => k.public_key(),
+            KeyKind::Public(k) => *k,
+        }
+    }
+
+    /// Compressed SEC1 public key
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        self.public_key().to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// First 4 bytes of HASH160 of the compressed public key
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let id = ripemd160::hash160(&self.public_key_bytes());
+        [id[0], id[1], id[2], id[3]]
+    }
+
+    pub fn neuter(&self) -> ExtendedKey {
+        ExtendedKey { key: KeyKind::Public(self.public_key()), ..self.clone() }
+    }
+
+    fn serialize(&self) -> String {
+        let (_, private_version, public_version) = VERSIONS[self.network];
+        let (version, key) = match &self.key {
+            KeyKind::Private(k) => (private_version, [&[0u8][..], &k.to_bytes()].concat()),
+            KeyKind::Public(_) => (public_version, self.public_key_bytes()),
+        };
+        let mut data = Vec::with_capacity(SERIALIZED_BYTES);
+        data.extend_from_slice(&version.to_be_bytes());
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&self.chain_code);
+        data.extend_from_slice(&key);
+        base58::check_encode(&data)
+    }
+
+    /// Extended private key, if this is one
+    pub fn xprv(&self) -> Option<String> {
+        matches!(self.key, KeyKind::Private(_)).then(|| self.serialize())
+    }
+
+    pub fn xpub(&self) -> String {
+        self.neuter().serialize()
+    }
+
+    /// CKDpriv / CKDpub; hardened indexes (>= 2^31) need a private key
+    pub fn child(&self, index: u32) -> Result<ExtendedKey, String> {
+        if self.depth == u8::MAX {
+            return Err("maximum derivation depth reached".to_string());
+        }
+        let mut data = match &self.key {
+            KeyKind::Private(k) if index >= HARDENED => [&[0u8][..], &k.to_bytes()].concat(),
+            KeyKind::Public(_) if index >= HARDENED => return Err("hardened derivation needs a private key".to_string()),
+            _ => self.public_key_bytes(),
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+        let i = hmac::mac("sha512", &self.chain_code, &data)?;
+        let il: [u8; 32] = i[..32].try_into().expect("64-byte HMAC");
+        let tweak: Option<Scalar> = Scalar::from_repr(il.into()).into();
+        // IL >= n or a zero / infinite child key: BIP-32 says to skip to the next index
+        let invalid = || format!("index {} gives an invalid key; use the next index", index);
+        let tweak = tweak.ok_or_else(invalid)?;
+        let key = match &self.key {
+            KeyKind::Private(k) => {
+                let child = tweak + k.to_nonzero_scalar().as_ref();
+                KeyKind::Private(SecretKey::from_bytes(&child.to_bytes()).map_err(|_| invalid())?)
+            }
+            KeyKind::Public(k) => {
+                let point = ProjectivePoint::GENERATOR * tweak + k.to_projective();
+                KeyKind::Public(PublicKey::from_affine(point.to_affine()).map_err(|_| invalid())?)
+            }
+        };
+        Ok(ExtendedKey {
+            network: self.network,
+            depth: self.depth + 1,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code: i[32..].try_into().expect("64-byte HMAC"),
+            key,
+        })
+    }
+
+    /// Derive along "m/44'/60'/0'/0/0" (from a master key) or a relative path such as "0/5";
+    /// hardened steps are written with ' or h
+    pub fn derive(&self, path: &str) -> Result<ExtendedKey, String> {
+        let path = path.trim();
+        let steps = match path.strip_prefix('m') {
+            Some(_) if self.depth != 0 => {
+                return Err(format!("absolute path {} needs a master key, not a depth {} key", path, self.depth));
+            }
+            Some(rest) => rest.trim_start_matches('/'),
+            None => path,
+        };
+        let mut key = self.clone();
+        for step in steps.split('/').filter(|s| !s.is_empty()) {
+            key = key.child(parse_index(step)?)?;
+        }
+        Ok(key)
+    }
+}
+
+fn parse_index(step: &str) -> Result<u32, String> {
+    let (number, hardened) = match step.strip_suffix(['\'', 'h', 'H']) {
+        Some(number) => (number, true),
+        None => (step, false),
+    };
+    let index: u32 = number.parse().map_err(|_| format!("invalid path step: {}", step))?;
+    if index >= HARDENED {
+        return Err(format!("path index out of range: {}", step));
+    }
+    Ok(if hardened { index | HARDENED } else { index })
+}
+
+fn index_text(index: u32) -> String {
+    if index >= HARDENED { format!("{}'", index - HARDENED) } else { index.to_string() }
+}
+
+/// Details of an extended key as JSON
+pub fn describe(key: &ExtendedKey) -> Value {
+    let private_key = match &key.key {
+        KeyKind::Private(k) => Some(hex::encode(k.to_bytes())),
+        KeyKind::Public(_) => None,
+    };
+    json!({
+        "depth": key.depth,
+        "child_number": index_text(key.child_number),
+        "fingerprint": hex::encode(key.fingerprint()),
+        "parent_fingerprint": hex::encode(key.parent_fingerprint),
+        "chain_code": hex::encode(key.chain_code),
+        "xprv": key.xprv(),
+        "xpub": key.xpub(),
+        "private_key": private_key,
+        "public_key": hex::encode(key.public_key_bytes()),
+    })
+}
+
+/// Derive `path` from an extended key (xprv / xpub / ...); returns JSON details
+pub fn derive(extended_key: &str, path: &str) -> Result<String, String> {
+    let key = ExtendedKey::parse(extended_key)?.derive(path)?;
+    let mut info = describe(&key);
+    info["path"] = json!(path.trim());
+    Ok(info.to_string())
+}
+
+/// Master key from a BIP-39 mnemonic and passphrase, then `path`; version: xprv | tprv | yprv | zprv
+pub fn derive_from_mnemonic(mnemonic: &str, passphrase: &str, version: &str, path: &str) -> Result<String, String> {
+    let master = ExtendedKey::master(&bip39::to_seed(mnemonic, passphrase)?, version)?;
+    let key = master.derive(path)?;
+    let mut info = describe(&key);
+    info["path"] = json!(path.trim());
+    info["master_fingerprint"] = json!(hex::encode(master.fingerprint()));
+    Ok(info.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP-32 test vector 1
+    #[test]
+    fn test_bip32_vector() {
+        let master = ExtendedKey::master(&hex::decode("000102030405060708090a0b0c0d0e0f").unwrap(), "xprv").unwrap();
+        let xprv = master.xprv().unwrap();
+        assert_eq!(xprv, "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi");
+        assert_eq!(master.xpub(), "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8");
+        let info: Value = serde_json::from_str(&derive(&xprv, "m/0'/1/2h/2/1000000000").unwrap()).unwrap();
+        assert_eq!(info["xprv"], "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76");
+        assert_eq!(info["xpub"], "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy");
+        assert_eq!(info["child_number"], "1000000000");
+    }
+
+    #[test]
+    fn test_bip44_and_public_derivation() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let info: Value = serde_json::from_str(&derive_from_mnemonic(mnemonic, "", "xprv", "m/44'/60'/0'/0/0").unwrap()).unwrap();
+        assert_eq!(info["private_key"], "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727");
+        assert_eq!(info["public_key"], "0237b0bb7a8288d38ed49a524b5dc98cff3eb5ca824c9f9dc0dfdb3d9cd600f299");
+        // BIP-84 account key
+        let info: Value = serde_json::from_str(&derive_from_mnemonic(mnemonic, "", "zprv", "m/84'/0'/0'").unwrap()).unwrap();
+        assert_eq!(info["xpub"], "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs");
+
+        // Non-hardened steps from the account xpub match private derivation
+        let account = "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj";
+        let info: Value = serde_json::from_str(&derive(account, "0/1").unwrap()).unwrap();
+        assert_eq!(info["xpub"], "xpub6Fbrwk4KhC8qpW547rQ6k2d2YBu672sBMtGV1q5duGH7pktZou5ZyuufVAC4rtyM5csX6hCkdPJe2SVZUQ2hAtMNcx3iS7qcnFdGJxmtDNn");
+        assert!(info["xprv"].is_null());
+        assert!(derive(account, "0'").unwrap_err().contains("private key"));
+        assert!(derive(account, "m/0").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+// BIP-39 mnemonics (English wordlist): entropy <-> words with the SHA-256 checksum,
+// and the 64-byte seed (PBKDF2-HMAC-SHA512, 2048 iterations, salt "mnemonic" + passphrase).
+use getrandom::getrandom;
+use serde_json::json;
+
+use super::pbkdf2_key;
+use crate::hash::sha256;
+
+const WORDS: &str = include_str!("wordlists/bip39_english.txt");
+const SEED_ITERATIONS: u32 = 2048;
+const SEED_BYTES: usize = 64;
+
+fn wordlist() -> Vec<&'static str> {
+    WORDS.lines().collect()
+}
+
+/// Words for 16-32 bytes of entropy (a multiple of 4): 11 bits per word, the last word
+/// completed with the first entropy_bits / 32 bits of SHA-256(entropy)
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, String> {
+    if !(16..=32).contains(&entropy.len()) || !entropy.len().is_multiple_of(4) {
+        return Err("entropy must be 16, 20, 24, 28 or 32 bytes".to_string());
+    }
+    let checksum_bits = entropy.len() / 4;
+    let hash = sha256::digest(entropy);
+    let bits: Vec<bool> = entropy
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .chain((0..checksum_bits).map(|i| (hash[i / 8] >> (7 - i % 8)) & 1 == 1))
+        .collect();
+    let list = wordlist();
+    let words: Vec<&str> = bits.chunks(11).map(|chunk| list[chunk.iter().fold(0usize, |acc, &b| (acc << 1) | b as usize)]).collect();
+    Ok(words.join(" "))
+}
+
+/// Random mnemonic of 12, 15, 18, 21 or 24 words
+pub fn generate(words: usize) -> Result<String, String> {
+    if !(12..=24).contains(&words) || !words.is_multiple_of(3) {
+        return Err("word count must be 12, 15, 18, 21 or 24".to_string());
+    }
+    let mut entropy = vec![0u8; words * 4 / 3];
+    getrandom(&mut entropy).map_err(|e| e.to_string())?;
+    entropy_to_mnemonic(&entropy)
+}
+
+/// Check the words and checksum of a mnemonic; returns its entropy
+pub fn mnemonic_to_entropy(mnemonic: &str) -> Result<Vec<u8>, String> {
+    let list = wordlist();
+    let words: Vec<String> = mnemonic.split_whitespace().map(str::to_lowercase).collect();
+    if !(12..=24).contains(&words.len()) || !words.len().is_multiple_of(3) {
+        return Err(format!("a mnemonic has 12, 15, 18, 21 or 24 words, not {}", words.len()));
+    }
+    let mut bits: Vec<bool> = Vec::with_capacity(words.len() * 11);
+    for (n, word) in words.iter().enumerate() {
+        let index = list.binary_search(&word.as_str()).map_err(|_| format!("word {} is not in the BIP-39 wordlist: {}", n + 1, word))?;
+        bits.extend((0..11).rev().map(|i| (index >> i) & 1 == 1));
+    }
+    let checksum_bits = bits.len() / 33;
+    let (data, checksum) = bits.split_at(bits.len() - checksum_bits);
+    let entropy: Vec<u8> = data.chunks(8).map(|byte| byte.iter().fold(0u8, |acc, &b| (acc << 1) | b as u8)).collect();
+    let hash = sha256::digest(&entropy);
+    if checksum.iter().enumerate().any(|(i, &bit)| bit != ((hash[i / 8] >> (7 - i % 8)) & 1 == 1)) {
+        return Err("invalid mnemonic checksum".to_string());
+    }
+    Ok(entropy)
+}
+
+/// Validation result as JSON: valid, word count, entropy (hex) or the error
+pub fn inspect(mnemonic: &str) -> String {
+    let words = mnemonic.split_whitespace().count();
+    match mnemonic_to_entropy(mnemonic) {
+        Ok(entropy) => json!({ "valid": true, "words": words, "entropy": hex::encode(&entropy), "entropy_bits": entropy.len() * 8 }),
+        Err(e) => json!({ "valid": false, "words": words, "error": e }),
+    }
+    .to_string()
+}
+
+/// 64-byte seed of a valid mnemonic and optional passphrase
+pub fn to_seed(mnemonic: &str, passphrase: &str) -> Result<Vec<u8>, String> {
+    mnemonic_to_entropy(mnemonic)?;
+    // BIP-39 normalises both strings to NFKD, which only leaves ASCII unchanged
+    if !passphrase.is_ascii() {
+        return Err("only ASCII passphrases are supported (BIP-39 requires NFKD normalisation)".to_string());
+    }
+    let words: Vec<String> = mnemonic.split_whitespace().map(str::to_lowercase).collect();
+    let salt = format!("mnemonic{}", passphrase);
+    pbkdf2_key::derive("sha512", words.join(" ").as_bytes(), salt.as_bytes(), SEED_ITERATIONS, SEED_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trezor reference vectors (passphrase "TREZOR")
+    #[test]
+    fn test_vectors() {
+        let mnemonic = entropy_to_mnemonic(&[0u8; 16]).unwrap();
+        assert_eq!(mnemonic, "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about");
+        assert_eq!(
+            hex::encode(to_seed(&mnemonic, "TREZOR").unwrap()),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        let mnemonic = entropy_to_mnemonic(&[0xff; 32]).unwrap();
+        assert_eq!(mnemonic, "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote");
+        assert_eq!(mnemonic_to_entropy(&mnemonic.to_uppercase()).unwrap(), [0xff; 32]);
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(generate(24).unwrap().split(' ').count(), 24);
+        assert_eq!(mnemonic_to_entropy("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon").unwrap_err(), "invalid mnemonic checksum");
+        assert!(mnemonic_to_entropy("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abut").unwrap_err().contains("word 12"));
+        assert!(generate(13).is_err());
+        let info: serde_json::Value = serde_json::from_str(&inspect("legal winner thank year wave sausage worth useful legal winner thank yellow")).unwrap();
+        assert_eq!(info["entropy"], "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f");
+    }
+}
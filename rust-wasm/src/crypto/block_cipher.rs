@@ -0,0 +1,79 @@
+// Block cipher registry - maps cipher names to concrete cipher types
+//
+// 128-bit block: aes128, aes192, aes256, camellia128/192/256, aria128/192/256, sm4, twofish, serpent
+// 64-bit block:  des, tdes (16- or 24-byte key), blowfish, cast5, idea
+
+pub const CIPHERS: &[&str] = &[
+    "aes128", "aes192", "aes256",
+    "camellia128", "camellia192", "camellia256",
+    "aria128", "aria192", "aria256",
+    "sm4", "twofish", "serpent",
+    "des", "tdes", "blowfish", "cast5", "idea",
+];
+
+// Block size in bytes
+pub fn block_size(cipher: &str) -> Result<usize, String> {
+    match cipher {
+        "aes128" | "aes192" | "aes256" | "camellia128" | "camellia192" | "camellia256" | "aria128"
+        | "aria192" | "aria256" | "sm4" | "twofish" | "serpent" => Ok(16),
+        "des" | "tdes" | "blowfish" | "cast5" | "idea" => Ok(8),
+        _ => Err(format!("unknown cipher: {}", cipher)),
+    }
+}
+
+// Accepted key lengths in bytes, for error messages and UIs
+pub fn key_sizes(cipher: &str) -> Result<&'static str, String> {
+    match cipher {
+        "aes128" | "camellia128" | "aria128" | "sm4" | "idea" => Ok("16"),
+        "aes192" | "camellia192" | "aria192" => Ok("24"),
+        "aes256" | "camellia256" | "aria256" => Ok("32"),
+        "twofish" => Ok("16, 24 or 32"),
+        "serpent" => Ok("1 to 32"),
+        "des" => Ok("8"),
+        "tdes" => Ok("16 or 24"),
+        "blowfish" => Ok("4 to 56"),
+        "cast5" => Ok("5 to 16"),
+        _ => Err(format!("unknown cipher: {}", cipher)),
+    }
+}
+
+pub fn invalid_key_length(cipher: &str) -> String {
+    match key_sizes(cipher) {
+        Ok(sizes) => format!("invalid key length for {} (expected {} bytes)", cipher, sizes),
+        Err(e) => e,
+    }
+}
+
+// Expands to `$wide!(CipherType, args..)` for 128-bit block ciphers and
+// `$narrow!(CipherType, args..)` for 64-bit block ciphers, selected by name at runtime.
+// Both callbacks must evaluate to `Result<_, String>`.
+macro_rules! dispatch_block_cipher {
+    ($name:expr, $key:expr, $wide:ident, $narrow:ident, $($args:expr),* $(,)?) => {
+        match $name {
+            "aes128" => $wide!(aes::Aes128, $($args),*),
+            "aes192" => $wide!(aes::Aes192, $($args),*),
+            "aes256" => $wide!(aes::Aes256, $($args),*),
+            "camellia128" => $wide!(camellia::Camellia128, $($args),*),
+            "camellia192" => $wide!(camellia::Camellia192, $($args),*),
+            "camellia256" => $wide!(camellia::Camellia256, $($args),*),
+            "aria128" => $wide!(aria::Aria128, $($args),*),
+            "aria192" => $wide!(aria::Aria192, $($args),*),
+            "aria256" => $wide!(aria::Aria256, $($args),*),
+            "sm4" => $wide!(sm4::Sm4, $($args),*),
+            "twofish" => $wide!(twofish::Twofish, $($args),*),
+            "serpent" => $wide!(serpent::Serpent, $($args),*),
+            "des" => $narrow!(des::Des, $($args),*),
+            "tdes" => match $key.len() {
+                16 => $narrow!(des::TdesEde2, $($args),*),
+                24 => $narrow!(des::TdesEde3, $($args),*),
+                _ => Err($crate::crypto::block_cipher::invalid_key_length("tdes")),
+            },
+            "blowfish" => $narrow!(blowfish::Blowfish, $($args),*),
+            "cast5" => $narrow!(cast5::Cast5, $($args),*),
+            "idea" => $narrow!(idea::Idea, $($args),*),
+            other => Err(format!("unknown cipher: {}", other)),
+        }
+    };
+}
+
+pub(crate) use dispatch_block_cipher;
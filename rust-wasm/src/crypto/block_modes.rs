@@ -0,0 +1,441 @@
+// Generic block cipher modes and paddings for every cipher in `block_cipher`
+//
+// Modes:    ecb, cbc, ctr, cfb (full-block CFB), ofb
+// Paddings: pkcs7, iso7816 (0x80 00..), ansix923 (00.. len), iso10126 (random.. len), zero, none
+// CTR uses a full-block big-endian counter, matching `aes*_ctr` and `des_ctr`;
+// use `ctr_layout` for other counter layouts.
+use super::block_cipher::{self, dispatch_block_cipher};
+use super::ctr_layout;
+use cbc::cipher::{BlockCipher, BlockDecrypt, BlockEncrypt, KeyInit};
+use getrandom::getrandom;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Ecb,
+    Cbc,
+    Ctr,
+    Cfb,
+    Ofb,
+}
+
+impl Mode {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "ecb" => Ok(Mode::Ecb),
+            "cbc" => Ok(Mode::Cbc),
+            "ctr" => Ok(Mode::Ctr),
+            "cfb" => Ok(Mode::Cfb),
+            "ofb" => Ok(Mode::Ofb),
+            _ => Err(format!("unknown mode: {}", name)),
+        }
+    }
+
+    // Stream modes accept any plaintext length without padding
+    fn is_stream(self) -> bool {
+        matches!(self, Mode::Ctr | Mode::Cfb | Mode::Ofb)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    Pkcs7,
+    Iso7816,
+    AnsiX923,
+    Iso10126,
+    Zero,
+    None,
+}
+
+impl Padding {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "pkcs7" | "pkcs5" => Ok(Padding::Pkcs7),
+            "iso7816" => Ok(Padding::Iso7816),
+            "ansix923" => Ok(Padding::AnsiX923),
+            "iso10126" => Ok(Padding::Iso10126),
+            "zero" => Ok(Padding::Zero),
+            "none" => Ok(Padding::None),
+            _ => Err(format!("unknown padding: {}", name)),
+        }
+    }
+}
+
+pub fn pad(data: &[u8], block_size: usize, padding: Padding) -> Vec<u8> {
+    let mut out = data.to_vec();
+    let n = block_size - data.len() % block_size;
+    match padding {
+        Padding::Pkcs7 => out.resize(data.len() + n, n as u8),
+        Padding::Iso7816 => {
+            out.push(0x80);
+            out.resize(data.len() + n, 0);
+        }
+        Padding::AnsiX923 => {
+            out.resize(data.len() + n - 1, 0);
+            out.push(n as u8);
+        }
+        Padding::Iso10126 => {
+            let mut filler = vec![0u8; n - 1];
+            getrandom(&mut filler).expect("random padding failed");
+            out.extend_from_slice(&filler);
+            out.push(n as u8);
+        }
+        Padding::Zero => {
+            if !data.len().is_multiple_of(block_size) {
+                out.resize(data.len() + n, 0);
+            }
+        }
+        Padding::None => {}
+    }
+    out
+}
+
+pub fn unpad(data: &[u8], block_size: usize, padding: Padding) -> Result<Vec<u8>, String> {
+    let bad = || "invalid padding".to_string();
+    let len = data.len();
+    match padding {
+        Padding::Pkcs7 | Padding::AnsiX923 | Padding::Iso10126 => {
+            let n = *data.last().ok_or_else(bad)? as usize;
+            if n == 0 || n > block_size || n > len {
+                return Err(bad());
+            }
+            let filler = &data[len - n..len - 1];
+            let ok = match padding {
+                Padding::Pkcs7 => filler.iter().all(|&b| b as usize == n),
+                Padding::AnsiX923 => filler.iter().all(|&b| b == 0),
+                _ => true,
+            };
+            if !ok {
+                return Err(bad());
+            }
+            Ok(data[..len - n].to_vec())
+        }
+        Padding::Iso7816 => {
+            let tail = len.saturating_sub(block_size);
+            let pos = data[tail..].iter().rposition(|&b| b != 0).ok_or_else(bad)? + tail;
+            if data[pos] != 0x80 {
+                return Err(bad());
+            }
+            Ok(data[..pos].to_vec())
+        }
+        Padding::Zero => {
+            let end = data.iter().rposition(|&b| b != 0).map_or(0, |p| p + 1);
+            Ok(data[..end].to_vec())
+        }
+        Padding::None => Ok(data.to_vec()),
+    }
+}
+
+fn xor_in_place(dst: &mut [u8], src: &[u8]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+// Raw mode operations over an already padded buffer
+fn encrypt_blocks<C: BlockCipher + BlockEncrypt>(cipher: &C, mode: Mode, iv: &[u8], data: &mut [u8]) {
+    let bs = C::block_size();
+    match mode {
+        Mode::Ecb => {
+            for chunk in data.chunks_exact_mut(bs) {
+                cipher.encrypt_block(chunk.into());
+            }
+        }
+        Mode::Cbc => {
+            let mut prev = iv.to_vec();
+            for chunk in data.chunks_exact_mut(bs) {
+                xor_in_place(chunk, &prev);
+                cipher.encrypt_block(chunk.into());
+                prev.copy_from_slice(chunk);
+            }
+        }
+        Mode::Cfb => {
+            let mut register = iv.to_vec();
+            for chunk in data.chunks_mut(bs) {
+                cipher.encrypt_block(register.as_mut_slice().into());
+                xor_in_place(chunk, &register);
+                register[..chunk.len()].copy_from_slice(chunk);
+            }
+        }
+        Mode::Ofb => {
+            let mut register = iv.to_vec();
+            for chunk in data.chunks_mut(bs) {
+                cipher.encrypt_block(register.as_mut_slice().into());
+                xor_in_place(chunk, &register);
+            }
+        }
+        Mode::Ctr => unreachable!("CTR is handled by ctr_layout"),
+    }
+}
+
+fn decrypt_blocks<C: BlockCipher + BlockEncrypt + BlockDecrypt>(cipher: &C, mode: Mode, iv: &[u8], data: &mut [u8]) {
+    let bs = C::block_size();
+    match mode {
+        Mode::Ecb => {
+            for chunk in data.chunks_exact_mut(bs) {
+                cipher.decrypt_block(chunk.into());
+            }
+        }
+        Mode::Cbc => {
+            let mut prev = iv.to_vec();
+            let mut saved = vec![0u8; bs];
+            for chunk in data.chunks_exact_mut(bs) {
+                saved.copy_from_slice(chunk);
+                cipher.decrypt_block(chunk.into());
+                xor_in_place(chunk, &prev);
+                std::mem::swap(&mut prev, &mut saved);
+            }
+        }
+        Mode::Cfb => {
+            let mut register = iv.to_vec();
+            let mut next = vec![0u8; bs];
+            for chunk in data.chunks_mut(bs) {
+                next[..chunk.len()].copy_from_slice(chunk);
+                cipher.encrypt_block(register.as_mut_slice().into());
+                xor_in_place(chunk, &register);
+                register.copy_from_slice(&next);
+            }
+        }
+        Mode::Ofb => encrypt_blocks(cipher, mode, iv, data),
+        Mode::Ctr => unreachable!("CTR is handled by ctr_layout"),
+    }
+}
+
+macro_rules! run_encrypt {
+    ($cipher:ty, $name:expr, $key:expr, $mode:expr, $iv:expr, $data:expr) => {{
+        let cipher = <$cipher>::new_from_slice($key).map_err(|_| block_cipher::invalid_key_length($name))?;
+        encrypt_blocks(&cipher, $mode, $iv, $data);
+        Ok(())
+    }};
+}
+
+macro_rules! run_decrypt {
+    ($cipher:ty, $name:expr, $key:expr, $mode:expr, $iv:expr, $data:expr) => {{
+        let cipher = <$cipher>::new_from_slice($key).map_err(|_| block_cipher::invalid_key_length($name))?;
+        decrypt_blocks(&cipher, $mode, $iv, $data);
+        Ok(())
+    }};
+}
+
+fn check_iv(mode: Mode, iv: &[u8], bs: usize) -> Result<(), String> {
+    if mode != Mode::Ecb && iv.len() != bs {
+        return Err(format!("IV must be {} bytes", bs));
+    }
+    Ok(())
+}
+
+fn ctr_layout_for(bs: usize) -> &'static str {
+    if bs == 16 { "ctr128be" } else { "ctr64be" }
+}
+
+pub fn encrypt(cipher: &str, mode: &str, padding: &str, key: &[u8], iv: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let bs = block_cipher::block_size(cipher)?;
+    let mode = Mode::parse(mode)?;
+    let padding = Padding::parse(padding)?;
+    check_iv(mode, iv, bs)?;
+
+    let mut buf = pad(plaintext, bs, padding);
+    if !mode.is_stream() && !buf.len().is_multiple_of(bs) {
+        return Err(format!("data must be a multiple of {} bytes without padding", bs));
+    }
+    if mode == Mode::Ctr {
+        ctr_layout::apply_keystream(cipher, key, ctr_layout_for(bs), iv, 0, 0, &mut buf)?;
+        return Ok(buf);
+    }
+    let iv = if mode == Mode::Ecb { &[][..] } else { iv };
+    dispatch_block_cipher!(cipher, key, run_encrypt, run_encrypt, cipher, key, mode, iv, &mut buf)?;
+    Ok(buf)
+}
+
+pub fn decrypt(cipher: &str, mode: &str, padding: &str, key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    let bs = block_cipher::block_size(cipher)?;
+    let mode = Mode::parse(mode)?;
+    let padding = Padding::parse(padding)?;
+    check_iv(mode, iv, bs)?;
+
+    if !mode.is_stream() && !ciphertext.len().is_multiple_of(bs) {
+        return Err(format!("ciphertext must be a multiple of {} bytes", bs));
+    }
+    let mut buf = ciphertext.to_vec();
+    if mode == Mode::Ctr {
+        ctr_layout::apply_keystream(cipher, key, ctr_layout_for(bs), iv, 0, 0, &mut buf)?;
+    } else {
+        let iv = if mode == Mode::Ecb { &[][..] } else { iv };
+        dispatch_block_cipher!(cipher, key, run_decrypt, run_decrypt, cipher, key, mode, iv, &mut buf)?;
+    }
+    unpad(&buf, bs, padding)
+}
+
+pub fn random_iv(cipher: &str) -> Result<Vec<u8>, String> {
+    let mut iv = vec![0u8; block_cipher::block_size(cipher)?];
+    getrandom(&mut iv).expect("random iv failed");
+    Ok(iv)
+}
+
+// Auto-IV version: generates an IV and prepends it: [IV][CIPHERTEXT] (ECB has no IV)
+pub fn encrypt_auto_iv(cipher: &str, mode: &str, padding: &str, key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    if Mode::parse(mode)? == Mode::Ecb {
+        return encrypt(cipher, mode, padding, key, &[], plaintext);
+    }
+    let iv = random_iv(cipher)?;
+    let ciphertext = encrypt(cipher, mode, padding, key, &iv, plaintext)?;
+    let mut result = Vec::with_capacity(iv.len() + ciphertext.len());
+    result.extend_from_slice(&iv);
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+pub fn decrypt_auto_iv(cipher: &str, mode: &str, padding: &str, key: &[u8], combined: &[u8]) -> Result<Vec<u8>, String> {
+    if Mode::parse(mode)? == Mode::Ecb {
+        return decrypt(cipher, mode, padding, key, &[], combined);
+    }
+    let bs = block_cipher::block_size(cipher)?;
+    if combined.len() < bs {
+        return Err("ciphertext too short".to_string());
+    }
+    decrypt(cipher, mode, padding, key, &combined[..bs], &combined[bs..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    fn ecb_block(cipher: &str, key: &str, pt: &str) -> String {
+        let ct = encrypt(cipher, "ecb", "none", &h(key), &[], &h(pt)).unwrap();
+        assert_eq!(decrypt(cipher, "ecb", "none", &h(key), &[], &ct).unwrap(), h(pt));
+        hex::encode(ct)
+    }
+
+    // Published single-block test vectors
+    #[test]
+    fn test_camellia_rfc3713() {
+        let pt = "0123456789abcdeffedcba9876543210";
+        assert_eq!(ecb_block("camellia128", "0123456789abcdeffedcba9876543210", pt), "67673138549669730857065648eabe43");
+        assert_eq!(
+            ecb_block("camellia192", "0123456789abcdeffedcba98765432100011223344556677", pt),
+            "b4993401b3e996f84ee5cee7d79b09b9"
+        );
+        assert_eq!(
+            ecb_block("camellia256", "0123456789abcdeffedcba987654321000112233445566778899aabbccddeeff", pt),
+            "9acc237dff16d76c20ef7c919e3a7509"
+        );
+    }
+
+    #[test]
+    fn test_aria_rfc5794() {
+        let pt = "00112233445566778899aabbccddeeff";
+        assert_eq!(ecb_block("aria128", "000102030405060708090a0b0c0d0e0f", pt), "d718fbd6ab644c739da95f3be6451778");
+        assert_eq!(
+            ecb_block("aria192", "000102030405060708090a0b0c0d0e0f1011121314151617", pt),
+            "26449c1805dbe7aa25a468ce263a9e79"
+        );
+        assert_eq!(
+            ecb_block("aria256", "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", pt),
+            "f92bd7c79fb72e2f2b8f80c1972d24fc"
+        );
+    }
+
+    #[test]
+    fn test_sm4_gbt32907() {
+        let v = "0123456789abcdeffedcba9876543210";
+        assert_eq!(ecb_block("sm4", v, v), "681edf34d206965e86b3e94f536e4246");
+    }
+
+    #[test]
+    fn test_twofish_reference() {
+        let zero = "00000000000000000000000000000000";
+        assert_eq!(ecb_block("twofish", zero, zero), "9f589f5cf6122c32b6bfec2f2ae8c35a");
+    }
+
+    #[test]
+    fn test_serpent_nessie() {
+        let zero = "00000000000000000000000000000000";
+        assert_eq!(ecb_block("serpent", "80000000000000000000000000000000", zero), "264e5481eff42a4606abda06c0bfda3d");
+    }
+
+    #[test]
+    fn test_blowfish_schneier() {
+        assert_eq!(ecb_block("blowfish", "0000000000000000", "0000000000000000"), "4ef997456198dd78");
+        assert_eq!(ecb_block("blowfish", "ffffffffffffffff", "ffffffffffffffff"), "51866fd5b85ecb8a");
+    }
+
+    #[test]
+    fn test_cast5_rfc2144() {
+        let pt = "0123456789abcdef";
+        assert_eq!(ecb_block("cast5", "0123456712345678234567893456789a", pt), "238b4fe5847e44b2");
+        assert_eq!(ecb_block("cast5", "01234567123456782345", pt), "eb6a711a2c02271b");
+        assert_eq!(ecb_block("cast5", "0123456712", pt), "7ac816d16e9b302e");
+    }
+
+    #[test]
+    fn test_idea_reference() {
+        assert_eq!(ecb_block("idea", "00010002000300040005000600070008", "0000000100020003"), "11fbed2b01986de5");
+    }
+
+    // NIST SP 800-38A F.2.1 / F.3.13 / F.4.1 (AES-128)
+    #[test]
+    fn test_sp800_38a_modes() {
+        let key = h("2b7e151628aed2a6abf7158809cf4f3c");
+        let iv = h("000102030405060708090a0b0c0d0e0f");
+        let pt = h("6bc1bee22e409f96e93d7e117393172a");
+        let cbc = encrypt("aes128", "cbc", "none", &key, &iv, &pt).unwrap();
+        assert_eq!(hex::encode(cbc), "7649abac8119b246cee98e9b12e9197d");
+        let cfb = encrypt("aes128", "cfb", "none", &key, &iv, &pt).unwrap();
+        assert_eq!(hex::encode(&cfb), "3b3fd92eb72dad20333449f8e83cfb4a");
+        assert_eq!(decrypt("aes128", "cfb", "none", &key, &iv, &cfb).unwrap(), pt);
+        let ofb = encrypt("aes128", "ofb", "none", &key, &iv, &pt).unwrap();
+        assert_eq!(hex::encode(ofb), "3b3fd92eb72dad20333449f8e83cfb4a");
+    }
+
+    #[test]
+    fn test_every_cipher_mode_and_padding_roundtrips() {
+        let message = b"every cipher, every mode, every padding";
+        for &cipher in block_cipher::CIPHERS {
+            let key: Vec<u8> = match cipher {
+                "des" => vec![7u8; 8],
+                "aes192" | "camellia192" | "aria192" | "tdes" => vec![7u8; 24],
+                "aes256" | "camellia256" | "aria256" | "twofish" | "serpent" | "blowfish" => vec![7u8; 32],
+                _ => vec![7u8; 16],
+            };
+            for mode in ["ecb", "cbc", "ctr", "cfb", "ofb"] {
+                for padding in ["pkcs7", "iso7816", "ansix923", "iso10126", "zero"] {
+                    let combined = encrypt_auto_iv(cipher, mode, padding, &key, message).unwrap();
+                    let plain = decrypt_auto_iv(cipher, mode, padding, &key, &combined).unwrap();
+                    assert_eq!(plain, message, "{} {} {}", cipher, mode, padding);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_existing_cbc_module() {
+        let iv = [3u8; 16];
+        let ct = crate::crypto::aes256_cbc::encrypt("compat", "k", &iv);
+        let mut key = [0u8; 32];
+        key[0] = b'k';
+        assert_eq!(decrypt("aes256", "cbc", "pkcs7", &key, &iv, &ct).unwrap(), b"compat");
+    }
+
+    #[test]
+    fn test_paddings() {
+        assert_eq!(pad(b"abc", 8, Padding::Pkcs7), b"abc\x05\x05\x05\x05\x05");
+        assert_eq!(pad(b"abc", 8, Padding::Iso7816), b"abc\x80\x00\x00\x00\x00");
+        assert_eq!(pad(b"abc", 8, Padding::AnsiX923), b"abc\x00\x00\x00\x00\x05");
+        assert_eq!(pad(b"abcdefgh", 8, Padding::Zero), b"abcdefgh");
+        assert_eq!(pad(b"abcdefgh", 8, Padding::Pkcs7).len(), 16);
+        assert!(unpad(b"abc\x05\x05\x05\x04\x05", 8, Padding::Pkcs7).is_err());
+        assert!(unpad(b"abc\x00\x00\x00\x00\x00", 8, Padding::Iso7816).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(encrypt("aes128", "cbc", "pkcs7", &[0u8; 16], &[0u8; 8], b"x").is_err());
+        assert!(encrypt("aes128", "ecb", "none", &[0u8; 16], &[], b"short").is_err());
+        assert!(encrypt("cast5", "ecb", "pkcs7", &[0u8; 4], &[], b"x").is_err());
+        assert!(encrypt("rijndael", "ecb", "pkcs7", &[0u8; 16], &[], b"x").is_err());
+        assert!(encrypt("aes128", "xts", "pkcs7", &[0u8; 16], &[], b"x").is_err());
+    }
+}
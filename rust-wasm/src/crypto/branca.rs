@@ -0,0 +1,111 @@
+// Branca tokens (https://github.com/tuupola/branca-spec)
+//
+// Token: Base62 of [0xBA][TIMESTAMP32 BE][NONCE24][XCHACHA20-POLY1305 CIPHERTEXT + TAG16]
+// The 29-byte header is the associated data. Keys are 32 raw bytes.
+// Timestamps are seconds since the Unix epoch and are passed in by the caller (wasm has no clock).
+use crate::encoding::base62;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::XChaCha20Poly1305;
+use getrandom::getrandom;
+
+const VERSION: u8 = 0xBA;
+const HEADER_BYTES: usize = 1 + 4 + 24;
+const TAG_BYTES: usize = 16;
+
+pub fn generate_key() -> Vec<u8> {
+    let mut key = vec![0u8; 32];
+    getrandom(&mut key).expect("random key generation failed");
+    key
+}
+
+fn cipher(key: &[u8]) -> Result<XChaCha20Poly1305, String> {
+    if key.len() != 32 {
+        return Err("Branca key must be 32 bytes".to_string());
+    }
+    Ok(XChaCha20Poly1305::new(key.into()))
+}
+
+pub fn encode_with_nonce(key: &[u8], payload: &[u8], timestamp: u32, nonce: &[u8; 24]) -> Result<String, String> {
+    let mut token = Vec::with_capacity(HEADER_BYTES + payload.len() + TAG_BYTES);
+    token.push(VERSION);
+    token.extend_from_slice(&timestamp.to_be_bytes());
+    token.extend_from_slice(nonce);
+    let ciphertext = cipher(key)?
+        .encrypt(nonce.into(), Payload { msg: payload, aad: &token })
+        .map_err(|_| "encryption failed".to_string())?;
+    token.extend_from_slice(&ciphertext);
+    Ok(base62::encode(&token))
+}
+
+pub fn encode(key: &[u8], payload: &[u8], timestamp: u32) -> Result<String, String> {
+    let mut nonce = [0u8; 24];
+    getrandom(&mut nonce).expect("random nonce generation failed");
+    encode_with_nonce(key, payload, timestamp, &nonce)
+}
+
+fn decode_token(token: &str) -> Result<Vec<u8>, String> {
+    let bytes = base62::decode(token.trim()).map_err(|_| "invalid token".to_string())?;
+    if bytes.len() < HEADER_BYTES + TAG_BYTES || bytes[0] != VERSION {
+        return Err("invalid token".to_string());
+    }
+    Ok(bytes)
+}
+
+// Creation time of a token; it is not authenticated until the token is decoded with the key
+pub fn timestamp(token: &str) -> Result<u32, String> {
+    let bytes = decode_token(token)?;
+    Ok(u32::from_be_bytes(bytes[1..5].try_into().expect("4 bytes")))
+}
+
+// ttl: maximum token age in seconds, 0 to accept any age
+pub fn decode(key: &[u8], token: &str, ttl: u64, now: u64) -> Result<Vec<u8>, String> {
+    let cipher = cipher(key)?;
+    let bytes = decode_token(token)?;
+    let (header, ciphertext) = bytes.split_at(HEADER_BYTES);
+    let payload = cipher
+        .decrypt(header[5..].into(), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| "invalid token".to_string())?;
+
+    let issued = u32::from_be_bytes(header[1..5].try_into().expect("4 bytes")) as u64;
+    if ttl > 0 && issued.saturating_add(ttl) < now {
+        return Err("token expired".to_string());
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"supersecretkeyyoushouldnotcommit";
+    const NONCE: [u8; 24] = [0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef, 0xbe, 0xef];
+
+    // branca-spec test vectors
+    #[test]
+    fn test_spec_vectors() {
+        let cases = [
+            (0, "870S4BYxgHw0KnP3W9fgVUHEhT5g86vJ17etaC5Kh5uIraWHCI1psNQGv298ZmjPwoYbjDQ9chy2z"),
+            (123206400, "875GH23U0Dr6nHFA63DhOyd9LkYudBkX8RsCTOMz5xoYAMw9sMd5QwcEqLDRnTDHPenOX7nP2trlT"),
+        ];
+        for (ts, token) in cases {
+            assert_eq!(encode_with_nonce(KEY, b"Hello world!", ts, &NONCE).unwrap(), token);
+            assert_eq!(decode(KEY, token, 0, 0).unwrap(), b"Hello world!");
+            assert_eq!(timestamp(token).unwrap(), ts);
+        }
+    }
+
+    #[test]
+    fn test_ttl_and_tampering() {
+        let key = generate_key();
+        let token = encode(&key, b"session", 1000).unwrap();
+        assert_eq!(decode(&key, &token, 100, 1100).unwrap(), b"session");
+        assert_eq!(decode(&key, &token, 100, 1101).unwrap_err(), "token expired");
+        assert_eq!(decode(&key, &token, u64::MAX, u64::MAX).unwrap(), b"session");
+        assert_eq!(decode(&generate_key(), &token, 0, 1000).unwrap_err(), "invalid token");
+
+        let mut bytes = base62::decode(&token).unwrap();
+        bytes[2] ^= 1;
+        assert_eq!(decode(&key, &base62::encode(&bytes), 0, 1000).unwrap_err(), "invalid token");
+        assert!(decode(&key[..16], &token, 0, 1000).is_err());
+    }
+}
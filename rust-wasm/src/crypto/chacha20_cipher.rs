@@ -1,5 +1,5 @@
 // ChaCha20 stream cipher 
-use chacha20::ChaCha20;
+use chacha20::{ChaCha20, XChaCha20};
 use chacha20::cipher::{KeyIvInit, StreamCipher};
 use getrandom::getrandom;
 
@@ -9,6 +9,15 @@ pub fn random_nonce() -> [u8; 12] {
     nonce
 }
 
+/// XChaCha20 (24-byte nonce, counter from 0) applied in place
+pub fn xchacha20_apply(key: &[u8], nonce: &[u8], data: &mut [u8]) -> Result<(), String> {
+    if key.len() != 32 || nonce.len() != 24 {
+        return Err("XChaCha20 needs a 32-byte key and a 24-byte nonce".to_string());
+    }
+    XChaCha20::new(key.into(), nonce.into()).apply_keystream(data);
+    Ok(())
+}
+
 pub fn encrypt_auto_nonce(plaintext: &str, key_text: &str) -> Vec<u8> {
     let mut key = [0u8; 32];
     let key_bytes = key_text.as_bytes();
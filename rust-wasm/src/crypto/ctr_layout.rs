@@ -0,0 +1,272 @@
+// Configurable CTR mode - nonce/counter split, counter endianness, initial counter and seeking
+//
+// The counter block is built from the nonce and a counter field:
+//   big-endian layouts    [NONCE][COUNTER]   (e.g. ctr32be = 96-bit nonce + 32-bit counter, as in GCM)
+//   little-endian layouts [COUNTER][NONCE]   (e.g. ctr32le, as in AES-GCM-SIV)
+// `initial_counter` is added into the counter field. A nonce that already covers the whole block
+// (like the 16-byte IV of `aes*_ctr`) is also accepted.
+// Only the counter field is incremented; it wraps around inside its own width.
+use super::block_cipher::{self, dispatch_block_cipher};
+use ctr::cipher::{InnerIvInit, KeyInit, StreamCipher, StreamCipherCoreWrapper, StreamCipherSeek};
+use ctr::{flavors, CtrCore};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CounterLayout {
+    Ctr128BE,
+    Ctr128LE,
+    Ctr64BE,
+    Ctr64LE,
+    Ctr32BE,
+    Ctr32LE,
+}
+
+impl CounterLayout {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "ctr128be" => Ok(CounterLayout::Ctr128BE),
+            "ctr128le" => Ok(CounterLayout::Ctr128LE),
+            "ctr64be" => Ok(CounterLayout::Ctr64BE),
+            "ctr64le" => Ok(CounterLayout::Ctr64LE),
+            "ctr32be" => Ok(CounterLayout::Ctr32BE),
+            "ctr32le" => Ok(CounterLayout::Ctr32LE),
+            _ => Err(format!("unknown counter layout: {}", name)),
+        }
+    }
+
+    // Width of the counter field in bytes
+    pub fn counter_len(self) -> usize {
+        match self {
+            CounterLayout::Ctr128BE | CounterLayout::Ctr128LE => 16,
+            CounterLayout::Ctr64BE | CounterLayout::Ctr64LE => 8,
+            CounterLayout::Ctr32BE | CounterLayout::Ctr32LE => 4,
+        }
+    }
+
+    fn little_endian(self) -> bool {
+        matches!(self, CounterLayout::Ctr128LE | CounterLayout::Ctr64LE | CounterLayout::Ctr32LE)
+    }
+}
+
+// Build the initial counter block: the nonce (or a full block) with `initial_counter`
+// added into the counter field, wrapping inside the field's width
+pub fn initial_block(
+    layout: CounterLayout,
+    block_size: usize,
+    nonce: &[u8],
+    initial_counter: u64,
+) -> Result<Vec<u8>, String> {
+    let ctr_len = layout.counter_len();
+    if ctr_len > block_size {
+        return Err(format!("{}-bit counter does not fit a {}-byte block", ctr_len * 8, block_size));
+    }
+    let nonce_len = block_size - ctr_len;
+    let mut block = vec![0u8; block_size];
+    if nonce.len() == block_size {
+        block.copy_from_slice(nonce);
+    } else if nonce.len() == nonce_len {
+        if layout.little_endian() {
+            block[ctr_len..].copy_from_slice(nonce);
+        } else {
+            block[..nonce_len].copy_from_slice(nonce);
+        }
+    } else {
+        return Err(format!(
+            "nonce must be {} bytes (or a full {}-byte counter block)",
+            nonce_len, block_size
+        ));
+    }
+
+    let field = if layout.little_endian() {
+        &mut block[..ctr_len]
+    } else {
+        &mut block[nonce_len..]
+    };
+    let mut wide = [0u8; 16];
+    if layout.little_endian() {
+        wide[..ctr_len].copy_from_slice(field);
+        let value = u128::from_le_bytes(wide).wrapping_add(initial_counter as u128);
+        field.copy_from_slice(&value.to_le_bytes()[..ctr_len]);
+    } else {
+        wide[16 - ctr_len..].copy_from_slice(field);
+        let value = u128::from_be_bytes(wide).wrapping_add(initial_counter as u128);
+        field.copy_from_slice(&value.to_be_bytes()[16 - ctr_len..]);
+    }
+    Ok(block)
+}
+
+fn run<S: StreamCipher + StreamCipherSeek>(mut stream: S, position: u128, data: &mut [u8]) -> Result<(), String> {
+    stream
+        .try_seek(position)
+        .map_err(|_| "seek position is beyond the counter range".to_string())?;
+    stream
+        .try_apply_keystream(data)
+        .map_err(|_| "data is longer than the remaining counter range".to_string())
+}
+
+// Instantiate a CTR stream cipher for a concrete cipher type and counter flavor
+macro_rules! ctr_stream {
+    ($flavor:ident, $cipher:ty, $key:expr, $iv:expr, $position:expr, $data:expr) => {{
+        let cipher = <$cipher>::new_from_slice($key).map_err(|_| "invalid key length".to_string())?;
+        let core = CtrCore::<$cipher, flavors::$flavor>::inner_iv_slice_init(cipher, $iv)
+            .map_err(|_| "invalid nonce length".to_string())?;
+        run(StreamCipherCoreWrapper::from_core(core), $position, $data)
+    }};
+}
+
+// 64-bit block ciphers support the 64- and 32-bit counter layouts
+macro_rules! ctr_dispatch_64 {
+    ($cipher:ty, $layout:expr, $key:expr, $iv:expr, $position:expr, $data:expr) => {
+        match $layout {
+            CounterLayout::Ctr64BE => ctr_stream!(Ctr64BE, $cipher, $key, $iv, $position, $data),
+            CounterLayout::Ctr64LE => ctr_stream!(Ctr64LE, $cipher, $key, $iv, $position, $data),
+            CounterLayout::Ctr32BE => ctr_stream!(Ctr32BE, $cipher, $key, $iv, $position, $data),
+            CounterLayout::Ctr32LE => ctr_stream!(Ctr32LE, $cipher, $key, $iv, $position, $data),
+            CounterLayout::Ctr128BE | CounterLayout::Ctr128LE => {
+                Err("128-bit counters need a 16-byte block cipher".to_string())
+            }
+        }
+    };
+}
+
+// 128-bit block ciphers support every layout
+macro_rules! ctr_dispatch_128 {
+    ($cipher:ty, $layout:expr, $key:expr, $iv:expr, $position:expr, $data:expr) => {
+        match $layout {
+            CounterLayout::Ctr128BE => ctr_stream!(Ctr128BE, $cipher, $key, $iv, $position, $data),
+            CounterLayout::Ctr128LE => ctr_stream!(Ctr128LE, $cipher, $key, $iv, $position, $data),
+            other => ctr_dispatch_64!($cipher, other, $key, $iv, $position, $data),
+        }
+    };
+}
+
+// Encrypt or decrypt `data` in place (CTR is symmetric).
+// `initial_counter` is the value the counter field starts at; `offset` is a byte
+// position in the keystream from there, which gives random access into a ciphertext.
+pub fn apply_keystream(
+    cipher: &str,
+    key: &[u8],
+    layout: &str,
+    nonce: &[u8],
+    initial_counter: u64,
+    offset: u64,
+    data: &mut [u8],
+) -> Result<(), String> {
+    let layout = CounterLayout::parse(layout)?;
+    let bs = block_cipher::block_size(cipher)?;
+    let iv = initial_block(layout, bs, nonce, initial_counter)?;
+    let position = offset as u128;
+
+    dispatch_block_cipher!(cipher, key, ctr_dispatch_128, ctr_dispatch_64, layout, key, &iv, position, data)
+}
+
+pub fn process_bytes(
+    cipher: &str,
+    key: &[u8],
+    layout: &str,
+    nonce: &[u8],
+    initial_counter: u64,
+    offset: u64,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut buf = data.to_vec();
+    apply_keystream(cipher, key, layout, nonce, initial_counter, offset, &mut buf)?;
+    Ok(buf)
+}
+
+// Raw keystream bytes starting at the given position
+pub fn keystream(
+    cipher: &str,
+    key: &[u8],
+    layout: &str,
+    nonce: &[u8],
+    initial_counter: u64,
+    offset: u64,
+    len: usize,
+) -> Result<Vec<u8>, String> {
+    let mut buf = vec![0u8; len];
+    apply_keystream(cipher, key, layout, nonce, initial_counter, offset, &mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(s: &str) -> Vec<u8> {
+        hex::decode(s).unwrap()
+    }
+
+    // NIST SP 800-38A F.5.1 CTR-AES128.Encrypt
+    #[test]
+    fn test_sp800_38a_aes128_ctr() {
+        let key = h("2b7e151628aed2a6abf7158809cf4f3c");
+        let iv = h("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff");
+        let pt = h("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51");
+        let ct = process_bytes("aes128", &key, "ctr128be", &iv, 0, 0, &pt).unwrap();
+        assert_eq!(hex::encode(&ct), "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff");
+
+        // Random access into the second block
+        let tail = process_bytes("aes128", &key, "ctr128be", &iv, 1, 0, &pt[16..]).unwrap();
+        assert_eq!(tail, ct[16..]);
+        let tail = process_bytes("aes128", &key, "ctr128be", &iv, 0, 21, &pt[21..]).unwrap();
+        assert_eq!(tail, ct[21..]);
+    }
+
+    // GCM-style 96-bit nonce + 32-bit big-endian counter starting at 2 (after J0 and the tag block)
+    #[test]
+    fn test_gcm_style_layout() {
+        let key = [0x11u8; 16];
+        let nonce = [0x22u8; 12];
+        let mut full = nonce.to_vec();
+        full.extend_from_slice(&2u32.to_be_bytes());
+        let a = keystream("aes128", &key, "ctr32be", &nonce, 2, 0, 64).unwrap();
+        let b = keystream("aes128", &key, "ctr128be", &full, 0, 0, 64).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_32_bit_counter_wraps_inside_field() {
+        let key = [0x33u8; 16];
+        let nonce = [0x44u8; 12];
+        // Counter at 0xffffffff then wraps to 0 without touching the nonce
+        let ks = keystream("aes128", &key, "ctr32be", &nonce, 0xffff_ffff, 0, 32).unwrap();
+        let first = keystream("aes128", &key, "ctr32be", &nonce, 0, 0, 16).unwrap();
+        assert_eq!(&ks[16..], &first[..]);
+    }
+
+    #[test]
+    fn test_little_endian_layout_puts_counter_first() {
+        let key = [0x55u8; 16];
+        let nonce = [0x66u8; 12];
+        let mut full = 7u32.to_le_bytes().to_vec();
+        full.extend_from_slice(&nonce);
+        let a = keystream("aes128", &key, "ctr32le", &nonce, 7, 0, 16).unwrap();
+        let b = keystream("aes128", &key, "ctr32le", &full, 0, 0, 16).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_matches_existing_ctr_modules() {
+        let nonce = [9u8; 16];
+        let ct = crate::crypto::aes256_ctr::encrypt("layout check", "key", &nonce);
+        let mut key = [0u8; 32];
+        key[..3].copy_from_slice(b"key");
+        let pt = process_bytes("aes256", &key, "ctr128be", &nonce, 0, 0, &ct).unwrap();
+        assert_eq!(pt, b"layout check");
+
+        let nonce = [0xffu8; 8];
+        let ct = crate::crypto::des_ctr::encrypt("wraps the 64-bit counter", "deskey01", &nonce);
+        let pt = process_bytes("des", b"deskey01", "ctr64be", &nonce, 0, 0, &ct).unwrap();
+        assert_eq!(pt, b"wraps the 64-bit counter");
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(process_bytes("des", &[0u8; 8], "ctr128be", &[0u8; 8], 0, 0, b"x").is_err());
+        assert!(process_bytes("aes128", &[0u8; 16], "ctr32be", &[0u8; 8], 0, 0, b"x").is_err());
+        assert!(process_bytes("aes128", &[0u8; 15], "ctr32be", &[0u8; 12], 0, 0, b"x").is_err());
+        assert!(process_bytes("aes128", &[0u8; 16], "ctr16be", &[0u8; 12], 0, 0, b"x").is_err());
+        // Seeking past the end of a 32-bit counter
+        assert!(process_bytes("aes128", &[0u8; 16], "ctr32be", &[0u8; 12], 0, 1 << 40, b"x").is_err());
+    }
+}
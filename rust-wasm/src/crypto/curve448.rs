@@ -0,0 +1,143 @@
+// Curve448 (RFC 7748 / RFC 8032) on top of ed448-goldilocks: the X448 function and
+// Ed448 point / scalar helpers. Scalar multiplications are constant-time.
+use ed448_goldilocks::Scalar;
+use ed448_goldilocks::curve::edwards::{CompressedEdwardsY, ExtendedPoint};
+use ed448_goldilocks::curve::MontgomeryPoint;
+
+pub const POINT_LEN: usize = 57;
+
+/// Reduce a little-endian integer of at most 114 bytes modulo the base point order L
+pub fn scalar_mod_order(bytes: &[u8]) -> Scalar {
+    let mut wide = [0u8; 114];
+    wide[..bytes.len()].copy_from_slice(bytes);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+/// Canonical scalar (< L) from its 57-byte encoding
+pub fn scalar_from_canonical(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_canonical_bytes(bytes.try_into().ok()?)
+}
+
+/// [scalar]B for the Ed448 base point B
+pub fn mul_base(scalar: &Scalar) -> ExtendedPoint {
+    ExtendedPoint::generator().scalar_mul(scalar)
+}
+
+/// 57-byte encoding: little-endian y with the sign of x in the top bit
+pub fn encode(point: &ExtendedPoint) -> [u8; POINT_LEN] {
+    point.compress().0
+}
+
+/// Decode a point, rejecting non-canonical encodings (y >= p, stray bits, or -0 for x)
+pub fn decode(bytes: &[u8]) -> Option<ExtendedPoint> {
+    let bytes: [u8; POINT_LEN] = bytes.try_into().ok()?;
+    let point = CompressedEdwardsY(bytes).decompress()?;
+    (encode(&point) == bytes).then_some(point)
+}
+
+pub const X448_LEN: usize = 56;
+
+/// X448 function (RFC 7748 section 5): Montgomery ladder on u-coordinates.
+/// `u` = None uses the base point u = 5.
+pub fn x448(scalar: &[u8], u: Option<&[u8]>) -> Result<[u8; X448_LEN], String> {
+    if scalar.len() != X448_LEN || u.is_some_and(|u| u.len() != X448_LEN) {
+        return Err(format!("X448 keys must be {} bytes", X448_LEN));
+    }
+    let mut k: [u8; X448_LEN] = scalar.try_into().expect("56 bytes");
+    k[0] &= 252;
+    k[55] |= 128;
+    // The ladder walks all 448 bits, so the clamped value is used as is rather than reduced
+    let k = Scalar::from_bytes(k);
+    let point = match u {
+        Some(u) => MontgomeryPoint(u.try_into().expect("56 bytes")),
+        None => MontgomeryPoint::generator(),
+    };
+    Ok((&point * &k).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7748 section 5.2
+    #[test]
+    fn test_x448_rfc7748() {
+        let cases = [
+            (
+                "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+                "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+                "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f",
+            ),
+            (
+                "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+                "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db",
+                "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d",
+            ),
+        ];
+        for (k, u, expected) in cases {
+            let (k, u) = (hex::decode(k).unwrap(), hex::decode(u).unwrap());
+            assert_eq!(hex::encode(x448(&k, Some(&u)).unwrap()), expected);
+        }
+    }
+
+    // RFC 7748 section 5.2: k = u = 5, then k, u = X448(k, u), k
+    #[test]
+    fn test_x448_iterated() {
+        let mut k = [0u8; X448_LEN];
+        k[0] = 5;
+        let mut u = k;
+        for i in 1..=1000 {
+            let next = x448(&k, Some(&u)).unwrap();
+            u = k;
+            k = next;
+            if i == 1 {
+                assert_eq!(
+                    hex::encode(k),
+                    "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"
+                );
+            }
+        }
+        assert_eq!(
+            hex::encode(k),
+            "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"
+        );
+    }
+
+    // RFC 7748 section 6.2
+    #[test]
+    fn test_x448_diffie_hellman() {
+        let alice = hex::decode("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b").unwrap();
+        let bob = hex::decode("1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d").unwrap();
+        let alice_public = x448(&alice, None).unwrap();
+        let bob_public = x448(&bob, None).unwrap();
+        assert_eq!(
+            hex::encode(alice_public),
+            "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"
+        );
+        assert_eq!(
+            hex::encode(bob_public),
+            "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609"
+        );
+        let shared = "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d";
+        assert_eq!(hex::encode(x448(&alice, Some(&bob_public)).unwrap()), shared);
+        assert_eq!(hex::encode(x448(&bob, Some(&alice_public)).unwrap()), shared);
+    }
+
+    #[test]
+    fn test_point_encoding() {
+        let b = mul_base(&scalar_mod_order(&[2]));
+        let enc = encode(&b);
+        assert!(decode(&enc).unwrap() == b);
+        // Stray bits in the last byte and y >= p are rejected
+        let mut bad = enc;
+        bad[POINT_LEN - 1] |= 1;
+        assert!(decode(&bad).is_none());
+        // y = p is a valid point (y = 0) only when written canonically
+        let mut p = [0xffu8; POINT_LEN];
+        p[28] = 0xfe;
+        p[POINT_LEN - 1] = 0;
+        assert!(decode(&p).is_none());
+        assert!(decode(&[0u8; POINT_LEN]).is_some());
+        assert!(decode(&enc[1..]).is_none());
+    }
+}
@@ -0,0 +1,224 @@
+// Diffie-Hellman key agreement: X25519 / X448 (RFC 7748) and ECDH over
+// P-256, P-384, P-521 and secp256k1 (SEC 1), with optional HKDF post-processing.
+use getrandom::getrandom;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use x25519_dalek::{PublicKey as X25519Public, StaticSecret};
+
+use super::curve448;
+use super::kdf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    X25519,
+    X448,
+    P256,
+    P384,
+    P521,
+    Secp256k1,
+}
+
+impl Curve {
+    pub fn parse(name: &str) -> Result<Curve, String> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "x25519" => Ok(Curve::X25519),
+            "x448" => Ok(Curve::X448),
+            "p256" | "p-256" | "secp256r1" | "prime256v1" => Ok(Curve::P256),
+            "p384" | "p-384" | "secp384r1" => Ok(Curve::P384),
+            "p521" | "p-521" | "secp521r1" => Ok(Curve::P521),
+            "secp256k1" | "k256" => Ok(Curve::Secp256k1),
+            _ => Err(format!("unknown key agreement curve: {}", name)),
+        }
+    }
+
+    /// Length of a private key in bytes
+    pub fn private_len(self) -> usize {
+        match self {
+            Curve::X25519 | Curve::P256 | Curve::Secp256k1 => 32,
+            Curve::X448 => curve448::X448_LEN,
+            Curve::P384 => 48,
+            Curve::P521 => 66,
+        }
+    }
+}
+
+/// Runs `$body` with `$m` bound to the RustCrypto crate of a short-Weierstrass curve
+macro_rules! with_ec_curve {
+    ($curve:expr, $m:ident => $body:expr) => {
+        match $curve {
+            Curve::P256 => { use p256 as $m; $body }
+            Curve::P384 => { use p384 as $m; $body }
+            Curve::P521 => { use p521 as $m; $body }
+            Curve::Secp256k1 => { use k256 as $m; $body }
+            Curve::X25519 | Curve::X448 => unreachable!("not a Weierstrass curve"),
+        }
+    };
+}
+pub(crate) use with_ec_curve;
+
+fn check_private(curve: Curve, private: &[u8]) -> Result<(), String> {
+    if private.len() != curve.private_len() {
+        return Err(format!("private key must be {} bytes", curve.private_len()));
+    }
+    Ok(())
+}
+
+/// Random private key (a valid scalar for the EC curves)
+pub fn generate(curve: Curve) -> Vec<u8> {
+    let mut key = vec![0u8; curve.private_len()];
+    loop {
+        getrandom(&mut key).expect("random generation failed");
+        if curve == Curve::P521 {
+            key[0] &= 0x01;
+        }
+        if public_key(curve, &key, false).is_ok() {
+            return key;
+        }
+    }
+}
+
+/// Public key: 32/56 raw bytes for X25519/X448, SEC1 point (compressed or not) for EC curves
+pub fn public_key(curve: Curve, private: &[u8], compressed: bool) -> Result<Vec<u8>, String> {
+    check_private(curve, private)?;
+    match curve {
+        Curve::X25519 => {
+            let secret = StaticSecret::from(<[u8; 32]>::try_from(private).expect("32 bytes"));
+            Ok(X25519Public::from(&secret).as_bytes().to_vec())
+        }
+        Curve::X448 => Ok(curve448::x448(private, None)?.to_vec()),
+        _ => with_ec_curve!(curve, m => {
+            let secret = m::SecretKey::from_slice(private).map_err(|_| "invalid private key".to_string())?;
+            Ok(secret.public_key().to_encoded_point(compressed).as_bytes().to_vec())
+        }),
+    }
+}
+
+/// Raw shared secret (the x-coordinate for EC curves)
+pub fn shared_secret(curve: Curve, private: &[u8], peer_public: &[u8]) -> Result<Vec<u8>, String> {
+    check_private(curve, private)?;
+    match curve {
+        Curve::X25519 => {
+            let peer = <[u8; 32]>::try_from(peer_public).map_err(|_| "X25519 public key must be 32 bytes".to_string())?;
+            let secret = StaticSecret::from(<[u8; 32]>::try_from(private).expect("32 bytes"));
+            let shared = secret.diffie_hellman(&X25519Public::from(peer));
+            if !shared.was_contributory() {
+                return Err("low-order public key".to_string());
+            }
+            Ok(shared.as_bytes().to_vec())
+        }
+        Curve::X448 => {
+            let shared = curve448::x448(private, Some(peer_public))?;
+            if shared.iter().all(|&b| b == 0) {
+                return Err("low-order public key".to_string());
+            }
+            Ok(shared.to_vec())
+        }
+        _ => with_ec_curve!(curve, m => {
+            let secret = m::SecretKey::from_slice(private).map_err(|_| "invalid private key".to_string())?;
+            let peer = m::PublicKey::from_sec1_bytes(peer_public).map_err(|_| "invalid public key".to_string())?;
+            let shared = m::ecdh::diffie_hellman(secret.to_nonzero_scalar(), peer.as_affine());
+            Ok(shared.raw_secret_bytes().to_vec())
+        }),
+    }
+}
+
+/// Shared secret run through HKDF (hash: sha256 | sha384 | sha512)
+pub fn derive_key(
+    curve: Curve,
+    private: &[u8],
+    peer_public: &[u8],
+    hash: &str,
+    salt: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    let shared = shared_secret(curve, private, peer_public)?;
+    kdf::hkdf(hash, &shared, salt, info, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 7748 section 5.2 second X448 test vector
+    #[test]
+    fn test_x448_rfc7748() {
+        let k = hex::decode("203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f").unwrap();
+        let u = hex::decode("0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db").unwrap();
+        assert_eq!(
+            hex::encode(curve448::x448(&k, Some(&u)).unwrap()),
+            "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d"
+        );
+    }
+
+    // Vectors from Python cryptography: private 00..1f / 00..37, peer private all 0x07
+    #[test]
+    fn test_montgomery_curves() {
+        let private: Vec<u8> = (0u8..32).collect();
+        assert_eq!(
+            hex::encode(public_key(Curve::X25519, &private, false).unwrap()),
+            "8f40c5adb68f25624ae5b214ea767a6ec94d829d3d7b5e1ad1ba6f3e2138285f"
+        );
+        let peer = hex::decode("13be4feaeaf204c7fd3358fc9c00721881d174278128227ec674f37f7fe97b6d").unwrap();
+        assert_eq!(
+            hex::encode(shared_secret(Curve::X25519, &private, &peer).unwrap()),
+            "b1c049d203a104e54c881ad42a83e10ce317864e6be1769665845464b3b5576a"
+        );
+        assert_eq!(
+            hex::encode(derive_key(Curve::X25519, &private, &peer, "sha256", b"salt", b"hashlab", 32).unwrap()),
+            "2dc407e80da07935ce27302cca97c4caa92655020b0c13bfa1cbce83ca7b8dd8"
+        );
+        assert!(shared_secret(Curve::X25519, &private, &[0u8; 32]).is_err());
+
+        let private: Vec<u8> = (0u8..56).collect();
+        assert_eq!(
+            hex::encode(public_key(Curve::X448, &private, false).unwrap()),
+            "3c6fd1d02960e0d9e93308fc65736141c30db307977f81b7b10996e51e53f573e5c86621205ff491209d3b7cd7933428177ba4defae14dc1"
+        );
+        let peer = hex::decode("02e0e2f24768d9c5f3db3b49bb260404611ab670c112974cefb817c4c832ea5594e77752bd084eca35df7b4716421418713821c5fe3c955e").unwrap();
+        assert_eq!(
+            hex::encode(shared_secret(Curve::X448, &private, &peer).unwrap()),
+            "12c796837a11053a7f23e5169b00ea4c10ba2556a602b6950a71e73386062184cb9d81f82af90500f91d24889ade86a8a8f02e0cb00d3950"
+        );
+    }
+
+    // Vectors from Python cryptography: private 01..n, peer private 7
+    #[test]
+    fn test_weierstrass_curves() {
+        let cases = [
+            (Curve::P256, "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+             "02515c3d6eb9e396b904d3feca7f54fdcd0cc1e997bf375dca515ad0a6c3b4035f",
+             "048e533b6fa0bf7b4625bb30667c01fb607ef9f8b8a80fef5b300628703187b2a373eb1dbde03318366d069f83a6f5900053c73633cb041b21c55e1a86c1f400b4",
+             "30403f44c1c02ff9d7a9a22d8ddcbdf95576660e7448603acc97b142f7c4cc34"),
+            (Curve::P384, "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f30",
+             "03c76f2283dda95cd49b0ed9e733d2904474e37216f124e13d2c9ab4cf01021c49ad9cabb3d0b97499aef2f0ab313fa028",
+             "04283c1d7365ce4788f29f8ebf234edffead6fe997fbea5ffa2d58cc9dfa7b1c508b05526f55b9ebb2040f05b48fb6d0e19475c99061e41b88ba52efdb8c1690471a61d867ed799729d9c92cd01dbd225630d84ede32a78f9e64664cdac512ef8c",
+             "4f4c5f0b1f482eaf07a26b5e7452ecbaf1d2a5534afd7a91920a7f88b4331da22dfa5e9a168a125824078bafd870213a"),
+            (Curve::P521, "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f404142",
+             "02000366c8c3b22dfb87d0922163cd4b53cd43a24a29f79292fa4ef1288d69ed139a7fc0552120ea1bdb4f88ca0da4eb91de9b077018d5885dbff0e91a66639a9b72a5",
+             "040056d5d1d99d5b7f6346eeb65fda0b073a0c5f22e0e8f5483228f018d2c2f7114c5d8c308d0abfc698d8c9a6df30dce3bbc46f953f50fdc2619a01cead882816ecd4003d2d1b7d9baaa2a110d1d8317a39d68478b5c582d02824f0dd71dbd98a26cbde556bd0f293cdec9e2b9523a34591ce1a5f9e76712a5ddefc7b5c6b8bc90525251b",
+             "0081861880796e5960a69e4e822126b7ed1a000fae13bd713a04caf4b91b22b98d0f9370e09dded32bfc023109c5d2bc4390fc7db25a703c5751fc7a6335b70b5746"),
+            (Curve::Secp256k1, "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+             "0284bf7562262bbd6940085748f3be6afa52ae317155181ece31b66351ccffa4b0",
+             "045cbdf0646e5db4eaa398f365f2ea7a0e3d419b7e0330e39ce92bddedcac4f9bc6aebca40ba255960a3178d6d861a54dba813d0b813fde7b5a5082628087264da",
+             "ec745b04c07f0cbeb61f5fafc79c468702e07c046d143f7f92c21b6585b35600"),
+        ];
+        for (curve, private, public, peer, shared) in cases {
+            let private = hex::decode(private).unwrap();
+            assert_eq!(hex::encode(public_key(curve, &private, true).unwrap()), public, "{:?}", curve);
+            let peer = hex::decode(peer).unwrap();
+            assert_eq!(hex::encode(shared_secret(curve, &private, &peer).unwrap()), shared, "{:?}", curve);
+        }
+        assert!(shared_secret(Curve::P256, &[1u8; 32], &[4u8; 65]).is_err());
+    }
+
+    #[test]
+    fn test_agreement_roundtrip() {
+        for curve in [Curve::X25519, Curve::X448, Curve::P256, Curve::P521, Curve::Secp256k1] {
+            let a = generate(curve);
+            let b = generate(curve);
+            let pa = public_key(curve, &a, false).unwrap();
+            let pb = public_key(curve, &b, true).unwrap();
+            assert_eq!(shared_secret(curve, &a, &pb).unwrap(), shared_secret(curve, &b, &pa).unwrap());
+        }
+    }
+}
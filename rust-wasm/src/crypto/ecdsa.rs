@@ -0,0 +1,336 @@
+// ECDSA over P-256, P-384, P-521 and secp256k1 with RFC 6979 deterministic nonces,
+// DER / raw r||s signatures, low-S normalisation and secp256k1 public key recovery.
+use ecdsa::elliptic_curve::generic_array::typenum::Unsigned;
+use ecdsa::elliptic_curve::{CurveArithmetic, FieldBytes, FieldBytesEncoding, PrimeField, Scalar};
+use num_bigint::BigUint;
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use sha1::Sha1;
+use sha2::digest::core_api::BlockSizeUser;
+use sha2::digest::{Digest, FixedOutputReset};
+use sha2::{Sha256, Sha384, Sha512};
+use sha3::{Keccak256, Sha3_256};
+
+use super::ecdh::{self, Curve, with_ec_curve};
+use crate::hash::digest;
+
+fn check_curve(curve: Curve) -> Result<(), String> {
+    match curve {
+        Curve::X25519 | Curve::X448 => Err("curve does not support ECDSA".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Message digest; "none" means the message already is the digest
+fn prehash(message: &[u8], hash: &str) -> Result<Vec<u8>, String> {
+    if hash.eq_ignore_ascii_case("none") {
+        Ok(message.to_vec())
+    } else {
+        digest::digest(hash, message)
+    }
+}
+
+pub fn generate(curve: Curve) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    Ok(ecdh::generate(curve))
+}
+
+pub fn public_key(curve: Curve, private: &[u8], compressed: bool) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    ecdh::public_key(curve, private, compressed)
+}
+
+/// Deterministic signature as raw r||s (use `raw_to_der` for DER)
+pub fn sign(curve: Curve, private: &[u8], message: &[u8], hash: &str, low_s: bool) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    let z = prehash(message, hash)?;
+    macro_rules! sign_with {
+        ($c:ty) => {{
+            let (d, k, z) = rfc6979_inputs::<$c>(private, &z, hash)?;
+            let (sig, _) = ecdsa::hazmat::sign_prehashed::<$c, _>(&d, k, &z).map_err(|e| e.to_string())?;
+            // secp256k1 signatures are always low-S, as Bitcoin and Ethereum require
+            let sig = if low_s || curve == Curve::Secp256k1 { sig.normalize_s().unwrap_or(sig) } else { sig };
+            Ok(sig.to_bytes().to_vec())
+        }};
+    }
+    match curve {
+        Curve::P256 => sign_with!(p256::NistP256),
+        Curve::P384 => sign_with!(p384::NistP384),
+        Curve::P521 => sign_with!(p521::NistP521),
+        Curve::Secp256k1 => sign_with!(k256::Secp256k1),
+        Curve::X25519 | Curve::X448 => unreachable!("checked above"),
+    }
+}
+
+/// bits2int (RFC 6979 section 2.3.2): the leftmost `qlen` bits of `bytes` as an integer
+fn bits2int(bytes: &[u8], qlen: u64) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let len = bytes.len() as u64 * 8;
+    if len > qlen { value >> (len - qlen) } else { value }
+}
+
+/// Big-endian integer left-padded to `len` bytes
+fn int2octets(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    [vec![0u8; len - bytes.len()], bytes].concat()
+}
+
+/// RFC 6979 section 3.2 nonce: HMAC_DRBG over the message hash seeded with the key and
+/// bits2octets(H(m)), drawing field-sized candidates until `accept` takes one
+fn rfc6979_nonce<D, S>(private: &[u8], h1: &[u8], accept: impl Fn(&[u8]) -> Option<S>) -> S
+where
+    D: Digest + BlockSizeUser + FixedOutputReset,
+{
+    let mut drbg = rfc6979::HmacDrbg::<D>::new(private, h1, &[]);
+    let mut t = vec![0u8; private.len()];
+    loop {
+        drbg.fill_bytes(&mut t);
+        if let Some(k) = accept(&t) {
+            return k;
+        }
+    }
+}
+
+/// (private scalar d, nonce k, digest z) as taken by `ecdsa::hazmat::sign_prehashed`
+type SigningInputs<C> = (Scalar<C>, Scalar<C>, FieldBytes<C>);
+
+/// Private scalar, RFC 6979 nonce (HMAC over `hash`) and bits2int(digest) for signing. Digests
+/// shorter than the field are left-padded (P-521 with SHA-256 is fine), longer ones keep their
+/// leftmost bits. For "none" the HMAC hash follows the digest length.
+fn rfc6979_inputs<C: CurveArithmetic>(private: &[u8], z: &[u8], hash: &str) -> Result<SigningInputs<C>, String> {
+    let len = C::FieldBytesSize::USIZE;
+    if private.len() != len {
+        return Err(format!("private key must be {} bytes", len));
+    }
+    let order = BigUint::from_bytes_be(&C::ORDER.encode_field_bytes());
+    let qlen = order.bits();
+    let field_bytes = |value: &BigUint| {
+        let mut bytes = FieldBytes::<C>::default();
+        bytes.copy_from_slice(&int2octets(value, len));
+        bytes
+    };
+    let scalar = |value: &BigUint| -> Option<Scalar<C>> {
+        if *value == BigUint::ZERO || *value >= order {
+            return None;
+        }
+        Scalar::<C>::from_repr(field_bytes(value)).into()
+    };
+    let d = scalar(&BigUint::from_bytes_be(private)).ok_or("invalid private key")?;
+    let e = bits2int(z, qlen);
+    let h1 = int2octets(&(&e % &order), len);
+    let accept = |t: &[u8]| scalar(&bits2int(t, qlen));
+    let hmac_hash = match hash.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "none" => match z.len() {
+            20 => "sha1",
+            48 => "sha384",
+            64 => "sha512",
+            _ => "sha256",
+        }
+        .to_string(),
+        other => other.to_string(),
+    };
+    let k = match hmac_hash.as_str() {
+        "sha1" => rfc6979_nonce::<Sha1, _>(private, &h1, accept),
+        "sha256" => rfc6979_nonce::<Sha256, _>(private, &h1, accept),
+        "sha384" => rfc6979_nonce::<Sha384, _>(private, &h1, accept),
+        "sha512" => rfc6979_nonce::<Sha512, _>(private, &h1, accept),
+        "sha3256" => rfc6979_nonce::<Sha3_256, _>(private, &h1, accept),
+        "keccak256" => rfc6979_nonce::<Keccak256, _>(private, &h1, accept),
+        _ => return Err(format!("unknown hash algorithm: {}", hash)),
+    };
+    Ok((d, k, field_bytes(&e)))
+}
+
+/// Verify a raw r||s or DER signature against a SEC1 public key
+pub fn verify(curve: Curve, public: &[u8], message: &[u8], hash: &str, signature: &[u8]) -> Result<bool, String> {
+    check_curve(curve)?;
+    let mut z = prehash(message, hash)?;
+    // bits2int left-pads short digests; the curve crates reject those below half the field size
+    if z.len() < curve.private_len() {
+        z = [vec![0u8; curve.private_len() - z.len()], z].concat();
+    }
+    with_ec_curve!(curve, m => {
+        let key = m::ecdsa::VerifyingKey::from_sec1_bytes(public).map_err(|_| "invalid public key".to_string())?;
+        let sig = m::ecdsa::Signature::from_slice(signature).or_else(|_| m::ecdsa::Signature::from_der(signature));
+        Ok(sig.is_ok_and(|sig| key.verify_prehash(&z, &sig).is_ok()))
+    })
+}
+
+pub fn raw_to_der(curve: Curve, raw: &[u8]) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    with_ec_curve!(curve, m => {
+        let sig = m::ecdsa::Signature::from_slice(raw).map_err(|_| "invalid raw signature".to_string())?;
+        Ok(sig.to_der().as_bytes().to_vec())
+    })
+}
+
+pub fn der_to_raw(curve: Curve, der: &[u8]) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    with_ec_curve!(curve, m => {
+        let sig = m::ecdsa::Signature::from_der(der).map_err(|_| "invalid DER signature".to_string())?;
+        Ok(sig.to_bytes().to_vec())
+    })
+}
+
+/// Replace s with n - s when s > n/2 (raw r||s in and out)
+pub fn normalize_s(curve: Curve, raw: &[u8]) -> Result<Vec<u8>, String> {
+    check_curve(curve)?;
+    with_ec_curve!(curve, m => {
+        let sig = m::ecdsa::Signature::from_slice(raw).map_err(|_| "invalid raw signature".to_string())?;
+        Ok(sig.normalize_s().unwrap_or(sig).to_bytes().to_vec())
+    })
+}
+
+/// secp256k1 recoverable signature r||s||v (v = recovery id 0 or 1, always low-S), with the
+/// same RFC 6979 nonce as `sign`
+pub fn sign_recoverable(private: &[u8], message: &[u8], hash: &str) -> Result<Vec<u8>, String> {
+    let z = prehash(message, hash)?;
+    let (d, k, z) = rfc6979_inputs::<k256::Secp256k1>(private, &z, hash)?;
+    // The recovery id comes from R; negating s to make it low-S flips the parity of R's y
+    let (sig, recid) = ecdsa::hazmat::sign_prehashed::<k256::Secp256k1, _>(&d, k, &z).map_err(|e| e.to_string())?;
+    let (sig, recid) = match sig.normalize_s() {
+        Some(low) => (low, k256::ecdsa::RecoveryId::new(!recid.is_y_odd(), recid.is_x_reduced())),
+        None => (sig, recid),
+    };
+    let mut out = sig.to_bytes().to_vec();
+    out.push(recid.to_byte());
+    Ok(out)
+}
+
+/// Recover the secp256k1 public key from r||s||v; v may be 0/1 or Ethereum's 27/28
+pub fn recover_public_key(message: &[u8], hash: &str, signature: &[u8], compressed: bool) -> Result<Vec<u8>, String> {
+    if signature.len() != 65 {
+        return Err("recoverable signature must be 65 bytes".to_string());
+    }
+    let z = prehash(message, hash)?;
+    let v = match signature[64] {
+        27 | 28 => signature[64] - 27,
+        v => v,
+    };
+    let recid = k256::ecdsa::RecoveryId::from_byte(v).ok_or("invalid recovery id")?;
+    let sig = k256::ecdsa::Signature::from_slice(&signature[..64]).map_err(|_| "invalid signature".to_string())?;
+    let key = k256::ecdsa::VerifyingKey::recover_from_prehash(&z, &sig, recid)
+        .map_err(|_| "public key recovery failed".to_string())?;
+    Ok(key.to_encoded_point(compressed).as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6979 appendix A.2.5, P-256 with SHA-256, message "sample"
+    #[test]
+    fn test_rfc6979_p256() {
+        let private = hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+        let sig = sign(Curve::P256, &private, b"sample", "sha256", false).unwrap();
+        assert_eq!(
+            hex::encode(&sig),
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+        );
+    }
+
+    // RFC 6979 A.2.5 (P-256) and A.2.7 (P-521), message "sample" hashed with other than the
+    // curve's usual digest: the nonce HMAC must follow the selected hash
+    #[test]
+    fn test_rfc6979_cross_hash() {
+        let p256 = hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+        let p521 = hex::decode("00fad06daa62ba3b25d2fb40133da757205de67f5bb0018fee8c86e1b68c7e75caa896eb32f1f47c70855836a6d16fcc1466f6d8fbec67db89ec0c08b0e996b83538").unwrap();
+        let cases = [
+            (Curve::P256, &p256, "sha1",
+             "61340c88c3aaebeb4f6d667f672ca9759a6ccaa9fa8811313039ee4a35471d326d7f147dac089441bb2e2fe8f7a3fa264b9c475098fdcf6e00d7c996e1b8b7eb"),
+            (Curve::P256, &p256, "sha512",
+             "8496a60b5e9b47c825488827e0495b0e3fa109ec4568fd3f8d1097678eb97f002362ab1adbe2b8adf9cb9edab740ea6049c028114f2460f96554f61fae3302fe"),
+            (Curve::P521, &p521, "sha256",
+             "01511bb4d675114fe266fc4372b87682baecc01d3cc62cf2303c92b3526012659d16876e25c7c1e57648f23b73564d67f61c6f14d527d54972810421e7d87589e1a7004a171143a83163d6df460aaf61522695f207a58b95c0644d87e52aa1a347916e4f7a72930b1bc06dbe22ce3f58264afd23704cbb63b29b931f7de6c9d949a7ecfc"),
+            (Curve::P521, &p521, "sha384",
+             "01ea842a0e17d2de4f92c15315c63ddf72685c18195c2bb95e572b9c5136ca4b4b576ad712a52be9730627d16054ba40cc0b8d3ff035b12ae75168397f5d50c6745101f21a3cee066e1961025fb048bd5fe2b7924d0cd797babe0a83b66f1e35eeaf5fde143fa85dc394a7dee766523393784484bdf3e00114a1c857cde1aa203db65d61"),
+        ];
+        for (curve, private, hash, expected) in cases {
+            let sig = sign(curve, private, b"sample", hash, false).unwrap();
+            assert_eq!(hex::encode(&sig), expected, "{:?} {}", curve, hash);
+            assert!(verify(curve, &public_key(curve, private, false).unwrap(), b"sample", hash, &sig).unwrap());
+        }
+    }
+
+    // Deterministic signatures from Python cryptography, private key 01..n, message "hashlab"
+    #[test]
+    fn test_sign_verify_vectors() {
+        let cases = [
+            (Curve::P256, "sha256", 32,
+             "04515c3d6eb9e396b904d3feca7f54fdcd0cc1e997bf375dca515ad0a6c3b4035f4536be3a50f318fbf9a5475902a221502bef0d57e08c53b2cc0a56f17d9f9354",
+             "bb2c581fab4b042b18ced35469f4bb91a9f13de66fdeebfec885463269a6923cba0953179fc37011ed5636d87214cccf524b8796f8e5981124c3c42bec21b55a"),
+            (Curve::P384, "sha384", 48,
+             "04c76f2283dda95cd49b0ed9e733d2904474e37216f124e13d2c9ab4cf01021c49ad9cabb3d0b97499aef2f0ab313fa02826bc1f83451b5c8962a75caff73588d4400a6296436154fb343c393e91048a6c7bcbadc83cd8a5f26feae883156f92a1",
+             "6371d304c4954352e187160db916a638d651d3808ea21c0ab781b76013e6964a407fc658f717f39ae06e322c1b7b415f4a0918148882edcfbca2078c6d024d4b5b14437cad4c33392d0fd1b0b58ca9be653e838916b304b275b94f14376338db"),
+            (Curve::P521, "sha512", 66,
+             "04000366c8c3b22dfb87d0922163cd4b53cd43a24a29f79292fa4ef1288d69ed139a7fc0552120ea1bdb4f88ca0da4eb91de9b077018d5885dbff0e91a66639a9b72a500bd5e44e3a526e1051a4371c9bae5c7611ed489582ecdcc1ea277fe2379286a3a1c0c7224c7b1ebb0a8b6e5fbda5cead23f47c300917d4f98f2d2d4dc79d0109826",
+             "01ebfb37219f848ae194900f6606bf7b55cda5587fe8e350806bfc3dd814b8885b6c666451cc93dce574ee7a76fbc0a7547bed8deea269d6a71fd43c5c820593e5e000deffde5c916b09b82eff252b041b8ad08bda05ca6611fd1577a679565fdbd68c1d57c5aa9657c7dd1c328cd2e7b5c0751d06d08005f57c46b65183e615d4d05996"),
+        ];
+        for (curve, hash, n, public, expected) in cases {
+            let mut private: Vec<u8> = (1..=n as u8).collect();
+            if curve == Curve::P521 {
+                private[0] = 1;
+            }
+            let public = hex::decode(public).unwrap();
+            assert_eq!(public_key(curve, &private, false).unwrap(), public);
+            let sig = sign(curve, &private, b"hashlab", hash, false).unwrap();
+            assert_eq!(hex::encode(&sig), expected, "{:?}", curve);
+            assert!(verify(curve, &public, b"hashlab", hash, &sig).unwrap());
+            let der = raw_to_der(curve, &sig).unwrap();
+            assert!(verify(curve, &public, b"hashlab", hash, &der).unwrap());
+            assert_eq!(der_to_raw(curve, &der).unwrap(), sig);
+            assert!(!verify(curve, &public, b"hashlab!", hash, &sig).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_low_s() {
+        let private: Vec<u8> = (1u8..=32).collect();
+        let low = sign(Curve::P256, &private, b"hashlab", "sha256", true).unwrap();
+        assert_eq!(
+            hex::encode(&low[32..]),
+            "45f6ace7603c8fef12a9c9278deb33306a9b7316ae320673cef6069710416ff7"
+        );
+        let high = sign(Curve::P256, &private, b"hashlab", "sha256", false).unwrap();
+        assert_eq!(normalize_s(Curve::P256, &high).unwrap(), low);
+        // secp256k1 signing is always low-S
+        let sig = sign(Curve::Secp256k1, &private, b"hashlab", "sha256", false).unwrap();
+        assert_eq!(
+            hex::encode(&sig),
+            "8a2b19670e8ec05739ba77e02f4ffc6157e1c761475246c8d3d3de27df319b6143a2eb05102ea72e670ccb43f62e5065569f854d64450147d419f00e6dc6043e"
+        );
+        assert!(sign(Curve::X25519, &private, b"", "sha256", true).is_err());
+    }
+
+    // EIP-155 example transaction: signing hash and key 0x4646...46, v = 37 (chain id 1, recovery id 0)
+    #[test]
+    fn test_secp256k1_recovery() {
+        let private = [0x46u8; 32];
+        let hash = hex::decode("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53").unwrap();
+        let sig = sign_recoverable(&private, &hash, "none").unwrap();
+        assert_eq!(
+            hex::encode(&sig),
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa63627667cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d8300"
+        );
+        let public = public_key(Curve::Secp256k1, &private, false).unwrap();
+        assert_eq!(recover_public_key(&hash, "none", &sig, false).unwrap(), public);
+        let mut eth = sig.clone();
+        eth[64] = 27;
+        assert_eq!(recover_public_key(&hash, "none", &eth, false).unwrap(), public);
+        let recovered = recover_public_key(b"hashlab", "keccak256", &sign_recoverable(&private, b"hashlab", "keccak256").unwrap(), true).unwrap();
+        assert_eq!(recovered, public_key(Curve::Secp256k1, &private, true).unwrap());
+    }
+
+    // The nonce HMAC follows the selected hash, as in `sign`, and v stays correct after low-S
+    #[test]
+    fn test_recoverable_nonce_hash() {
+        let private = [0x46u8; 32];
+        let public = public_key(Curve::Secp256k1, &private, true).unwrap();
+        for hash in ["keccak256", "sha512", "sha1"] {
+            for message in [&b"hashlab"[..], b"sample", b"test"] {
+                let sig = sign_recoverable(&private, message, hash).unwrap();
+                assert_eq!(sig[..64], sign(Curve::Secp256k1, &private, message, hash, true).unwrap()[..], "{}", hash);
+                assert_eq!(recover_public_key(message, hash, &sig, true).unwrap(), public, "{}", hash);
+            }
+        }
+    }
+}
@@ -0,0 +1,487 @@
+// EdDSA signatures (RFC 8032): Ed25519, Ed25519ph, Ed25519ctx, Ed448 and Ed448ph,
+// with raw, PKCS#8 / SPKI PEM (RFC 8410) and JWK (RFC 8037) key formats.
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::{Scalar, clamp_integer};
+use ed448_goldilocks::Scalar as Scalar448;
+use getrandom::getrandom;
+use sha2::{Digest, Sha512};
+use sha3::Shake256;
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+
+use super::curve448;
+use crate::encoding::pem;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    Ed25519,
+    Ed448,
+}
+
+impl Curve {
+    pub fn parse(name: &str) -> Result<Curve, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "ed25519" => Ok(Curve::Ed25519),
+            "ed448" => Ok(Curve::Ed448),
+            _ => Err(format!("unknown EdDSA curve: {}", name)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Curve::Ed25519 => "Ed25519",
+            Curve::Ed448 => "Ed448",
+        }
+    }
+
+    /// Length of both the private seed and the public key
+    pub fn key_len(self) -> usize {
+        match self {
+            Curve::Ed25519 => 32,
+            Curve::Ed448 => 57,
+        }
+    }
+
+    fn oid_byte(self) -> u8 {
+        match self {
+            Curve::Ed25519 => 0x70,
+            Curve::Ed448 => 0x71,
+        }
+    }
+}
+
+/// Signature scheme variants; `context` is only meaningful for the ctx/ph/448 schemes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scheme {
+    Ed25519,
+    Ed25519ph,
+    Ed25519ctx,
+    Ed448,
+    Ed448ph,
+}
+
+impl Scheme {
+    fn parse(name: &str) -> Result<Scheme, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "ed25519" => Ok(Scheme::Ed25519),
+            "ed25519ph" => Ok(Scheme::Ed25519ph),
+            "ed25519ctx" => Ok(Scheme::Ed25519ctx),
+            "ed448" => Ok(Scheme::Ed448),
+            "ed448ph" => Ok(Scheme::Ed448ph),
+            _ => Err(format!("unknown EdDSA scheme: {}", name)),
+        }
+    }
+
+    fn curve(self) -> Curve {
+        match self {
+            Scheme::Ed25519 | Scheme::Ed25519ph | Scheme::Ed25519ctx => Curve::Ed25519,
+            Scheme::Ed448 | Scheme::Ed448ph => Curve::Ed448,
+        }
+    }
+
+    /// Domain separation prefix (dom2 / dom4) and the possibly pre-hashed message
+    fn prepare(self, message: &[u8], context: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+        if context.len() > 255 {
+            return Err("context must be at most 255 bytes".to_string());
+        }
+        let dom = |prefix: &[u8], flag: u8| {
+            let mut d = prefix.to_vec();
+            d.push(flag);
+            d.push(context.len() as u8);
+            d.extend_from_slice(context);
+            d
+        };
+        const DOM2: &[u8] = b"SigEd25519 no Ed25519 collisions";
+        const DOM4: &[u8] = b"SigEd448";
+        match self {
+            Scheme::Ed25519 if context.is_empty() => Ok((Vec::new(), message.to_vec())),
+            Scheme::Ed25519 => Err("Ed25519 does not take a context; use Ed25519ctx".to_string()),
+            Scheme::Ed25519ctx if context.is_empty() => Err("Ed25519ctx requires a non-empty context".to_string()),
+            Scheme::Ed25519ctx => Ok((dom(DOM2, 0), message.to_vec())),
+            Scheme::Ed25519ph => Ok((dom(DOM2, 1), Sha512::digest(message).to_vec())),
+            Scheme::Ed448 => Ok((dom(DOM4, 0), message.to_vec())),
+            Scheme::Ed448ph => Ok((dom(DOM4, 1), shake256(&[message], 64))),
+        }
+    }
+}
+
+fn shake256(parts: &[&[u8]], len: usize) -> Vec<u8> {
+    let mut hasher = Shake256::default();
+    for p in parts {
+        hasher.update(p);
+    }
+    let mut out = vec![0u8; len];
+    hasher.finalize_xof().read(&mut out);
+    out
+}
+
+fn check_len(curve: Curve, key: &[u8], what: &str) -> Result<(), String> {
+    if key.len() != curve.key_len() {
+        return Err(format!("{} {} must be {} bytes", curve.name(), what, curve.key_len()));
+    }
+    Ok(())
+}
+
+/// Random private key (seed)
+pub fn generate(curve: Curve) -> Vec<u8> {
+    let mut seed = vec![0u8; curve.key_len()];
+    getrandom(&mut seed).expect("random generation failed");
+    seed
+}
+
+struct Ed25519Secret {
+    scalar: Scalar,
+    prefix: [u8; 32],
+    public: [u8; 32],
+}
+
+fn expand_ed25519(seed: &[u8]) -> Ed25519Secret {
+    let h = Sha512::digest(seed);
+    let scalar_bytes = clamp_integer(h[..32].try_into().expect("32 bytes"));
+    let public = EdwardsPoint::mul_base_clamped(scalar_bytes).compress().to_bytes();
+    Ed25519Secret {
+        scalar: Scalar::from_bytes_mod_order(scalar_bytes),
+        prefix: h[32..].try_into().expect("32 bytes"),
+        public,
+    }
+}
+
+struct Ed448Secret {
+    scalar: Scalar448,
+    prefix: Vec<u8>,
+    public: [u8; 57],
+}
+
+fn expand_ed448(seed: &[u8]) -> Ed448Secret {
+    let mut h = shake256(&[seed], 114);
+    h[0] &= 0xfc;
+    h[55] |= 0x80;
+    h[56] = 0;
+    // B has prime order L, so reducing the clamped scalar leaves [s]B unchanged
+    let scalar = curve448::scalar_mod_order(&h[..57]);
+    let public = curve448::encode(&curve448::mul_base(&scalar));
+    Ed448Secret { scalar, prefix: h[57..].to_vec(), public }
+}
+
+fn ed25519_hash(parts: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    for p in parts {
+        Digest::update(&mut hasher, p);
+    }
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+fn ed448_hash(parts: &[&[u8]]) -> Scalar448 {
+    curve448::scalar_mod_order(&shake256(parts, 114))
+}
+
+/// Public key for a private seed
+pub fn public_key(curve: Curve, seed: &[u8]) -> Result<Vec<u8>, String> {
+    check_len(curve, seed, "private key")?;
+    Ok(match curve {
+        Curve::Ed25519 => expand_ed25519(seed).public.to_vec(),
+        Curve::Ed448 => expand_ed448(seed).public.to_vec(),
+    })
+}
+
+/// Sign with scheme ed25519 | ed25519ph | ed25519ctx | ed448 | ed448ph
+pub fn sign(scheme: &str, seed: &[u8], message: &[u8], context: &[u8]) -> Result<Vec<u8>, String> {
+    let scheme = Scheme::parse(scheme)?;
+    check_len(scheme.curve(), seed, "private key")?;
+    let (dom, msg) = scheme.prepare(message, context)?;
+    match scheme.curve() {
+        Curve::Ed25519 => {
+            let secret = expand_ed25519(seed);
+            let r = ed25519_hash(&[&dom, &secret.prefix, &msg]);
+            let big_r = EdwardsPoint::mul_base(&r).compress().to_bytes();
+            let k = ed25519_hash(&[&dom, &big_r, &secret.public, &msg]);
+            let s = r + k * secret.scalar;
+            Ok([big_r, s.to_bytes()].concat())
+        }
+        Curve::Ed448 => {
+            let secret = expand_ed448(seed);
+            let r = ed448_hash(&[&dom, &secret.prefix, &msg]);
+            let big_r = curve448::encode(&curve448::mul_base(&r));
+            let k = ed448_hash(&[&dom, &big_r, &secret.public, &msg]);
+            let s = r + k * secret.scalar;
+            Ok([big_r, s.to_bytes_rfc_8032()].concat())
+        }
+    }
+}
+
+/// Verify a signature; malformed keys are errors, bad signatures return false
+pub fn verify(scheme: &str, public: &[u8], message: &[u8], signature: &[u8], context: &[u8]) -> Result<bool, String> {
+    let scheme = Scheme::parse(scheme)?;
+    let curve = scheme.curve();
+    check_len(curve, public, "public key")?;
+    let (dom, msg) = scheme.prepare(message, context)?;
+    if signature.len() != 2 * curve.key_len() {
+        return Ok(false);
+    }
+    let (big_r, s) = signature.split_at(curve.key_len());
+    match curve {
+        Curve::Ed25519 => {
+            let a = CompressedEdwardsY(public.try_into().expect("32 bytes"))
+                .decompress()
+                .ok_or("invalid Ed25519 public key")?;
+            let s: Option<Scalar> = Scalar::from_canonical_bytes(s.try_into().expect("32 bytes")).into();
+            let Some(s) = s else { return Ok(false) };
+            let k = ed25519_hash(&[&dom, big_r, public, &msg]);
+            // R' = [S]B - [k]A must re-encode to R
+            let check = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+            Ok(check.compress().as_bytes()[..] == big_r[..])
+        }
+        Curve::Ed448 => {
+            let a = curve448::decode(public).ok_or("invalid Ed448 public key")?;
+            let Some(r_point) = curve448::decode(big_r) else { return Ok(false) };
+            let Some(s) = curve448::scalar_from_canonical(s) else { return Ok(false) };
+            let k = ed448_hash(&[&dom, big_r, public, &msg]);
+            // [S]B = R + [k]A
+            Ok(curve448::mul_base(&s) == r_point.add(&a.scalar_mul(&k)))
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Key formats
+// ----------------------------------------------------------------------------
+
+/// PKCS#8 PrivateKeyInfo (RFC 8410): SEQ { INT 0, SEQ { OID 1.3.101.11x }, OCTET STRING { OCTET STRING seed } }
+pub fn private_to_pkcs8(curve: Curve, seed: &[u8]) -> Result<Vec<u8>, String> {
+    check_len(curve, seed, "private key")?;
+    let n = seed.len() as u8;
+    let mut der = vec![0x30, n + 14, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, curve.oid_byte()];
+    der.extend_from_slice(&[0x04, n + 2, 0x04, n]);
+    der.extend_from_slice(seed);
+    Ok(der)
+}
+
+/// SubjectPublicKeyInfo: SEQ { SEQ { OID }, BIT STRING public }
+pub fn public_to_spki(curve: Curve, public: &[u8]) -> Result<Vec<u8>, String> {
+    check_len(curve, public, "public key")?;
+    let n = public.len() as u8;
+    let mut der = vec![0x30, n + 10, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, curve.oid_byte(), 0x03, n + 1, 0x00];
+    der.extend_from_slice(public);
+    Ok(der)
+}
+
+pub fn private_to_pem(curve: Curve, seed: &[u8]) -> Result<String, String> {
+    Ok(pem::encode("PRIVATE KEY", &private_to_pkcs8(curve, seed)?))
+}
+
+pub fn public_to_pem(curve: Curve, public: &[u8]) -> Result<String, String> {
+    Ok(pem::encode("PUBLIC KEY", &public_to_spki(curve, public)?))
+}
+
+/// OKP JWK; includes "d" when a private seed is given
+pub fn to_jwk(curve: Curve, key: &[u8], private: bool) -> Result<String, String> {
+    let (seed, public) = if private {
+        (Some(key), public_key(curve, key)?)
+    } else {
+        check_len(curve, key, "public key")?;
+        (None, key.to_vec())
+    };
+    let mut jwk = serde_json::json!({
+        "kty": "OKP",
+        "crv": curve.name(),
+        "x": URL_SAFE_NO_PAD.encode(&public),
+    });
+    if let Some(seed) = seed {
+        jwk["d"] = URL_SAFE_NO_PAD.encode(seed).into();
+    }
+    Ok(jwk.to_string())
+}
+
+/// A key read from PEM, DER or JWK
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImportedKey {
+    pub curve: Curve,
+    pub private: Option<Vec<u8>>,
+    pub public: Vec<u8>,
+}
+
+/// Read a PKCS#8 / SPKI key in PEM or DER, or an OKP JWK
+pub fn import(input: &[u8]) -> Result<ImportedKey, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    if text.starts_with('{') {
+        return import_jwk(text);
+    }
+    let der = if text.starts_with("-----BEGIN") { pem::decode(text)?.1 } else { input.to_vec() };
+    import_der(&der)
+}
+
+fn import_der(der: &[u8]) -> Result<ImportedKey, String> {
+    for curve in [Curve::Ed25519, Curve::Ed448] {
+        let n = curve.key_len();
+        let pkcs8 = private_to_pkcs8(curve, &vec![0u8; n])?;
+        let spki = public_to_spki(curve, &vec![0u8; n])?;
+        if der.len() == pkcs8.len() && der[..pkcs8.len() - n] == pkcs8[..pkcs8.len() - n] {
+            let seed = der[pkcs8.len() - n..].to_vec();
+            let public = public_key(curve, &seed)?;
+            return Ok(ImportedKey { curve, private: Some(seed), public });
+        }
+        if der.len() == spki.len() && der[..spki.len() - n] == spki[..spki.len() - n] {
+            return Ok(ImportedKey { curve, private: None, public: der[spki.len() - n..].to_vec() });
+        }
+    }
+    Err("not an Ed25519 or Ed448 PKCS#8 / SPKI key".to_string())
+}
+
+fn import_jwk(text: &str) -> Result<ImportedKey, String> {
+    let jwk: serde_json::Value = serde_json::from_str(text).map_err(|_| "invalid JWK JSON".to_string())?;
+    if jwk["kty"] != "OKP" {
+        return Err("JWK is not an OKP key".to_string());
+    }
+    let curve = Curve::parse(jwk["crv"].as_str().unwrap_or(""))?;
+    let field = |name: &str| -> Result<Option<Vec<u8>>, String> {
+        match jwk[name].as_str() {
+            Some(v) => URL_SAFE_NO_PAD
+                .decode(v)
+                .map(Some)
+                .map_err(|_| format!("invalid base64url in JWK \"{}\"", name)),
+            None => Ok(None),
+        }
+    };
+    let x = field("x")?;
+    match field("d")? {
+        Some(seed) => {
+            let public = public_key(curve, &seed)?;
+            if x.is_some_and(|x| x != public) {
+                return Err("JWK \"x\" does not match \"d\"".to_string());
+            }
+            Ok(ImportedKey { curve, private: Some(seed), public })
+        }
+        None => {
+            let public = x.ok_or("JWK has no \"x\"")?;
+            check_len(curve, &public, "public key")?;
+            Ok(ImportedKey { curve, private: None, public })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8032 section 7.1 test 1
+    #[test]
+    fn test_ed25519_rfc8032() {
+        let seed = hex::decode("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+        let public = public_key(Curve::Ed25519, &seed).unwrap();
+        assert_eq!(
+            hex::encode(&public),
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        let sig = sign("ed25519", &seed, b"", b"").unwrap();
+        assert_eq!(
+            hex::encode(&sig),
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        );
+        assert!(verify("ed25519", &public, b"", &sig, b"").unwrap());
+        assert!(!verify("ed25519", &public, b"x", &sig, b"").unwrap());
+    }
+
+    // Vectors from OpenSSL 3.5 (`pkeyutl -rawin -pkeyopt instance:...`), seed 00..1f, message "hashlab"
+    #[test]
+    fn test_ed25519_variants() {
+        let seed: Vec<u8> = (0u8..32).collect();
+        let public = public_key(Curve::Ed25519, &seed).unwrap();
+        let cases = [
+            ("ed25519", &b""[..], "b347641d904fe15f3438fd877f1bb5dbf502b03d85455aab7b11f6a59d0f37754ddca8415bdf9e71da94371fe76374c8ef26fcfa5da45145183bd341ad5a8109"),
+            ("ed25519ph", b"foo", "5a1fdb88d92bf165dbc2ed7cfae97aeea299f88bd69ae31859a384ac5ed6baca736e22e9a5ce9d03f0f5eee87bfecb16909d4ac44f24ed19a0abfb22b2da9c05"),
+            ("ed25519ctx", b"foo", "f9146e2d469b2c708d2fab9995d1493ddd0901007c9503d63f1af1cd0f452d6aa812454affd0bbf154f3323e44d9239e5bb98806225d621bc9e11b2be7a01b07"),
+        ];
+        for (scheme, ctx, expected) in cases {
+            let sig = sign(scheme, &seed, b"hashlab", ctx).unwrap();
+            assert_eq!(hex::encode(&sig), expected, "{}", scheme);
+            assert!(verify(scheme, &public, b"hashlab", &sig, ctx).unwrap());
+        }
+        assert!(!verify("ed25519ctx", &public, b"hashlab", &hex::decode(cases[2].2).unwrap(), b"bar").unwrap());
+        assert!(sign("ed25519ctx", &seed, b"hashlab", b"").is_err());
+    }
+
+    // RFC 8032 section 7.4 (Ed448) and 7.5 (Ed448ph)
+    #[test]
+    fn test_ed448_rfc8032() {
+        let cases = [
+            (
+                "ed448",
+                "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+                "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+                "",
+                "",
+                "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4dbb61149f05a7363268c71d95808ff2e652600",
+            ),
+            (
+                "ed448",
+                "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                "03",
+                "",
+                "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f4352541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cbcee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0ff3348ab21aa4adafd1d234441cf807c03a00",
+            ),
+            (
+                "ed448",
+                "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                "03",
+                "666f6f",
+                "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d5428407e85dcbc98a49155c13764e66c3c00",
+            ),
+            (
+                "ed448ph",
+                "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42ef7822e0d5104127dc05d6dbefde69e3ab2cec7c867c6e2c49",
+                "259b71c19f83ef77a7abd26524cbdb3161b590a48f7d17de3ee0ba9c52beb743c09428a131d6b1b57303d90d8132c276d5ed3d5d01c0f53880",
+                "616263",
+                "",
+                "822f6901f7480f3d5f562c592994d9693602875614483256505600bbc281ae381f54d6bce2ea911574932f52a4e6cadd78769375ec3ffd1b801a0d9b3f4030cd433964b6457ea39476511214f97469b57dd32dbc560a9a94d00bff07620464a3ad203df7dc7ce360c3cd3696d9d9fab90f00",
+            ),
+        ];
+        for (scheme, seed, public, message, ctx, expected) in cases {
+            let (seed, message, ctx) = (hex::decode(seed).unwrap(), hex::decode(message).unwrap(), hex::decode(ctx).unwrap());
+            let public_bytes = public_key(Curve::Ed448, &seed).unwrap();
+            assert_eq!(hex::encode(&public_bytes), public);
+            let sig = sign(scheme, &seed, &message, &ctx).unwrap();
+            assert_eq!(hex::encode(&sig), expected, "{} {:?}", scheme, ctx);
+            assert!(verify(scheme, &public_bytes, &message, &sig, &ctx).unwrap());
+            assert!(!verify(scheme, &public_bytes, b"other", &sig, &ctx).unwrap());
+        }
+        // Context and prehash flag are bound into the signature
+        let public = hex::decode(cases[2].2).unwrap();
+        assert!(!verify("ed448", &public, &[3], &hex::decode(cases[2].5).unwrap(), b"bar").unwrap());
+        assert!(!verify("ed448ph", &public, &[3], &hex::decode(cases[1].5).unwrap(), b"").unwrap());
+        // S >= L is rejected
+        let mut sig = hex::decode(cases[0].5).unwrap();
+        sig[57..].copy_from_slice(&[0xff; 57]);
+        assert!(!verify("ed448", &hex::decode(cases[0].2).unwrap(), b"", &sig, b"").unwrap());
+    }
+
+    #[test]
+    fn test_key_formats() {
+        let seed: Vec<u8> = (0u8..57).collect();
+        let pem = private_to_pem(Curve::Ed448, &seed).unwrap();
+        assert!(pem.contains("MEcCAQAwBQYDK2VxBDsEOQABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f"));
+        let key = import(pem.as_bytes()).unwrap();
+        assert_eq!(key.curve, Curve::Ed448);
+        assert_eq!(key.private.as_deref(), Some(&seed[..]));
+
+        let seed: Vec<u8> = (0u8..32).collect();
+        let public = public_key(Curve::Ed25519, &seed).unwrap();
+        assert_eq!(
+            public_to_pem(Curve::Ed25519, &public).unwrap(),
+            "-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEAA6EHv/POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg=\n-----END PUBLIC KEY-----\n"
+        );
+        let spki = public_to_spki(Curve::Ed25519, &public).unwrap();
+        assert_eq!(import(&spki).unwrap().public, public);
+
+        let jwk = to_jwk(Curve::Ed25519, &seed, true).unwrap();
+        let key = import(jwk.as_bytes()).unwrap();
+        assert_eq!(key.private.as_deref(), Some(&seed[..]));
+        assert_eq!(key.public, public);
+        let jwk = to_jwk(Curve::Ed25519, &public, false).unwrap();
+        assert!(!jwk.contains("\"d\""));
+        assert_eq!(import(jwk.as_bytes()).unwrap().private, None);
+        assert!(import(b"{\"kty\":\"EC\"}").is_err());
+    }
+}
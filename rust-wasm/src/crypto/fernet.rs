@@ -0,0 +1,134 @@
+// Fernet tokens (https://github.com/fernet/spec)
+//
+// Key:   32 bytes, URL-safe Base64 - [SIGNING_KEY16][ENCRYPTION_KEY16]
+// Token: URL-safe Base64 of [0x80][TIMESTAMP64 BE][IV16][AES-128-CBC CIPHERTEXT][HMAC-SHA256 32]
+//        with the HMAC computed over everything before it.
+// Timestamps are seconds since the Unix epoch and are passed in by the caller (wasm has no clock).
+use super::block_modes;
+use crate::hash::hmac;
+use base64::alphabet::URL_SAFE;
+use base64::engine::general_purpose::PAD;
+use base64::engine::{DecodePaddingMode, GeneralPurpose};
+use base64::Engine;
+use getrandom::getrandom;
+
+const VERSION: u8 = 0x80;
+const MAX_CLOCK_SKEW: u64 = 60;
+const HEADER_BYTES: usize = 1 + 8 + 16;
+const MAC_BYTES: usize = 32;
+
+// URL-safe alphabet, padding emitted but optional when decoding
+const BASE64: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, PAD.with_decode_padding_mode(DecodePaddingMode::Indifferent));
+
+pub fn generate_key() -> String {
+    let mut key = [0u8; 32];
+    getrandom(&mut key).expect("random key generation failed");
+    BASE64.encode(key)
+}
+
+fn parse_key(key: &str) -> Result<Vec<u8>, String> {
+    match BASE64.decode(key.trim()) {
+        Ok(bytes) if bytes.len() == 32 => Ok(bytes),
+        _ => Err("Fernet key must be 32 url-safe base64-encoded bytes".to_string()),
+    }
+}
+
+pub fn encrypt_with_iv(key: &str, data: &[u8], now: u64, iv: &[u8; 16]) -> Result<String, String> {
+    let key = parse_key(key)?;
+    let ciphertext = block_modes::encrypt("aes128", "cbc", "pkcs7", &key[16..], iv, data)?;
+
+    let mut token = Vec::with_capacity(HEADER_BYTES + ciphertext.len() + MAC_BYTES);
+    token.push(VERSION);
+    token.extend_from_slice(&now.to_be_bytes());
+    token.extend_from_slice(iv);
+    token.extend_from_slice(&ciphertext);
+    let mac = hmac::sha256(&key[..16], &token);
+    token.extend_from_slice(&mac);
+    Ok(BASE64.encode(token))
+}
+
+pub fn encrypt(key: &str, data: &[u8], now: u64) -> Result<String, String> {
+    let mut iv = [0u8; 16];
+    getrandom(&mut iv).expect("random iv generation failed");
+    encrypt_with_iv(key, data, now, &iv)
+}
+
+fn decode_token(token: &str) -> Result<Vec<u8>, String> {
+    let bytes = BASE64.decode(token.trim()).map_err(|_| "invalid token".to_string())?;
+    if bytes.len() < HEADER_BYTES + 16 + MAC_BYTES || bytes[0] != VERSION {
+        return Err("invalid token".to_string());
+    }
+    Ok(bytes)
+}
+
+// Creation time of a token; it is not authenticated until the token is decrypted
+pub fn timestamp(token: &str) -> Result<u64, String> {
+    let bytes = decode_token(token)?;
+    Ok(u64::from_be_bytes(bytes[1..9].try_into().expect("8 bytes")))
+}
+
+// ttl: maximum token age in seconds, 0 to skip the timestamp checks
+pub fn decrypt(key: &str, token: &str, ttl: u64, now: u64) -> Result<Vec<u8>, String> {
+    let key = parse_key(key)?;
+    let bytes = decode_token(token)?;
+    let (signed, mac) = bytes.split_at(bytes.len() - MAC_BYTES);
+    if !hmac::verify("sha256", &key[..16], signed, mac)? {
+        return Err("invalid token".to_string());
+    }
+
+    let issued = u64::from_be_bytes(signed[1..9].try_into().expect("8 bytes"));
+    // Like the reference implementation, timestamps are only checked when a TTL is given
+    if ttl > 0 {
+        if issued.saturating_add(ttl) < now {
+            return Err("token expired".to_string());
+        }
+        if issued > now.saturating_add(MAX_CLOCK_SKEW) {
+            return Err("token timestamp is in the future".to_string());
+        }
+    }
+    block_modes::decrypt("aes128", "cbc", "pkcs7", &key[16..], &signed[9..25], &signed[HEADER_BYTES..])
+        .map_err(|_| "invalid token".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // fernet/spec generate.json
+    #[test]
+    fn test_spec_vector() {
+        let key = "cw_0x689RpI-jtRR7oE8h_eQsKImvJapLeSbXpwF4e4=";
+        let iv: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let token = "gAAAAAAdwJ6wAAECAwQFBgcICQoLDA0ODy021cpGVWKZ_eEwCGM4BLLF_5CV9dOPmrhuVUPgJobwOz7JcbmrR64jVmpU4IwqDA==";
+        assert_eq!(encrypt_with_iv(key, b"hello", 499162800, &iv).unwrap(), token);
+        assert_eq!(decrypt(key, token, 60, 499162860).unwrap(), b"hello");
+        assert_eq!(timestamp(token).unwrap(), 499162800);
+    }
+
+    // Token produced by Python `cryptography.fernet.Fernet`
+    #[test]
+    fn test_python_token() {
+        let key = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=";
+        let token = "gAAAAABlU_EAZGVmZ2hpamtsbW5vcHFyc5OCJy9qQGmZ34XIchSblD1EMSt9GHH3GihGe_abGv47zj2QNU-6iRcgmju8QOceh1bC3RtSSEo21gniz-G_Lnw=";
+        assert_eq!(decrypt(key, token, 60, 1700000030).unwrap(), b"fernet token payload");
+        assert_eq!(decrypt(key, token.trim_end_matches('='), 0, 1700000030).unwrap(), b"fernet token payload");
+    }
+
+    #[test]
+    fn test_ttl_and_tampering() {
+        let key = generate_key();
+        let token = encrypt(&key, b"session", 1000).unwrap();
+        assert_eq!(decrypt(&key, &token, 100, 1100).unwrap(), b"session");
+        assert_eq!(decrypt(&key, &token, 100, 1101).unwrap_err(), "token expired");
+        assert_eq!(decrypt(&key, &token, 100, 900).unwrap_err(), "token timestamp is in the future");
+        // Without a TTL the timestamp is not checked at all
+        assert_eq!(decrypt(&key, &token, 0, 900).unwrap(), b"session");
+        assert_eq!(decrypt(&key, &token, 0, u64::MAX).unwrap(), b"session");
+        assert_eq!(decrypt(&generate_key(), &token, 0, 1000).unwrap_err(), "invalid token");
+
+        let mut bytes = BASE64.decode(&token).unwrap();
+        bytes[30] ^= 1;
+        assert_eq!(decrypt(&key, &BASE64.encode(bytes), 0, 1000).unwrap_err(), "invalid token");
+        assert!(parse_key("short").is_err());
+    }
+}
@@ -0,0 +1,761 @@
+// JSON Web Encryption (RFC 7516 / 7518) in compact and JSON serialization.
+//
+// Key management: dir, A128KW / A192KW / A256KW, A128GCMKW / A192GCMKW / A256GCMKW,
+// PBES2-HS256+A128KW / HS384+A192KW / HS512+A256KW, RSA-OAEP(-256/384/512), ECDH-ES and
+// ECDH-ES+A*KW over P-256 / P-384 / P-521 / X25519 / X448.
+// Content encryption: A128CBC-HS256 / A192CBC-HS384 / A256CBC-HS512 and A128GCM / A192GCM / A256GCM.
+use std::io::{Read, Write};
+
+use getrandom::getrandom;
+use serde_json::{Map, Value, json};
+
+use super::ecdh::{self, Curve};
+use super::jwt::{self, Jwk, KeyMaterial, b64, unb64};
+use super::{aes128_cbc, aes192_cbc, aes256_cbc, aes_kw, kdf, pbkdf2_key};
+use crate::encoding::key_container::{self, Key, PBES2_ITERATIONS};
+use crate::hash::hmac;
+use crate::steganography::aes_gcm_cipher;
+
+/// Highest PBES2 iteration count accepted when decrypting (p2c is attacker-controlled)
+const MAX_PBES2_ITERATIONS: u64 = 10_000_000;
+/// Upper bound for "zip": "DEF" payloads after inflation
+const MAX_INFLATED_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+enum KeyAlg {
+    Dir,
+    AesKw(usize),
+    AesGcmKw(usize),
+    Pbes2 { hash: &'static str, kek_len: usize },
+    RsaOaep(&'static str),
+    /// `None` for direct key agreement, otherwise the key wrap KEK length
+    EcdhEs(Option<usize>),
+}
+
+fn key_algorithm(name: &str) -> Result<KeyAlg, String> {
+    Ok(match name {
+        "dir" => KeyAlg::Dir,
+        "A128KW" => KeyAlg::AesKw(16),
+        "A192KW" => KeyAlg::AesKw(24),
+        "A256KW" => KeyAlg::AesKw(32),
+        "A128GCMKW" => KeyAlg::AesGcmKw(16),
+        "A192GCMKW" => KeyAlg::AesGcmKw(24),
+        "A256GCMKW" => KeyAlg::AesGcmKw(32),
+        "PBES2-HS256+A128KW" => KeyAlg::Pbes2 { hash: "sha256", kek_len: 16 },
+        "PBES2-HS384+A192KW" => KeyAlg::Pbes2 { hash: "sha384", kek_len: 24 },
+        "PBES2-HS512+A256KW" => KeyAlg::Pbes2 { hash: "sha512", kek_len: 32 },
+        "RSA-OAEP" => KeyAlg::RsaOaep("sha1"),
+        "RSA-OAEP-256" => KeyAlg::RsaOaep("sha256"),
+        "RSA-OAEP-384" => KeyAlg::RsaOaep("sha384"),
+        "RSA-OAEP-512" => KeyAlg::RsaOaep("sha512"),
+        "ECDH-ES" => KeyAlg::EcdhEs(None),
+        "ECDH-ES+A128KW" => KeyAlg::EcdhEs(Some(16)),
+        "ECDH-ES+A192KW" => KeyAlg::EcdhEs(Some(24)),
+        "ECDH-ES+A256KW" => KeyAlg::EcdhEs(Some(32)),
+        "RSA1_5" => return Err("RSA1_5 is not supported (padding oracle); use RSA-OAEP-256".to_string()),
+        _ => return Err(format!("unsupported JWE key management algorithm: {}", name)),
+    })
+}
+
+impl KeyAlg {
+    /// Whether the key input is a shared secret (or a password for PBES2)
+    fn uses_secret(self) -> bool {
+        matches!(self, KeyAlg::Dir | KeyAlg::AesKw(_) | KeyAlg::AesGcmKw(_) | KeyAlg::Pbes2 { .. })
+    }
+
+    fn usable(self, key: &KeyMaterial) -> bool {
+        match (self, key) {
+            (KeyAlg::RsaOaep(_), KeyMaterial::Asymmetric(Key::Rsa(_))) => true,
+            (KeyAlg::EcdhEs(_), KeyMaterial::Asymmetric(Key::Ec { curve, .. })) => *curve != Curve::Secp256k1,
+            (KeyAlg::EcdhEs(_), KeyMaterial::Xdh { .. }) => true,
+            (alg, KeyMaterial::Secret(_)) => alg.uses_secret(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Enc {
+    CbcHmac { key_len: usize, hash: &'static str },
+    Gcm(usize),
+}
+
+fn content_algorithm(name: &str) -> Result<Enc, String> {
+    Ok(match name {
+        "A128CBC-HS256" => Enc::CbcHmac { key_len: 16, hash: "sha256" },
+        "A192CBC-HS384" => Enc::CbcHmac { key_len: 24, hash: "sha384" },
+        "A256CBC-HS512" => Enc::CbcHmac { key_len: 32, hash: "sha512" },
+        "A128GCM" => Enc::Gcm(16),
+        "A192GCM" => Enc::Gcm(24),
+        "A256GCM" => Enc::Gcm(32),
+        _ => return Err(format!("unsupported JWE content encryption: {}", name)),
+    })
+}
+
+impl Enc {
+    fn cek_len(self) -> usize {
+        match self {
+            Enc::CbcHmac { key_len, .. } => 2 * key_len,
+            Enc::Gcm(key_len) => key_len,
+        }
+    }
+
+    fn iv_len(self) -> usize {
+        match self {
+            Enc::CbcHmac { .. } => 16,
+            Enc::Gcm(_) => 12,
+        }
+    }
+}
+
+fn random(len: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![0u8; len];
+    getrandom(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn decryption_failed() -> String {
+    "decryption failed".to_string()
+}
+
+// ----------------------------------------------------------------------------
+// Content encryption
+// ----------------------------------------------------------------------------
+
+/// AES-GCM with a 96-bit IV; output (and input when decrypting) is ciphertext || tag
+fn gcm(key: &[u8], iv: &[u8], aad: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    if encrypt {
+        aes_gcm_cipher::seal(key, iv, aad, data)
+    } else {
+        aes_gcm_cipher::open(key, iv, aad, data).map_err(|_| decryption_failed())
+    }
+}
+
+/// AES-CBC with PKCS#7 padding and a 128-bit IV
+fn cbc(key: &[u8], iv: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    let iv: &[u8; 16] = iv.try_into().map_err(|_| "AES-CBC needs a 128-bit IV".to_string())?;
+    match (key.len(), encrypt) {
+        (16, true) => Ok(aes128_cbc::encrypt_bytes(data, key.try_into().expect("16 bytes"), iv)),
+        (24, true) => Ok(aes192_cbc::encrypt_bytes(data, key.try_into().expect("24 bytes"), iv)),
+        (32, true) => Ok(aes256_cbc::encrypt_bytes(data, key.try_into().expect("32 bytes"), iv)),
+        (16, false) => aes128_cbc::decrypt_bytes(data, key.try_into().expect("16 bytes"), iv),
+        (24, false) => aes192_cbc::decrypt_bytes(data, key.try_into().expect("24 bytes"), iv),
+        (32, false) => aes256_cbc::decrypt_bytes(data, key.try_into().expect("32 bytes"), iv),
+        (n, _) => Err(format!("invalid AES key length: {} bytes", n)),
+    }
+}
+
+/// RFC 7518 section 5.2.2.1: HMAC over AAD || IV || ciphertext || AAD length in bits, truncated
+fn cbc_tag(mac_key: &[u8], hash: &str, aad: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    let al = (aad.len() as u64 * 8).to_be_bytes();
+    let mut tag = hmac::mac(hash, mac_key, &[aad, iv, ciphertext, &al].concat())?;
+    tag.truncate(mac_key.len());
+    Ok(tag)
+}
+
+/// Encrypt with the CEK; returns (ciphertext, tag)
+fn seal(enc: Enc, cek: &[u8], iv: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    match enc {
+        Enc::CbcHmac { key_len, hash } => {
+            let (mac_key, enc_key) = cek.split_at(key_len);
+            let ciphertext = cbc(enc_key, iv, plaintext, true)?;
+            let tag = cbc_tag(mac_key, hash, aad, iv, &ciphertext)?;
+            Ok((ciphertext, tag))
+        }
+        Enc::Gcm(_) => {
+            let mut sealed = gcm(cek, iv, aad, plaintext, true)?;
+            let tag = sealed.split_off(sealed.len() - 16);
+            Ok((sealed, tag))
+        }
+    }
+}
+
+fn open(enc: Enc, cek: &[u8], iv: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>, String> {
+    match enc {
+        Enc::CbcHmac { key_len, hash } => {
+            let (mac_key, enc_key) = cek.split_at(key_len);
+            let expected = cbc_tag(mac_key, hash, aad, iv, ciphertext)?;
+            // Constant-time tag comparison before touching the padding
+            if expected.len() != tag.len() || expected.iter().zip(tag).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+                return Err(decryption_failed());
+            }
+            cbc(enc_key, iv, ciphertext, false).map_err(|_| decryption_failed())
+        }
+        Enc::Gcm(_) => gcm(cek, iv, aad, &[ciphertext, tag].concat(), false),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Key management
+// ----------------------------------------------------------------------------
+
+fn pbes2_kek(hash: &str, kek_len: usize, alg: &str, password: &[u8], p2s: &[u8], p2c: u32) -> Result<Vec<u8>, String> {
+    // Salt is the algorithm name, a zero byte and the p2s value
+    let salt = [alg.as_bytes(), &[0], p2s].concat();
+    pbkdf2_key::derive(hash, password, &salt, p2c, kek_len)
+}
+
+fn header_bytes(header: &Map<String, Value>, name: &str) -> Result<Vec<u8>, String> {
+    match header.get(name) {
+        Some(Value::String(value)) => unb64(value, &format!("\"{}\"", name)),
+        Some(_) => Err(format!("header \"{}\" must be a string", name)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// ECDH-ES ConcatKDF (RFC 7518 section 4.6.2) with SHA-256
+fn concat_kdf(z: &[u8], algorithm_id: &str, key_len: usize, header: &Map<String, Value>) -> Result<Vec<u8>, String> {
+    let field = |bytes: &[u8]| [&(bytes.len() as u32).to_be_bytes()[..], bytes].concat();
+    let other_info = [
+        field(algorithm_id.as_bytes()),
+        field(&header_bytes(header, "apu")?),
+        field(&header_bytes(header, "apv")?),
+        ((key_len * 8) as u32).to_be_bytes().to_vec(),
+    ]
+    .concat();
+    kdf::concat_kdf("sha256", z, &other_info, key_len)
+}
+
+fn recipient_public(key: &KeyMaterial) -> Result<(Curve, &[u8]), String> {
+    match key {
+        KeyMaterial::Asymmetric(Key::Ec { curve, public, .. }) => Ok((*curve, public)),
+        KeyMaterial::Xdh { curve, public, .. } => Ok((*curve, public)),
+        _ => Err("ECDH-ES needs an EC, X25519 or X448 key".to_string()),
+    }
+}
+
+fn epk_jwk(curve: Curve, public: &[u8]) -> Value {
+    match curve {
+        Curve::X25519 | Curve::X448 => json!({ "kty": "OKP", "crv": key_container::curve_name(curve), "x": b64(public) }),
+        _ => {
+            let n = curve.private_len();
+            json!({
+                "kty": "EC",
+                "crv": key_container::curve_name(curve),
+                "x": b64(&public[1..1 + n]),
+                "y": b64(&public[1 + n..]),
+            })
+        }
+    }
+}
+
+fn epk_curve(header: &Map<String, Value>) -> Option<Curve> {
+    Curve::parse(header.get("epk")?.get("crv")?.as_str()?).ok()
+}
+
+/// Ephemeral public key from the "epk" header, checked to be on the recipient's curve
+fn read_epk(header: &Map<String, Value>, curve: Curve) -> Result<Vec<u8>, String> {
+    let epk = header.get("epk").ok_or("header has no \"epk\"")?;
+    if Curve::parse(epk["crv"].as_str().unwrap_or(""))? != curve {
+        return Err("\"epk\" is on a different curve than the key".to_string());
+    }
+    let coordinate = |name: &str| unb64(epk[name].as_str().unwrap_or(""), "\"epk\"");
+    match curve {
+        Curve::X25519 | Curve::X448 => coordinate("x"),
+        _ => Ok([vec![0x04], coordinate("x")?, coordinate("y")?].concat()),
+    }
+}
+
+/// Sender side: wrap `cek` (or, for dir / ECDH-ES, produce it) for one recipient, adding
+/// per-recipient header parameters (epk, iv / tag, p2s / p2c). Returns (CEK, encrypted key).
+fn wrap_key(
+    alg: KeyAlg,
+    alg_name: &str,
+    enc: (&str, Enc),
+    key: &KeyMaterial,
+    cek: &[u8],
+    header: &mut Map<String, Value>,
+    params: &Map<String, Value>,
+) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let secret = match key {
+        KeyMaterial::Secret(secret) => secret.as_slice(),
+        _ => &[],
+    };
+    match alg {
+        KeyAlg::Dir => {
+            if secret.len() != enc.1.cek_len() {
+                return Err(format!("{} needs a {}-byte key for dir", enc.0, enc.1.cek_len()));
+            }
+            Ok((secret.to_vec(), Vec::new()))
+        }
+        KeyAlg::AesKw(n) | KeyAlg::AesGcmKw(n) if secret.len() != n => {
+            Err(format!("{} needs a {}-byte key", alg_name, n))
+        }
+        KeyAlg::AesKw(_) => Ok((cek.to_vec(), aes_kw::wrap(secret, cek)?)),
+        KeyAlg::AesGcmKw(_) => {
+            let iv = random(12)?;
+            let mut wrapped = gcm(secret, &iv, &[], cek, true)?;
+            let tag = wrapped.split_off(wrapped.len() - 16);
+            header.insert("iv".to_string(), json!(b64(&iv)));
+            header.insert("tag".to_string(), json!(b64(&tag)));
+            Ok((cek.to_vec(), wrapped))
+        }
+        KeyAlg::Pbes2 { hash, kek_len } => {
+            let p2c = match params.get("p2c") {
+                Some(value) => value.as_u64().filter(|c| (1..=MAX_PBES2_ITERATIONS).contains(c)).ok_or("invalid \"p2c\"")?,
+                None => u64::from(PBES2_ITERATIONS),
+            };
+            let p2s = random(16)?;
+            let kek = pbes2_kek(hash, kek_len, alg_name, secret, &p2s, p2c as u32)?;
+            header.insert("p2s".to_string(), json!(b64(&p2s)));
+            header.insert("p2c".to_string(), json!(p2c));
+            Ok((cek.to_vec(), aes_kw::wrap(&kek, cek)?))
+        }
+        KeyAlg::RsaOaep(hash) => match key {
+            KeyMaterial::Asymmetric(Key::Rsa(k)) => Ok((cek.to_vec(), super::rsa::encrypt(k, cek, "oaep", hash, hash, "")?)),
+            _ => Err("RSA-OAEP needs an RSA key".to_string()),
+        },
+        KeyAlg::EcdhEs(kw) => {
+            let (curve, public) = recipient_public(key)?;
+            let ephemeral = ecdh::generate(curve);
+            let z = ecdh::shared_secret(curve, &ephemeral, public)?;
+            header.insert("epk".to_string(), epk_jwk(curve, &ecdh::public_key(curve, &ephemeral, false)?));
+            // apu / apv may come from the caller's header; they are part of the KDF input
+            let mut kdf_params = params.clone();
+            kdf_params.extend(header.clone());
+            match kw {
+                None => Ok((concat_kdf(&z, enc.0, enc.1.cek_len(), &kdf_params)?, Vec::new())),
+                Some(n) => {
+                    let kek = concat_kdf(&z, alg_name, n, &kdf_params)?;
+                    Ok((cek.to_vec(), aes_kw::wrap(&kek, cek)?))
+                }
+            }
+        }
+    }
+}
+
+/// Recipient side: recover the CEK from the encrypted key and the merged header
+fn unwrap_key(
+    alg: KeyAlg,
+    alg_name: &str,
+    enc: (&str, Enc),
+    key: &KeyMaterial,
+    header: &Map<String, Value>,
+    encrypted_key: &[u8],
+) -> Result<Vec<u8>, String> {
+    let secret = match key {
+        KeyMaterial::Secret(secret) => secret.as_slice(),
+        _ => &[],
+    };
+    match alg {
+        KeyAlg::Dir => Ok(secret.to_vec()),
+        KeyAlg::AesKw(_) => aes_kw::unwrap(secret, encrypted_key),
+        KeyAlg::AesGcmKw(_) => {
+            let sealed = [encrypted_key, &header_bytes(header, "tag")?].concat();
+            gcm(secret, &header_bytes(header, "iv")?, &[], &sealed, false)
+        }
+        KeyAlg::Pbes2 { hash, kek_len } => {
+            let p2c = header
+                .get("p2c")
+                .and_then(Value::as_u64)
+                .filter(|c| (1..=MAX_PBES2_ITERATIONS).contains(c))
+                .ok_or("missing or excessive \"p2c\"")?;
+            let kek = pbes2_kek(hash, kek_len, alg_name, secret, &header_bytes(header, "p2s")?, p2c as u32)?;
+            aes_kw::unwrap(&kek, encrypted_key)
+        }
+        KeyAlg::RsaOaep(hash) => match key {
+            KeyMaterial::Asymmetric(Key::Rsa(k)) => super::rsa::decrypt(k, encrypted_key, "oaep", hash, hash, ""),
+            _ => Err("RSA-OAEP needs an RSA key".to_string()),
+        },
+        KeyAlg::EcdhEs(kw) => {
+            let (curve, private) = match key {
+                KeyMaterial::Asymmetric(Key::Ec { curve, private: Some(d), .. }) => (*curve, d),
+                KeyMaterial::Xdh { curve, private: Some(d), .. } => (*curve, d),
+                _ => return Err("a private key is required".to_string()),
+            };
+            let z = ecdh::shared_secret(curve, private, &read_epk(header, curve)?)?;
+            match kw {
+                None => concat_kdf(&z, enc.0, enc.1.cek_len(), header),
+                Some(n) => aes_kw::unwrap(&concat_kdf(&z, alg_name, n, header)?, encrypted_key),
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Serialization
+// ----------------------------------------------------------------------------
+
+struct Recipient {
+    header: Map<String, Value>,
+    encrypted_key: Vec<u8>,
+}
+
+struct Message {
+    /// The protected header exactly as transmitted (base64url)
+    protected_b64: String,
+    protected: Map<String, Value>,
+    unprotected: Map<String, Value>,
+    recipients: Vec<Recipient>,
+    aad_b64: Option<String>,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+    compact: bool,
+}
+
+fn object(value: Option<&Value>, what: &str) -> Result<Map<String, Value>, String> {
+    match value {
+        None => Ok(Map::new()),
+        Some(Value::Object(map)) => Ok(map.clone()),
+        Some(_) => Err(format!("{} must be a JSON object", what)),
+    }
+}
+
+fn protected_header(encoded: &str) -> Result<Map<String, Value>, String> {
+    let value = serde_json::from_slice(&unb64(encoded, "protected header")?).map_err(|_| "protected header is not JSON".to_string())?;
+    object(Some(&value), "protected header")
+}
+
+fn parse_message(input: &str) -> Result<Message, String> {
+    let input = input.trim();
+    if !input.starts_with('{') {
+        let parts: Vec<&str> = input.split('.').collect();
+        if parts.len() != 5 {
+            return Err(format!("a compact JWE has 5 dot-separated parts, found {}", parts.len()));
+        }
+        return Ok(Message {
+            protected_b64: parts[0].to_string(),
+            protected: protected_header(parts[0])?,
+            unprotected: Map::new(),
+            recipients: vec![Recipient { header: Map::new(), encrypted_key: unb64(parts[1], "encrypted key")? }],
+            aad_b64: None,
+            iv: unb64(parts[2], "IV")?,
+            ciphertext: unb64(parts[3], "ciphertext")?,
+            tag: unb64(parts[4], "tag")?,
+            compact: true,
+        });
+    }
+    let json: Value = serde_json::from_str(input).map_err(|_| "invalid JWE JSON".to_string())?;
+    let text = |name: &str| json[name].as_str().unwrap_or("");
+    let protected_b64 = text("protected").to_string();
+    let protected = if protected_b64.is_empty() { Map::new() } else { protected_header(&protected_b64)? };
+    let recipient = |r: &Value| -> Result<Recipient, String> {
+        Ok(Recipient {
+            header: object(r.get("header"), "recipient header")?,
+            encrypted_key: unb64(r["encrypted_key"].as_str().unwrap_or(""), "encrypted key")?,
+        })
+    };
+    // General serialization lists recipients; the flattened form inlines a single one
+    let recipients = match json["recipients"].as_array() {
+        Some(list) => list.iter().map(recipient).collect::<Result<Vec<_>, _>>()?,
+        None => vec![recipient(&json)?],
+    };
+    Ok(Message {
+        protected_b64,
+        protected,
+        unprotected: object(json.get("unprotected"), "unprotected header")?,
+        recipients,
+        aad_b64: json["aad"].as_str().map(str::to_string),
+        iv: unb64(text("iv"), "IV")?,
+        ciphertext: unb64(text("ciphertext"), "ciphertext")?,
+        tag: unb64(text("tag"), "tag")?,
+        compact: false,
+    })
+}
+
+impl Message {
+    /// Additional authenticated data: the encoded protected header, plus "." and the JSON "aad"
+    fn aad(&self) -> Vec<u8> {
+        match &self.aad_b64 {
+            Some(aad) => format!("{}.{}", self.protected_b64, aad).into_bytes(),
+            None => self.protected_b64.clone().into_bytes(),
+        }
+    }
+
+    /// Protected, shared unprotected and per-recipient headers; names must not repeat
+    fn merged_header(&self, recipient: &Recipient) -> Result<Map<String, Value>, String> {
+        let mut merged = self.protected.clone();
+        for (name, value) in self.unprotected.iter().chain(&recipient.header) {
+            if merged.insert(name.clone(), value.clone()).is_some() {
+                return Err(format!("header parameter \"{}\" appears more than once", name));
+            }
+        }
+        Ok(merged)
+    }
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).map_err(|e| e.to_string())?;
+    encoder.finish().map_err(|e| e.to_string())
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut inflated = Vec::new();
+    flate2::read::DeflateDecoder::new(data)
+        .take(MAX_INFLATED_BYTES + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| "decompression failed".to_string())?;
+    if inflated.len() as u64 > MAX_INFLATED_BYTES {
+        return Err("decompressed payload is too large".to_string());
+    }
+    Ok(inflated)
+}
+
+// ----------------------------------------------------------------------------
+// Public API
+// ----------------------------------------------------------------------------
+
+/// Encrypt to the key(s) in `key` (secret / password, PEM, JWK or JWKS). `header` is an optional
+/// JSON object of extra protected header parameters ("zip": "DEF" compresses, "p2c" sets the
+/// PBES2 iterations, "apu" / "apv" feed ECDH-ES). `serialization` is "compact" (first usable key)
+/// or "json" (general serialization with one recipient per usable key).
+pub fn encrypt(plaintext: &[u8], key: &[u8], alg: &str, enc: &str, header: &str, serialization: &str) -> Result<String, String> {
+    let key_alg = key_algorithm(alg)?;
+    let content = content_algorithm(enc)?;
+    let compact = match serialization {
+        "compact" | "" => true,
+        "json" => false,
+        _ => return Err(format!("unknown JWE serialization: {}", serialization)),
+    };
+    let params = if header.trim().is_empty() {
+        Map::new()
+    } else {
+        match serde_json::from_str(header) {
+            Ok(Value::Object(extra)) => extra,
+            _ => return Err("header must be a JSON object".to_string()),
+        }
+    };
+    let mut keys: Vec<Jwk> = jwt::read_keys(key, key_alg.uses_secret())?
+        .into_iter()
+        .filter(|k| k.alg.as_deref().is_none_or(|a| a == alg) && key_alg.usable(&k.key))
+        .collect();
+    if keys.is_empty() {
+        return Err(format!("key cannot be used with {}", alg));
+    }
+    let direct = matches!(key_alg, KeyAlg::Dir | KeyAlg::EcdhEs(None));
+    if compact || direct {
+        if !compact && keys.len() > 1 {
+            return Err(format!("{} supports a single recipient", alg));
+        }
+        keys.truncate(1);
+    }
+
+    // Per-recipient parameters go into the protected header for compact serialization
+    let mut protected = params.clone();
+    protected.insert("enc".to_string(), json!(enc));
+    let mut cek = random(content.cek_len())?;
+    let mut recipients = Vec::new();
+    for jwk in &keys {
+        let mut recipient_header = Map::new();
+        recipient_header.insert("alg".to_string(), json!(alg));
+        if let Some(kid) = &jwk.kid {
+            recipient_header.insert("kid".to_string(), json!(kid));
+        }
+        let (key_cek, encrypted_key) = wrap_key(key_alg, alg, (enc, content), &jwk.key, &cek, &mut recipient_header, &params)?;
+        cek = key_cek;
+        if compact {
+            protected.extend(recipient_header);
+            recipient_header = Map::new();
+        }
+        recipients.push(Recipient { header: recipient_header, encrypted_key });
+    }
+
+    let protected_b64 = b64(Value::Object(protected.clone()).to_string().as_bytes());
+    let payload = match protected.get("zip").and_then(Value::as_str) {
+        Some("DEF") => deflate(plaintext)?,
+        Some(other) => return Err(format!("unsupported \"zip\": {}", other)),
+        None => plaintext.to_vec(),
+    };
+    let iv = random(content.iv_len())?;
+    let (ciphertext, tag) = seal(content, &cek, &iv, protected_b64.as_bytes(), &payload)?;
+    if compact {
+        return Ok([
+            protected_b64,
+            b64(&recipients[0].encrypted_key),
+            b64(&iv),
+            b64(&ciphertext),
+            b64(&tag),
+        ]
+        .join("."));
+    }
+    let recipients: Vec<Value> = recipients
+        .iter()
+        .map(|r| json!({ "header": r.header, "encrypted_key": b64(&r.encrypted_key) }))
+        .collect();
+    Ok(json!({
+        "protected": protected_b64,
+        "recipients": recipients,
+        "iv": b64(&iv),
+        "ciphertext": b64(&ciphertext),
+        "tag": b64(&tag),
+    })
+    .to_string())
+}
+
+/// Decrypt a compact or JSON (general or flattened) JWE with a secret / password, PEM, JWK or
+/// JWKS. Each recipient is tried with the keys matching its `kid` and `alg`.
+/// Returns the merged header of the recipient that succeeded and the plaintext.
+pub fn decrypt(input: &str, key: &[u8]) -> Result<(Map<String, Value>, Vec<u8>), String> {
+    let message = parse_message(input)?;
+    let aad = message.aad();
+    let mut usable_key = false;
+    let mut key_error = None;
+    for recipient in &message.recipients {
+        let header = message.merged_header(recipient)?;
+        let alg_name = header.get("alg").and_then(Value::as_str).ok_or("header has no \"alg\"")?;
+        let enc_name = header.get("enc").and_then(Value::as_str).ok_or("header has no \"enc\"")?;
+        let (alg, enc) = (key_algorithm(alg_name)?, content_algorithm(enc_name)?);
+        let kid = header.get("kid").and_then(Value::as_str);
+        // A secret suits only some recipients and a PEM / JWK only others: skip, don't abort
+        let keys = match jwt::read_keys(key, alg.uses_secret()) {
+            Ok(keys) => keys,
+            Err(e) => {
+                key_error = Some(e);
+                continue;
+            }
+        };
+        let candidates = keys.iter().filter(|k| {
+            (kid.is_none() || k.kid.is_none() || k.kid.as_deref() == kid)
+                && k.alg.as_deref().is_none_or(|a| a == alg_name)
+                && alg.usable(&k.key)
+                && (!matches!(alg, KeyAlg::EcdhEs(_)) || epk_curve(&header) == recipient_public(&k.key).ok().map(|(c, _)| c))
+        });
+        for candidate in candidates {
+            usable_key = true;
+            let Ok(cek) = unwrap_key(alg, alg_name, (enc_name, enc), &candidate.key, &header, &recipient.encrypted_key) else {
+                continue;
+            };
+            if cek.len() != enc.cek_len() {
+                continue;
+            }
+            if let Ok(plaintext) = open(enc, &cek, &message.iv, &aad, &message.ciphertext, &message.tag) {
+                let plaintext = match message.protected.get("zip").and_then(Value::as_str) {
+                    Some("DEF") => inflate(&plaintext)?,
+                    Some(other) => return Err(format!("unsupported \"zip\": {}", other)),
+                    None => plaintext,
+                };
+                return Ok((header, plaintext));
+            }
+        }
+    }
+    if usable_key {
+        Err(decryption_failed())
+    } else {
+        Err(key_error.unwrap_or_else(|| "no key usable for this JWE".to_string()))
+    }
+}
+
+/// Headers and sizes of a JWE without decrypting it
+pub fn inspect(input: &str) -> Result<String, String> {
+    let message = parse_message(input)?;
+    let recipients: Vec<Value> = message
+        .recipients
+        .iter()
+        .map(|r| json!({ "header": r.header, "encrypted_key_bytes": r.encrypted_key.len() }))
+        .collect();
+    Ok(json!({
+        "serialization": if message.compact { "compact" } else { "json" },
+        "protected": message.protected,
+        "unprotected": message.unprotected,
+        "recipients": recipients,
+        "aad": message.aad_b64,
+        "iv_bytes": message.iv.len(),
+        "ciphertext_bytes": message.ciphertext.len(),
+        "tag_bytes": message.tag.len(),
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::rsa::TEST_PRIVATE_PEM as RSA_PEM;
+
+    // RFC 7516 appendix A.3: A128KW with A128CBC-HS256
+    #[test]
+    fn test_rfc7516_a3() {
+        let token = "eyJhbGciOiJBMTI4S1ciLCJlbmMiOiJBMTI4Q0JDLUhTMjU2In0.6KB707dM9YTIgHtLvtgWQ8mKwboJW3of9locizkDTHzBC2IlrT1oOQ.AxY8DCtDaGlsbGljb3RoZQ.KDlTtXchhZTGufMYmOYGS4HffxPSUrfmqCHXaI9wOGY.U0m_YmjN04DJvceFICbCVQ";
+        let jwk = br#"{"kty":"oct","k":"GawgguFyGrWKav7AX4VKUg"}"#;
+        let (header, plaintext) = decrypt(token, jwk).unwrap();
+        assert_eq!(plaintext, b"Live long and prosper.");
+        assert_eq!(header["alg"], "A128KW");
+        let tampered = token.replace(".U0m_", ".U1m_");
+        assert_eq!(decrypt(&tampered, jwk).unwrap_err(), "decryption failed");
+    }
+
+    const PLAINTEXT: &[u8] = b"The true sign of intelligence is not knowledge but imagination.";
+
+    // Tokens produced with Python cryptography primitives
+    #[test]
+    fn test_python_vectors() {
+        let key: Vec<u8> = (0u8..32).collect();
+        assert_eq!(decrypt("eyJhbGciOiJkaXIiLCJlbmMiOiJBMjU2R0NNIn0..TqJ6AWgLze7k_5A1.omR-AB5AshUm_uUWipVLpBxOuaanf-piH7y74RvKtDyKCtipXXB1_mY7wCw0ZGGXuNfMI_pSRdF01AmfD0UG.lRbSSaWrEMqMQ9_vBBeXpA", &key).unwrap().1, PLAINTEXT);
+        let kek: Vec<u8> = (32u8..64).collect();
+        assert_eq!(decrypt("eyJhbGciOiJBMjU2R0NNS1ciLCJlbmMiOiJBMjU2Q0JDLUhTNTEyIiwiaXYiOiIzaFJJUzVRdW1tTEZaMUV3IiwidGFnIjoiN3diSV9aSzc3SVN1Uk5NR0EyTWd1dyJ9.tNzjAHaE1fC4byLHfknzTbnP2tUvX_njFrlucPVSFOQPkkN0k-GLTiYj0GRTcz5w_6uqWieCskDvUQUAOjeAIw.DmUWLpmBh-UKpXtvaBi2EA.0L842RfpWzwPM8yn4mT1oRnPwz06l1RgOinYdd_s9iGQQBhBCSgMq6u6xAMTkH1AeD2YOHsa8_4bX3ktLQi3_Q.h-LFTBM4hkvRPwF1QDdWEG9gfUJMjmb21jTIb4rUOS4", &kek).unwrap().1, PLAINTEXT);
+        let (header, plaintext) = decrypt("eyJhbGciOiJQQkVTMi1IUzI1NitBMTI4S1ciLCJlbmMiOiJBMTI4R0NNIiwicDJzIjoid3hXR3hTdFpGcXVZbHNoMU5yOWt6dyIsInAyYyI6MTAwMCwiY3R5IjoidGV4dC9wbGFpbiJ9.EZcYu8UlkBfqf6ufA372FOQCz22C5HdI.z8KDbT2yT16pt_jP.qQ3PZctJcNSW57EOZ9-XfOyWmmU2D3wuPznvWKBgx8NMnHNErLC9Id3bg-hU_ppaIjoNFMV8vPawJzgLzYgM.H6SEan7_nzLXh8wW8_x0JQ", b"Thus from my lips, by yours, my sin is purged.").unwrap();
+        assert_eq!((header["p2c"].as_u64(), plaintext.as_slice()), (Some(1000), PLAINTEXT));
+        assert_eq!(decrypt("eyJhbGciOiJSU0EtT0FFUC0yNTYiLCJlbmMiOiJBMjU2R0NNIiwia2lkIjoicjEifQ.UXayey953nxeVRNBtqLyY9UjDu-M2ZP93HeqCx5iFyqR2xvJrBGoQlMM_ett3f-Pr5j1EOX6o9qPi7FJWKnqyifDeflpPsvCaFpWL2312fFOvhPvQ8NYqGCBnlOljsPW5P3t6Mit9156-BCWaRgw7aKSBYa2JTBQIVcEmAXPArI.CE8Whcux67nOcxEr.hneuy-rdYNw3IaOIuBj63xRgW5bOOtXVysZY4JITcC4165S27INj49Yc4OX4Ff7yD-WhXx8Z1ALg9y7KAAfE.OfEU_oZGtlo_AQvbHHoD7w", RSA_PEM.as_bytes()).unwrap().1, PLAINTEXT);
+        let ec = r#"{"kty":"EC","crv":"P-256","x":"y4x-jH5EDwMMBgMBviqqTtLdzBa5enUvQTPkeRPUDcI","y":"-XdkFD8aGZoeqSnqXkqbkxnF9RupEOoGau_3U8p8kmI","d":"DWxD88jjDKzby6fhA-I-g6WGRxljy6F3fCi5Yth4d08"}"#;
+        assert_eq!(decrypt("eyJhbGciOiJFQ0RILUVTIiwiZW5jIjoiQTEyOEdDTSIsImVwayI6eyJrdHkiOiJFQyIsImNydiI6IlAtMjU2IiwieCI6ImpYZ3lGdmVPT2JrSzdyVVMzUWNfSkNpeHhZa3pOZVZsNjZtMzF2aFpJUzgiLCJ5IjoiR0xaT29VMlBYSFcyOWhleTJuMU9YMk9iSG82aW8wcVlUUmlVYVlLMS1CayJ9LCJhcHUiOiJRV3hwWTJVIiwiYXB2IjoiUW05aSJ9..go0I3YmDwY5jyezs.YrBUHX3L-Bz_6VHYN9bq6x9alWOlHvl_GJy4xOPBWfNtN2C9FmPUd1Z7KJiFr6C3lRyBDusq_6Mq-QSkOOrL.Xro6nh3HcDESqT6IRzHdTA", ec.as_bytes()).unwrap().1, PLAINTEXT);
+
+        // General JSON serialization: the first recipient is for another key, with JSON "aad"
+        let json = r#"{"protected":"eyJlbmMiOiJBMjU2Q0JDLUhTNTEyIn0","unprotected":{"cty":"text/plain"},"recipients":[{"header":{"alg":"A128KW","kid":"other"},"encrypted_key":"TnqcvpPNeIRD50pS4e6LJR372J3SAiZU54aRMFqGQ60bXZJr6zFrPsG0YqlxIgAxA1wiFeoHQlM6fV0OJdFd01GHAL82FoRa"},{"header":{"alg":"ECDH-ES+A256KW","kid":"x","epk":{"kty":"OKP","crv":"X25519","x":"7vwKX11nZfhsSnuY78M3Fjyz8oDlKuND-sAP_kzk1VM"}},"encrypted_key":"uyPnsLaUDk64RvLJU-CyTOZ5P_Cs1t57YH5zbpPhkjyNMhIijkybVvTRFDtNJLMPQjLknn9r8v9uW2MSPNKcUm4n01zRDmaj"}],"aad":"ZXh0cmEgZGF0YQ","iv":"mu9fikvIAF9HvReJ2cD-cA","ciphertext":"nOIDQ_40j0SK0njUnd0Eskok-iOSoiSEaYQjhzD6ptm1YUdn044eddbMx7XfNpfMkgPRbThH7_8OgD2Ck5Qe8g","tag":"Co2nMRQj_L8apoNymyvtx-YAWCeUqPSWHSj5cvthlJU"}"#;
+        let jwks = r#"{"keys":[{"kty":"OKP","crv":"X25519","kid":"x","x":"wYqaZ2C4GeiqCPl2UJ5HGyrgYBCYCNHhujMgkLYfTXU","d":"oO7IRCpYkabTweK2ABhV24b1hHbmgAHP2Ef2k0lwxlk"}]}"#;
+        let (header, plaintext) = decrypt(json, jwks.as_bytes()).unwrap();
+        assert_eq!((header["cty"].as_str(), plaintext.as_slice()), (Some("text/plain"), PLAINTEXT));
+        assert_eq!(decrypt(json, ec.as_bytes()).unwrap_err(), "no key usable for this JWE");
+        let info: Value = serde_json::from_str(&inspect(json).unwrap()).unwrap();
+        assert_eq!(info["recipients"][1]["header"]["alg"], "ECDH-ES+A256KW");
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let secret = |n: usize| vec![7u8; n];
+        let cases: [(&str, &str, Vec<u8>); 6] = [
+            ("dir", "A128CBC-HS256", secret(32)),
+            ("A128KW", "A192GCM", secret(16)),
+            ("A192KW", "A192CBC-HS384", secret(24)),
+            ("A128GCMKW", "A128GCM", secret(16)),
+            ("PBES2-HS512+A256KW", "A256GCM", b"password".to_vec()),
+            ("RSA-OAEP", "A128CBC-HS256", RSA_PEM.as_bytes().to_vec()),
+        ];
+        for (alg, enc, key) in cases {
+            let token = encrypt(PLAINTEXT, &key, alg, enc, r#"{"p2c":1000}"#, "compact").unwrap();
+            assert_eq!(decrypt(&token, &key).unwrap().1, PLAINTEXT, "{} {}", alg, enc);
+        }
+        assert!(encrypt(PLAINTEXT, &secret(16), "dir", "A256GCM", "", "compact").is_err());
+        assert!(encrypt(PLAINTEXT, RSA_PEM.as_bytes(), "A128KW", "A128GCM", "", "compact").is_err());
+
+        // ECDH-ES with P-384 and X448 keys, compressed payload
+        let d = ecdh::generate(Curve::P384);
+        let public = ecdh::public_key(Curve::P384, &d, false).unwrap();
+        let p384 = Key::Ec { curve: Curve::P384, private: Some(d), public }.to_jwk(false).unwrap();
+        let x448 = format!(r#"{{"kty":"OKP","crv":"X448","d":"{}"}}"#, b64(&[9u8; 56]));
+        for (key, alg) in [(p384.as_str(), "ECDH-ES+A192KW"), (x448.as_str(), "ECDH-ES")] {
+            let token = encrypt(PLAINTEXT, key.as_bytes(), alg, "A256GCM", r#"{"zip":"DEF"}"#, "compact").unwrap();
+            assert_eq!(decrypt(&token, key.as_bytes()).unwrap().1, PLAINTEXT, "{}", alg);
+        }
+
+        // JSON serialization to every key of a JWKS
+        let jwks = format!(
+            r#"{{"keys":[{{"kty":"oct","kid":"a","k":"{}"}},{{"kty":"oct","kid":"b","k":"{}"}}]}}"#,
+            b64(&secret(32)),
+            b64(&[1u8; 32])
+        );
+        let json = encrypt(PLAINTEXT, jwks.as_bytes(), "A256KW", "A256GCM", "", "json").unwrap();
+        let single_b = format!(r#"{{"kty":"oct","kid":"b","k":"{}"}}"#, b64(&[1u8; 32]));
+        let (header, plaintext) = decrypt(&json, single_b.as_bytes()).unwrap();
+        assert_eq!((header["kid"].as_str(), plaintext.as_slice()), (Some("b"), PLAINTEXT));
+        assert_eq!(decrypt(&json, &secret(16)).unwrap_err(), "decryption failed");
+    }
+
+    // A secret-key recipient before an RSA one must not stop decryption with the RSA key
+    #[test]
+    fn test_mixed_recipients() {
+        let kek = [7u8; 32];
+        let jwk = format!(r#"{{"kty":"oct","k":"{}"}}"#, b64(&kek));
+        let mut message: Value = serde_json::from_str(&encrypt(PLAINTEXT, jwk.as_bytes(), "A256KW", "A256GCM", "", "json").unwrap()).unwrap();
+        let wrapped = unb64(message["recipients"][0]["encrypted_key"].as_str().unwrap(), "").unwrap();
+        let cek = aes_kw::unwrap(&kek, &wrapped).unwrap();
+        let rsa = jwt::read_keys(RSA_PEM.as_bytes(), false).unwrap().remove(0).key;
+        let enc = ("A256GCM", content_algorithm("A256GCM").unwrap());
+        let (_, encrypted_key) = wrap_key(key_algorithm("RSA-OAEP").unwrap(), "RSA-OAEP", enc, &rsa, &cek, &mut Map::new(), &Map::new()).unwrap();
+        message["recipients"].as_array_mut().unwrap().push(json!({ "header": { "alg": "RSA-OAEP" }, "encrypted_key": b64(&encrypted_key) }));
+        let message = message.to_string();
+        let (header, plaintext) = decrypt(&message, RSA_PEM.as_bytes()).unwrap();
+        assert_eq!((header["alg"].as_str(), plaintext.as_slice()), (Some("RSA-OAEP"), PLAINTEXT));
+        assert_eq!(decrypt(&message, &kek).unwrap().1, PLAINTEXT);
+    }
+}
@@ -0,0 +1,257 @@
+// Key-expansion KDFs: HKDF (RFC 5869), TLS 1.3 HKDF-Expand-Label (RFC 8446),
+// NIST SP 800-108 counter mode (HMAC PRF), ANSI X9.63 and ConcatKDF (SP 800-56A).
+use hkdf::Hkdf;
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+
+use crate::hash::hmac;
+
+/// Output length of the named hash in bytes
+pub fn hash_len(hash: &str) -> Result<usize, String> {
+    match hash {
+        "sha1" => Ok(20),
+        "sha224" => Ok(28),
+        "sha256" => Ok(32),
+        "sha384" => Ok(48),
+        "sha512" => Ok(64),
+        _ => Err(format!("unknown hash: {}", hash)),
+    }
+}
+
+/// Block length of the named hash, checking `length` against the HKDF bound of 255 blocks
+fn output_block_len(hash: &str, length: usize) -> Result<usize, String> {
+    let block_len = hash_len(hash)?;
+    if length > 255 * block_len {
+        return Err("output length too large".to_string());
+    }
+    Ok(block_len)
+}
+
+fn digest(hash: &str, parts: &[&[u8]]) -> Result<Vec<u8>, String> {
+    fn run<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+        let mut h = D::new();
+        for p in parts {
+            h.update(p);
+        }
+        h.finalize().to_vec()
+    }
+    match hash {
+        "sha1" => Ok(run::<Sha1>(parts)),
+        "sha224" => Ok(run::<Sha224>(parts)),
+        "sha256" => Ok(run::<Sha256>(parts)),
+        "sha384" => Ok(run::<Sha384>(parts)),
+        "sha512" => Ok(run::<Sha512>(parts)),
+        _ => Err(format!("unknown hash: {}", hash)),
+    }
+}
+
+/// HKDF-Extract: PRK = HMAC-Hash(salt, IKM); an empty salt means HashLen zeros
+pub fn hkdf_extract(hash: &str, salt: &[u8], ikm: &[u8]) -> Result<Vec<u8>, String> {
+    let salt = if salt.is_empty() { None } else { Some(salt) };
+    match hash {
+        "sha256" => Ok(Hkdf::<Sha256>::extract(salt, ikm).0.to_vec()),
+        "sha384" => Ok(Hkdf::<Sha384>::extract(salt, ikm).0.to_vec()),
+        "sha512" => Ok(Hkdf::<Sha512>::extract(salt, ikm).0.to_vec()),
+        _ => Err(format!("unsupported HKDF hash: {}", hash)),
+    }
+}
+
+/// HKDF-Expand: OKM of `length` bytes (at most 255 * HashLen) from a PRK
+pub fn hkdf_expand(hash: &str, prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, String> {
+    let mut okm = vec![0u8; length];
+    let res = match hash {
+        "sha256" => Hkdf::<Sha256>::from_prk(prk)
+            .map_err(|_| "PRK too short".to_string())?
+            .expand(info, &mut okm),
+        "sha384" => Hkdf::<Sha384>::from_prk(prk)
+            .map_err(|_| "PRK too short".to_string())?
+            .expand(info, &mut okm),
+        "sha512" => Hkdf::<Sha512>::from_prk(prk)
+            .map_err(|_| "PRK too short".to_string())?
+            .expand(info, &mut okm),
+        _ => return Err(format!("unsupported HKDF hash: {}", hash)),
+    };
+    res.map_err(|_| "output length too large".to_string())?;
+    Ok(okm)
+}
+
+/// Full HKDF: Extract then Expand
+pub fn hkdf(hash: &str, ikm: &[u8], salt: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, String> {
+    let prk = hkdf_extract(hash, salt, ikm)?;
+    hkdf_expand(hash, &prk, info, length)
+}
+
+/// TLS 1.3 HKDF-Expand-Label(Secret, Label, Context, Length); "tls13 " is prepended to the label
+pub fn tls13_expand_label(
+    hash: &str,
+    secret: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    let full_label = format!("tls13 {}", label);
+    if length > u16::MAX as usize || full_label.len() > 255 || context.len() > 255 {
+        return Err("HkdfLabel field too long".to_string());
+    }
+    let mut info = Vec::with_capacity(4 + full_label.len() + context.len());
+    info.extend_from_slice(&(length as u16).to_be_bytes());
+    info.push(full_label.len() as u8);
+    info.extend_from_slice(full_label.as_bytes());
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+    hkdf_expand(hash, secret, &info, length)
+}
+
+/// TLS 1.3 Derive-Secret(Secret, Label, Messages) = Expand-Label(Secret, Label, Hash(Messages), HashLen)
+pub fn tls13_derive_secret(hash: &str, secret: &[u8], label: &str, messages: &[u8]) -> Result<Vec<u8>, String> {
+    let transcript = digest(hash, &[messages])?;
+    tls13_expand_label(hash, secret, label, &transcript, transcript.len())
+}
+
+/// NIST SP 800-108 KDF in counter mode with an HMAC PRF, at most 255 * HashLen bytes.
+/// Each block is HMAC(key, [i]_32 || Label || 0x00 || Context || [L]_32), L in bits.
+pub fn sp800_108_counter(
+    hash: &str,
+    key: &[u8],
+    label: &[u8],
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, String> {
+    let block_len = output_block_len(hash, length)?;
+    let bits = length
+        .checked_mul(8)
+        .and_then(|bits| u32::try_from(bits).ok())
+        .ok_or_else(|| "output length too large".to_string())?;
+    let mut fixed = Vec::with_capacity(label.len() + context.len() + 5);
+    fixed.extend_from_slice(label);
+    fixed.push(0x00);
+    fixed.extend_from_slice(context);
+    fixed.extend_from_slice(&bits.to_be_bytes());
+
+    let mut out = Vec::with_capacity(length.checked_add(block_len).ok_or_else(|| "output length too large".to_string())?);
+    let mut counter: u32 = 1;
+    while out.len() < length {
+        let mut input = counter.to_be_bytes().to_vec();
+        input.extend_from_slice(&fixed);
+        out.extend_from_slice(&hmac::mac(hash, key, &input)?);
+        counter += 1;
+    }
+    out.truncate(length);
+    Ok(out)
+}
+
+/// ANSI X9.63 KDF (at most 255 * HashLen bytes): blocks are Hash(Z || counter_32 || SharedInfo), counter from 1
+pub fn x963(hash: &str, shared_secret: &[u8], shared_info: &[u8], length: usize) -> Result<Vec<u8>, String> {
+    counter_kdf(hash, length, |counter| digest(hash, &[shared_secret, &counter, shared_info]))
+}
+
+/// ConcatKDF (NIST SP 800-56A single-step, hash variant, at most 255 * HashLen bytes): blocks are Hash(counter_32 || Z || OtherInfo)
+pub fn concat_kdf(hash: &str, z: &[u8], other_info: &[u8], length: usize) -> Result<Vec<u8>, String> {
+    counter_kdf(hash, length, |counter| digest(hash, &[&counter, z, other_info]))
+}
+
+fn counter_kdf(
+    hash: &str,
+    length: usize,
+    block: impl Fn([u8; 4]) -> Result<Vec<u8>, String>,
+) -> Result<Vec<u8>, String> {
+    let block_len = output_block_len(hash, length)?;
+    let mut out = Vec::with_capacity(length.checked_add(block_len).ok_or_else(|| "output length too large".to_string())?);
+    let mut counter: u32 = 1;
+    while out.len() < length {
+        out.extend_from_slice(&block(counter.to_be_bytes())?);
+        counter += 1;
+    }
+    out.truncate(length);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 5869 test case 1
+    #[test]
+    fn test_hkdf_rfc5869() {
+        let ikm = [0x0bu8; 22];
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+        let prk = hkdf_extract("sha256", &salt, &ikm).unwrap();
+        assert_eq!(
+            hex::encode(&prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+        assert_eq!(
+            hex::encode(hkdf_expand("sha256", &prk, &info, 42).unwrap()),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert!(hkdf_expand("sha256", &prk, &info, 255 * 32 + 1).is_err());
+    }
+
+    #[test]
+    fn test_hkdf_sha384_sha512() {
+        assert_eq!(
+            hex::encode(hkdf("sha384", b"ikm", b"salt", b"info", 60).unwrap()),
+            "8a4904829f7acb5fe62bfbce3ed1a2d9428bdcba65d4db11e7471f3b7ab9eaffb058cfdad0c509f3ccd90685666fa8b2b2f6091d3ee2045886335d2c"
+        );
+        assert_eq!(
+            hex::encode(hkdf("sha512", b"ikm", b"", b"", 20).unwrap()),
+            "ad3937e102645867de089fceb12443da092fd7dd"
+        );
+    }
+
+    // RFC 8448 simple 1-RTT handshake: early secret and "derived" secret
+    #[test]
+    fn test_tls13_expand_label() {
+        let early = hkdf_extract("sha256", &[], &[0u8; 32]).unwrap();
+        assert_eq!(
+            hex::encode(&early),
+            "33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a"
+        );
+        assert_eq!(
+            hex::encode(tls13_derive_secret("sha256", &early, "derived", b"").unwrap()),
+            "6f2615a108c702c5678f54fc9dbab69716c076189c48250cebeac3576c3611ba"
+        );
+    }
+
+    #[test]
+    fn test_sp800_108_counter() {
+        let key: Vec<u8> = (0u8..32).collect();
+        assert_eq!(
+            hex::encode(sp800_108_counter("sha256", &key, b"label", b"context", 42).unwrap()),
+            "b9cd5f6323f01f4680650855f1ebea9b4c54c08131b506fc28c856364a38a2f4fb680c12ea51696887d9"
+        );
+        assert_eq!(
+            hex::encode(sp800_108_counter("sha512", b"key", b"", b"ctx", 80).unwrap()),
+            "1d75783a40d70dcd99b11058e555829613d9b97304637499186d085e5bab030db1acb2516ae66dbd8aa0b6a818e77b1d34535ef498f5122c408f74711fe8fb122d245eda7283561a308676a36410da68"
+        );
+        assert_eq!(sp800_108_counter("sha256", &key, b"", b"", usize::MAX / 4).unwrap_err(), "output length too large");
+    }
+
+    #[test]
+    fn test_x963_and_concat() {
+        let z: Vec<u8> = (0u8..32).collect();
+        assert_eq!(
+            hex::encode(x963("sha256", &z, b"shared info", 40).unwrap()),
+            "a2ba25392f91013464ed530b7260876d7d85630fd24cd7894a49600ab4c7a8c33354555992b455eb"
+        );
+        assert_eq!(
+            hex::encode(x963("sha384", b"z", b"", 64).unwrap()),
+            "db797e7f3b087f0b24a94e5fb89d38261ac34f3e136bd9b6c9afbb4378afca89faf4b1509c825773554cca208e7a63c95d69ea6b464d1e898e455d322ab265f7"
+        );
+        assert_eq!(
+            hex::encode(concat_kdf("sha256", &z[..16], b"other", 48).unwrap()),
+            "54fd6473ab4119f7d976907710fbcc6d6024f121416c5600154b167d6798b97ab6890cd512cec405fd072544a90e0c32"
+        );
+    }
+
+    // Requested lengths are bounded before anything is allocated
+    #[test]
+    fn test_output_length_limit() {
+        assert_eq!(x963("sha256", b"z", b"", 255 * 32).unwrap().len(), 255 * 32);
+        assert_eq!(x963("sha256", b"z", b"", 255 * 32 + 1).unwrap_err(), "output length too large");
+        assert_eq!(concat_kdf("sha512", b"z", b"", usize::MAX).unwrap_err(), "output length too large");
+        assert_eq!(sp800_108_counter("sha1", b"key", b"", b"", 255 * 20 + 1).unwrap_err(), "output length too large");
+        assert_eq!(sp800_108_counter("sha256", b"key", b"", b"", usize::MAX).unwrap_err(), "output length too large");
+    }
+}
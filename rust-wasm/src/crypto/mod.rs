@@ -11,6 +11,11 @@ pub mod aes256_ecb;
 pub mod aes128_ctr;
 pub mod aes192_ctr;
 pub mod aes256_ctr;
+// Block cipher registry and generic modes/paddings for every block cipher
+pub mod block_cipher;
+pub mod block_modes;
+// Configurable CTR counter layout (nonce/counter split, endianness, seeking)
+pub mod ctr_layout;
 // DES/3DES
 pub mod des_cbc;
 pub mod des_ecb;
@@ -22,5 +27,50 @@ pub mod tdes_ctr;
 pub mod rc4_cipher;
 // ChaCha20 (modern stream cipher)
 pub mod chacha20_cipher;
+// Salsa20/20, Salsa20/12, Salsa20/8, XSalsa20
+pub mod salsa20_cipher;
+// NaCl / libsodium: crypto_secretbox, crypto_box, crypto_box_seal
+pub mod nacl_box;
+// libsodium crypto_secretstream_xchacha20poly1305
+pub mod secretstream;
+// Segmented AEAD for chunked encryption of large streams (STREAM construction)
+pub mod stream_aead;
+// OpenSSL `enc` compatible "Salted__" format
+pub mod openssl_enc;
+// age v1 file encryption (X25519 and scrypt recipients)
+pub mod age;
+// OpenPGP symmetric messages (SKESK + SEIPD v1/v2) and ASCII armor
+pub mod openpgp;
+// Fernet and Branca tokens
+pub mod fernet;
+pub mod branca;
 // Key derivation
-pub mod pbkdf2_key;
\ No newline at end of file
+pub mod pbkdf2_key;
+// HKDF, TLS 1.3 Expand-Label, SP 800-108, X9.63 and ConcatKDF
+pub mod kdf;
+// EdDSA signatures (Ed25519 / Ed448) and Curve448 helpers
+pub mod curve448;
+pub mod eddsa;
+// Key agreement: X25519 / X448 and ECDH over NIST curves and secp256k1
+pub mod ecdh;
+// ECDSA signatures (RFC 6979) and secp256k1 public key recovery
+pub mod ecdsa;
+// RSA keygen, OAEP / PKCS#1 v1.5 encryption, PSS / PKCS#1 v1.5 signatures
+pub mod rsa;
+// AES key wrap (RFC 3394 / RFC 5649)
+pub mod aes_kw;
+// JSON Web Tokens (JWS): decode, verify, sign, JWK / JWKS
+pub mod jwt;
+// JSON Web Encryption (JWE) compact and JSON serialization
+pub mod jwe;
+// PASETO v3 / v4 local and public tokens, PASERK keys
+pub mod paseto;
+// Shamir secret sharing over GF(256): raw hex / Base64 shares and SLIP-39 mnemonics
+pub mod shamir;
+// BIP-39 mnemonics and BIP-32 / BIP-44 hierarchical deterministic keys
+pub mod bip39;
+pub mod bip32;
+// Bitcoin / Ethereum addresses, WIF keys and Ethereum ABI selectors
+pub mod address;
+// HOTP / TOTP one-time passwords and otpauth:// URIs
+pub mod otp;
@@ -0,0 +1,173 @@
+// NaCl / libsodium authenticated encryption
+// crypto_secretbox_easy  - XSalsa20-Poly1305 with a shared 32-byte key, output [MAC16][CIPHERTEXT]
+// crypto_box_easy        - X25519 + XSalsa20-Poly1305 between two keypairs, same output layout
+// crypto_box_seal        - anonymous sender: [EPHEMERAL_PK32][MAC16][CIPHERTEXT]
+use crypto_box::aead::{Aead, OsRng};
+use crypto_box::{PublicKey, SalsaBox, SecretKey};
+use crypto_secretbox::{KeyInit, XSalsa20Poly1305};
+use getrandom::getrandom;
+
+pub const KEY_BYTES: usize = 32;
+pub const NONCE_BYTES: usize = 24;
+pub const MAC_BYTES: usize = 16;
+pub const SEAL_BYTES: usize = 48;
+
+pub fn random_nonce() -> [u8; NONCE_BYTES] {
+    let mut nonce = [0u8; NONCE_BYTES];
+    getrandom(&mut nonce).expect("random nonce generation failed");
+    nonce
+}
+
+fn check_len(what: &str, bytes: &[u8], expected: usize) -> Result<(), String> {
+    if bytes.len() != expected {
+        return Err(format!("{} must be {} bytes", what, expected));
+    }
+    Ok(())
+}
+
+fn secret_key(bytes: &[u8]) -> Result<SecretKey, String> {
+    SecretKey::from_slice(bytes).map_err(|_| "secret key must be 32 bytes".to_string())
+}
+
+fn public_key(bytes: &[u8]) -> Result<PublicKey, String> {
+    PublicKey::from_slice(bytes).map_err(|_| "public key must be 32 bytes".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// crypto_secretbox
+// ---------------------------------------------------------------------------
+
+pub fn secretbox_encrypt(message: &[u8], nonce: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+    check_len("key", key, KEY_BYTES)?;
+    check_len("nonce", nonce, NONCE_BYTES)?;
+    XSalsa20Poly1305::new(key.into())
+        .encrypt(nonce.into(), message)
+        .map_err(|_| "encryption failed".to_string())
+}
+
+pub fn secretbox_decrypt(boxed: &[u8], nonce: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+    check_len("key", key, KEY_BYTES)?;
+    check_len("nonce", nonce, NONCE_BYTES)?;
+    if boxed.len() < MAC_BYTES {
+        return Err("ciphertext too short".to_string());
+    }
+    XSalsa20Poly1305::new(key.into())
+        .decrypt(nonce.into(), boxed)
+        .map_err(|_| "authentication failed".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// crypto_box
+// ---------------------------------------------------------------------------
+
+// Returns [SECRET_KEY32][PUBLIC_KEY32]
+pub fn box_keypair() -> Vec<u8> {
+    let sk = SecretKey::generate(&mut OsRng);
+    let mut out = Vec::with_capacity(64);
+    out.extend_from_slice(&sk.to_bytes());
+    out.extend_from_slice(sk.public_key().as_bytes());
+    out
+}
+
+// crypto_scalarmult_base: derive the public key of a secret key
+pub fn box_public_key(secret: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(secret_key(secret)?.public_key().as_bytes().to_vec())
+}
+
+pub fn box_encrypt(message: &[u8], nonce: &[u8], recipient_pk: &[u8], sender_sk: &[u8]) -> Result<Vec<u8>, String> {
+    check_len("nonce", nonce, NONCE_BYTES)?;
+    SalsaBox::new(&public_key(recipient_pk)?, &secret_key(sender_sk)?)
+        .encrypt(nonce.into(), message)
+        .map_err(|_| "encryption failed".to_string())
+}
+
+pub fn box_decrypt(boxed: &[u8], nonce: &[u8], sender_pk: &[u8], recipient_sk: &[u8]) -> Result<Vec<u8>, String> {
+    check_len("nonce", nonce, NONCE_BYTES)?;
+    if boxed.len() < MAC_BYTES {
+        return Err("ciphertext too short".to_string());
+    }
+    SalsaBox::new(&public_key(sender_pk)?, &secret_key(recipient_sk)?)
+        .decrypt(nonce.into(), boxed)
+        .map_err(|_| "authentication failed".to_string())
+}
+
+// ---------------------------------------------------------------------------
+// crypto_box_seal (anonymous sender)
+// ---------------------------------------------------------------------------
+
+pub fn box_seal(message: &[u8], recipient_pk: &[u8]) -> Result<Vec<u8>, String> {
+    public_key(recipient_pk)?
+        .seal(&mut OsRng, message)
+        .map_err(|_| "encryption failed".to_string())
+}
+
+pub fn box_seal_open(sealed: &[u8], recipient_sk: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < SEAL_BYTES {
+        return Err("ciphertext too short".to_string());
+    }
+    secret_key(recipient_sk)?
+        .unseal(sealed)
+        .map_err(|_| "authentication failed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Vec<u8> {
+        (0u8..32).collect()
+    }
+
+    fn nonce() -> Vec<u8> {
+        (0u8..24).collect()
+    }
+
+    // Vectors produced with libsodium crypto_secretbox_easy / crypto_box_easy / crypto_box_seal
+    #[test]
+    fn test_secretbox_matches_libsodium() {
+        let boxed = secretbox_encrypt(b"libsodium secretbox", &nonce(), &key()).unwrap();
+        assert_eq!(
+            hex::encode(&boxed),
+            "9a227021ed180473711cc33bf659724432965a3ca8aecb65d61cfc5b0bfd2fe330ca3e"
+        );
+        assert_eq!(secretbox_decrypt(&boxed, &nonce(), &key()).unwrap(), b"libsodium secretbox");
+    }
+
+    #[test]
+    fn test_secretbox_rejects_tampering() {
+        let mut boxed = secretbox_encrypt(b"message", &nonce(), &key()).unwrap();
+        boxed[20] ^= 1;
+        assert_eq!(secretbox_decrypt(&boxed, &nonce(), &key()).unwrap_err(), "authentication failed");
+    }
+
+    #[test]
+    fn test_box_matches_libsodium() {
+        let sk1 = [1u8; 32];
+        let sk2 = [2u8; 32];
+        let pk1 = box_public_key(&sk1).unwrap();
+        let pk2 = box_public_key(&sk2).unwrap();
+        assert_eq!(hex::encode(&pk1), "a4e09292b651c278b9772c569f5fa9bb13d906b46ab68c9df9dc2b4409f8a209");
+
+        let boxed = box_encrypt(b"libsodium box", &nonce(), &pk2, &sk1).unwrap();
+        assert_eq!(hex::encode(&boxed), "047db2a076439a2f065d11fa81974ab78ea6e1b0f5558f43d4761aa8b4");
+        assert_eq!(box_decrypt(&boxed, &nonce(), &pk1, &sk2).unwrap(), b"libsodium box");
+    }
+
+    #[test]
+    fn test_open_libsodium_sealed_box() {
+        let sealed = hex::decode(
+            "35eda4a69366deb3d62fa056f0287f5c2b52afc881a2f6c2ebf696f18e764f521fb48f7744f483670e8c9270d48aab23a20ef16b9c5b7e975f4416e30b",
+        )
+        .unwrap();
+        assert_eq!(box_seal_open(&sealed, &[2u8; 32]).unwrap(), b"libsodium box");
+    }
+
+    #[test]
+    fn test_sealed_box_roundtrip() {
+        let pair = box_keypair();
+        let sealed = box_seal(b"anonymous", &pair[32..]).unwrap();
+        assert_eq!(sealed.len(), SEAL_BYTES + 9);
+        assert_eq!(box_seal_open(&sealed, &pair[..32]).unwrap(), b"anonymous");
+        assert!(box_seal_open(&sealed, &[7u8; 32]).is_err());
+    }
+}
@@ -0,0 +1,757 @@
+// OpenPGP symmetric encryption (RFC 4880 / RFC 9580)
+//
+// Writing:  [SKESK v4][SEIPD v1]  - iterated+salted S2K key used directly, CFB with MDC (`gpg --symmetric`)
+//           [SKESK v6][SEIPD v2]  - HKDF-wrapped random session key, chunked AES-OCB, AES-EAX or AES-GCM
+// Reading:  either of the above, with old- or new-format packet headers (including partial lengths),
+//           and an inner literal packet that may be wrapped in a ZIP / ZLIB compressed packet.
+// Armor:    "PGP MESSAGE" block with 64-column Base64 and a CRC-24 checksum line.
+use super::block_cipher;
+use super::block_modes;
+use crate::steganography::aes_gcm_cipher;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use eax::aead::consts::U15;
+use eax::aead::{Aead, KeyInit, Payload};
+use eax::Eax;
+use getrandom::getrandom;
+use hkdf::Hkdf;
+use ocb3::Ocb3;
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use std::io::Read;
+
+const TAG_SKESK: u8 = 3;
+const TAG_COMPRESSED: u8 = 8;
+const TAG_MARKER: u8 = 10;
+const TAG_LITERAL: u8 = 11;
+const TAG_SEIPD: u8 = 18;
+const TAG_MDC: u8 = 19;
+const TAG_PADDING: u8 = 21;
+
+const AEAD_EAX: u8 = 1;
+const AEAD_OCB: u8 = 2;
+const AEAD_GCM: u8 = 3;
+const AEAD_TAG_BYTES: usize = 16;
+
+// S2K parameters used when encrypting: SHA-256, coded count 0xE0 (16 MiB of hashing)
+const S2K_HASH: u8 = 8;
+pub const S2K_COUNT: u8 = 0xE0;
+// Chunk size octet for SEIPD v2: 1 << (10 + 6) = 64 KiB
+const CHUNK_SIZE_OCTET: u8 = 10;
+
+const ARMOR_BEGIN: &str = "-----BEGIN PGP MESSAGE-----";
+const ARMOR_END: &str = "-----END PGP MESSAGE-----";
+
+// ---------------------------------------------------------------------------
+// Algorithms
+// ---------------------------------------------------------------------------
+
+// Symmetric algorithm ID -> (block_cipher name, key length)
+fn symmetric(id: u8) -> Result<(&'static str, usize), String> {
+    match id {
+        1 => Ok(("idea", 16)),
+        2 => Ok(("tdes", 24)),
+        3 => Ok(("cast5", 16)),
+        4 => Ok(("blowfish", 16)),
+        7 => Ok(("aes128", 16)),
+        8 => Ok(("aes192", 24)),
+        9 => Ok(("aes256", 32)),
+        10 => Ok(("twofish", 32)),
+        11 => Ok(("camellia128", 16)),
+        12 => Ok(("camellia192", 24)),
+        13 => Ok(("camellia256", 32)),
+        _ => Err(format!("unsupported symmetric algorithm {}", id)),
+    }
+}
+
+fn symmetric_id(name: &str) -> Result<u8, String> {
+    match name {
+        "idea" => Ok(1),
+        "tdes" => Ok(2),
+        "cast5" => Ok(3),
+        "blowfish" => Ok(4),
+        "aes128" => Ok(7),
+        "aes192" => Ok(8),
+        "aes256" => Ok(9),
+        "twofish" => Ok(10),
+        "camellia128" => Ok(11),
+        "camellia192" => Ok(12),
+        "camellia256" => Ok(13),
+        _ => Err(format!("unsupported cipher: {}", name)),
+    }
+}
+
+fn aead_nonce_len(aead: u8) -> Result<usize, String> {
+    match aead {
+        AEAD_EAX => Ok(16),
+        AEAD_GCM => Ok(12),
+        AEAD_OCB => Ok(15),
+        _ => Err(format!("unsupported AEAD algorithm {}", aead)),
+    }
+}
+
+// EAX (16-byte nonce) or OCB (15-byte nonce) with a 16-byte tag
+macro_rules! aead_with {
+    ($aead:expr, $cipher:ty, $key:expr, $nonce:expr, $payload:expr, $op:ident) => {
+        match $aead {
+            AEAD_EAX => Eax::<$cipher>::new($key.into()).$op($nonce.into(), $payload),
+            _ => Ocb3::<$cipher, U15>::new($key.into()).$op($nonce.into(), $payload),
+        }
+    };
+}
+
+// AEAD encryption or decryption for SEIPD v2 and SKESK v6 (AES only)
+fn aead_apply(sym: u8, aead: u8, key: &[u8], nonce: &[u8], msg: &[u8], ad: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    if nonce.len() != aead_nonce_len(aead)? {
+        return Err("invalid AEAD nonce".to_string());
+    }
+    if !(7..=9).contains(&sym) {
+        return Err("AEAD encryption requires AES".to_string());
+    }
+    if aead == AEAD_GCM {
+        return if encrypt { aes_gcm_cipher::seal(key, nonce, ad, msg) } else { aes_gcm_cipher::open(key, nonce, ad, msg) };
+    }
+    let payload = Payload { msg, aad: ad };
+    let result = match (sym, encrypt) {
+        (7, true) => aead_with!(aead, aes::Aes128, key, nonce, payload, encrypt),
+        (8, true) => aead_with!(aead, aes::Aes192, key, nonce, payload, encrypt),
+        (9, true) => aead_with!(aead, aes::Aes256, key, nonce, payload, encrypt),
+        (7, false) => aead_with!(aead, aes::Aes128, key, nonce, payload, decrypt),
+        (8, false) => aead_with!(aead, aes::Aes192, key, nonce, payload, decrypt),
+        _ => aead_with!(aead, aes::Aes256, key, nonce, payload, decrypt),
+    };
+    result.map_err(|_| "authentication failed".to_string())
+}
+
+fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8], len: usize) -> Vec<u8> {
+    let mut out = vec![0u8; len];
+    Hkdf::<Sha256>::new(Some(salt), ikm).expand(info, &mut out).expect("valid HKDF length");
+    out
+}
+
+// ---------------------------------------------------------------------------
+// String-to-key
+// ---------------------------------------------------------------------------
+
+struct S2k {
+    kind: u8,
+    hash: u8,
+    salt: Vec<u8>,
+    count: u8,
+}
+
+impl S2k {
+    fn parse(data: &[u8]) -> Result<(S2k, usize), String> {
+        let truncated = || "truncated S2K specifier".to_string();
+        let kind = *data.first().ok_or_else(truncated)?;
+        let hash = *data.get(1).ok_or_else(truncated)?;
+        match kind {
+            0 => Ok((S2k { kind, hash, salt: Vec::new(), count: 0 }, 2)),
+            1 => Ok((S2k { kind, hash, salt: data.get(2..10).ok_or_else(truncated)?.to_vec(), count: 0 }, 10)),
+            3 => {
+                let salt = data.get(2..10).ok_or_else(truncated)?.to_vec();
+                Ok((S2k { kind, hash, salt, count: *data.get(10).ok_or_else(truncated)? }, 11))
+            }
+            _ => Err(format!("unsupported S2K type {}", kind)),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![self.kind, self.hash];
+        out.extend_from_slice(&self.salt);
+        if self.kind == 3 {
+            out.push(self.count);
+        }
+        out
+    }
+
+    fn derive(&self, passphrase: &[u8], key_len: usize) -> Result<Vec<u8>, String> {
+        match self.hash {
+            2 => Ok(self.derive_with::<Sha1>(passphrase, key_len)),
+            8 => Ok(self.derive_with::<Sha256>(passphrase, key_len)),
+            9 => Ok(self.derive_with::<Sha384>(passphrase, key_len)),
+            10 => Ok(self.derive_with::<Sha512>(passphrase, key_len)),
+            11 => Ok(self.derive_with::<Sha224>(passphrase, key_len)),
+            _ => Err(format!("unsupported S2K hash algorithm {}", self.hash)),
+        }
+    }
+
+    // Each hash context is preloaded with one more zero octet than the previous one
+    fn derive_with<D: Digest>(&self, passphrase: &[u8], key_len: usize) -> Vec<u8> {
+        let mut input = self.salt.clone();
+        input.extend_from_slice(passphrase);
+        let total = if self.kind == 3 {
+            let coded = (16 + (self.count as usize & 15)) << ((self.count as usize >> 4) + 6);
+            coded.max(input.len())
+        } else {
+            input.len()
+        };
+
+        let mut key = Vec::with_capacity(key_len + 64);
+        let mut preload = 0;
+        while key.len() < key_len {
+            let mut hasher = D::new();
+            hasher.update(vec![0u8; preload]);
+            let mut remaining = total;
+            while remaining > 0 {
+                let take = remaining.min(input.len());
+                hasher.update(&input[..take]);
+                remaining -= take;
+            }
+            key.extend_from_slice(&hasher.finalize());
+            preload += 1;
+        }
+        key.truncate(key_len);
+        key
+    }
+}
+
+fn random_vec(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    getrandom(&mut bytes).expect("random generation failed");
+    bytes
+}
+
+// ---------------------------------------------------------------------------
+// Packet framing
+// ---------------------------------------------------------------------------
+
+struct Packet {
+    tag: u8,
+    body: Vec<u8>,
+}
+
+fn write_packet(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    out.push(0xC0 | tag);
+    let len = body.len();
+    if len < 192 {
+        out.push(len as u8);
+    } else if len < 8384 {
+        let v = len - 192;
+        out.push((v >> 8) as u8 + 192);
+        out.push(v as u8);
+    } else {
+        out.push(0xFF);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(body);
+}
+
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], String> {
+    let slice = data.get(*pos..*pos + len).ok_or_else(|| "truncated packet".to_string())?;
+    *pos += len;
+    Ok(slice)
+}
+
+fn read_be(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |acc, &b| (acc << 8) | b as usize)
+}
+
+fn parse_packets(data: &[u8]) -> Result<Vec<Packet>, String> {
+    let mut packets = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let ctb = take(data, &mut pos, 1)?[0];
+        if ctb & 0x80 == 0 {
+            return Err("invalid packet header".to_string());
+        }
+        let mut body = Vec::new();
+        let tag;
+        if ctb & 0x40 == 0 {
+            // Old format: length type in the low two bits
+            tag = (ctb >> 2) & 0x0F;
+            let len = match ctb & 3 {
+                0 => read_be(take(data, &mut pos, 1)?),
+                1 => read_be(take(data, &mut pos, 2)?),
+                2 => read_be(take(data, &mut pos, 4)?),
+                _ => data.len() - pos,
+            };
+            body.extend_from_slice(take(data, &mut pos, len)?);
+        } else {
+            tag = ctb & 0x3F;
+            loop {
+                let first = take(data, &mut pos, 1)?[0] as usize;
+                match first {
+                    0..=191 => {
+                        body.extend_from_slice(take(data, &mut pos, first)?);
+                        break;
+                    }
+                    192..=223 => {
+                        let len = ((first - 192) << 8) + take(data, &mut pos, 1)?[0] as usize + 192;
+                        body.extend_from_slice(take(data, &mut pos, len)?);
+                        break;
+                    }
+                    224..=254 => body.extend_from_slice(take(data, &mut pos, 1 << (first & 0x1F))?),
+                    _ => {
+                        let len = read_be(take(data, &mut pos, 4)?);
+                        body.extend_from_slice(take(data, &mut pos, len)?);
+                        break;
+                    }
+                }
+            }
+        }
+        packets.push(Packet { tag, body });
+    }
+    Ok(packets)
+}
+
+// ---------------------------------------------------------------------------
+// Armor
+// ---------------------------------------------------------------------------
+
+pub fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xB704CE;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= 0x1864CFB;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+pub fn armor(data: &[u8]) -> String {
+    let mut out = format!("{}\n\n", ARMOR_BEGIN);
+    for line in STANDARD.encode(data).as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        out.push('\n');
+    }
+    out.push('=');
+    out.push_str(&STANDARD.encode(&crc24(data).to_be_bytes()[1..]));
+    out.push('\n');
+    out.push_str(ARMOR_END);
+    out.push('\n');
+    out
+}
+
+pub fn dearmor(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || "invalid armor".to_string();
+    let mut lines = text.lines().map(str::trim_end).skip_while(|l| *l != ARMOR_BEGIN);
+    lines.next().ok_or_else(invalid)?;
+    // Armor headers ("Key: Value") end at the first blank line
+    let mut lines = lines.skip_while(|l| !l.is_empty()).skip(1);
+
+    let mut encoded = String::new();
+    let mut checksum = None;
+    for line in lines.by_ref() {
+        if line == ARMOR_END {
+            break;
+        }
+        if let Some(crc) = line.strip_prefix('=') {
+            checksum = Some(STANDARD.decode(crc).map_err(|_| invalid())?);
+            continue;
+        }
+        encoded.push_str(line.trim());
+    }
+    let data = STANDARD.decode(encoded).map_err(|_| invalid())?;
+    if let Some(crc) = checksum
+        && crc != crc24(&data).to_be_bytes()[1..]
+    {
+        return Err("armor checksum mismatch".to_string());
+    }
+    Ok(data)
+}
+
+fn binary(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.first().is_some_and(|b| b & 0x80 != 0) {
+        return Ok(data.to_vec());
+    }
+    dearmor(std::str::from_utf8(data).map_err(|_| "invalid armor".to_string())?)
+}
+
+// ---------------------------------------------------------------------------
+// Encryption
+// ---------------------------------------------------------------------------
+
+// Literal data packet: binary format, no file name, zero timestamp
+fn literal_packet(data: &[u8]) -> Vec<u8> {
+    let mut body = vec![b'b', 0, 0, 0, 0, 0];
+    body.extend_from_slice(data);
+    let mut out = Vec::new();
+    write_packet(&mut out, TAG_LITERAL, &body);
+    out
+}
+
+fn seipd_v1(sym: u8, session_key: &[u8], plaintext_packets: &[u8]) -> Result<Vec<u8>, String> {
+    let (cipher, _) = symmetric(sym)?;
+    let bs = block_cipher::block_size(cipher)?;
+    let mut data = random_vec(bs);
+    data.extend_from_within(bs - 2..);
+    data.extend_from_slice(plaintext_packets);
+    data.extend_from_slice(&[0xC0 | TAG_MDC, 20]);
+    let mdc = Sha1::digest(&data);
+    data.extend_from_slice(&mdc);
+
+    let mut body = vec![1];
+    body.extend_from_slice(&block_modes::encrypt(cipher, "cfb", "none", session_key, &vec![0u8; bs], &data)?);
+    Ok(body)
+}
+
+fn seipd_v2(sym: u8, aead: u8, session_key: &[u8], plaintext_packets: &[u8]) -> Result<Vec<u8>, String> {
+    let salt = random_vec(32);
+    let mut body = vec![2, sym, aead, CHUNK_SIZE_OCTET];
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&seipd_v2_process(&body.clone(), session_key, plaintext_packets, true)?);
+    Ok(body)
+}
+
+// Shared chunk loop for SEIPD v2; `header` is [2][SYM][AEAD][CHUNK][SALT32]
+fn seipd_v2_process(header: &[u8], session_key: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    let (sym, aead, chunk_octet, salt) = (header[1], header[2], header[3], &header[4..36]);
+    if chunk_octet > 16 {
+        return Err("invalid chunk size".to_string());
+    }
+    let (_, key_len) = symmetric(sym)?;
+    let nonce_len = aead_nonce_len(aead)?;
+    let ad = [0xC0 | TAG_SEIPD, 2, sym, aead, chunk_octet];
+    let derived = hkdf_sha256(session_key, salt, &ad, key_len + nonce_len - 8);
+    let (key, iv) = derived.split_at(key_len);
+    let nonce = |index: u64| {
+        let mut nonce = iv.to_vec();
+        nonce.extend_from_slice(&index.to_be_bytes());
+        nonce
+    };
+
+    let chunk_size = 1usize << (chunk_octet + 6);
+    let input_chunk = if encrypt { chunk_size } else { chunk_size + AEAD_TAG_BYTES };
+    let body = if encrypt {
+        data
+    } else {
+        data.len()
+            .checked_sub(AEAD_TAG_BYTES)
+            .map(|end| &data[..end])
+            .ok_or_else(|| "truncated encrypted data".to_string())?
+    };
+
+    let mut out = Vec::with_capacity(data.len() + AEAD_TAG_BYTES);
+    let mut index = 0u64;
+    let mut total = 0u64;
+    for chunk in body.chunks(input_chunk) {
+        let processed = aead_apply(sym, aead, key, &nonce(index), chunk, &ad, encrypt)
+            .map_err(|_| "modification detected".to_string())?;
+        total += if encrypt { chunk.len() } else { processed.len() } as u64;
+        out.extend_from_slice(&processed);
+        index += 1;
+    }
+
+    let mut final_ad = ad.to_vec();
+    final_ad.extend_from_slice(&total.to_be_bytes());
+    if encrypt {
+        out.extend_from_slice(&aead_apply(sym, aead, key, &nonce(index), &[], &final_ad, true)?);
+    } else {
+        aead_apply(sym, aead, key, &nonce(index), &data[body.len()..], &final_ad, false)
+            .map_err(|_| "modification detected".to_string())?;
+    }
+    Ok(out)
+}
+
+fn encrypt_with_count(data: &[u8], passphrase: &str, cipher: &str, aead: &str, count: u8, armored: bool) -> Result<Vec<u8>, String> {
+    let sym = symmetric_id(cipher)?;
+    let (_, key_len) = symmetric(sym)?;
+    let s2k = S2k { kind: 3, hash: S2K_HASH, salt: random_vec(8), count };
+    let s2k_key = s2k.derive(passphrase.as_bytes(), key_len)?;
+    let packets = literal_packet(data);
+
+    let mut out = Vec::new();
+    match aead {
+        "" => {
+            let mut skesk = vec![4, sym];
+            skesk.extend_from_slice(&s2k.to_bytes());
+            write_packet(&mut out, TAG_SKESK, &skesk);
+            write_packet(&mut out, TAG_SEIPD, &seipd_v1(sym, &s2k_key, &packets)?);
+        }
+        "ocb" | "eax" | "gcm" => {
+            let aead = match aead {
+                "ocb" => AEAD_OCB,
+                "eax" => AEAD_EAX,
+                _ => AEAD_GCM,
+            };
+            let session_key = random_vec(key_len);
+            let info = [0xC0 | TAG_SKESK, 6, sym, aead];
+            let kek = hkdf_sha256(&s2k_key, &[], &info, key_len);
+            let iv = random_vec(aead_nonce_len(aead)?);
+            let s2k_bytes = s2k.to_bytes();
+
+            let mut skesk = vec![6, (3 + s2k_bytes.len() + iv.len()) as u8, sym, aead, s2k_bytes.len() as u8];
+            skesk.extend_from_slice(&s2k_bytes);
+            skesk.extend_from_slice(&iv);
+            skesk.extend_from_slice(&aead_apply(sym, aead, &kek, &iv, &session_key, &info, true)?);
+            write_packet(&mut out, TAG_SKESK, &skesk);
+            write_packet(&mut out, TAG_SEIPD, &seipd_v2(sym, aead, &session_key, &packets)?);
+        }
+        _ => return Err(format!("unsupported AEAD mode: {}", aead)),
+    }
+    Ok(if armored { armor(&out).into_bytes() } else { out })
+}
+
+// aead: "" for SKESK v4 + SEIPD v1 (CFB + MDC, what `gpg --symmetric` writes),
+//       "ocb", "eax" or "gcm" for SKESK v6 + SEIPD v2 (RFC 9580, AES only; OCB is the mandatory mode)
+pub fn encrypt(data: &[u8], passphrase: &str, cipher: &str, aead: &str, armored: bool) -> Result<Vec<u8>, String> {
+    encrypt_with_count(data, passphrase, cipher, aead, S2K_COUNT, armored)
+}
+
+// ---------------------------------------------------------------------------
+// Decryption
+// ---------------------------------------------------------------------------
+
+// Candidate (symmetric algorithm, session key) for one SKESK packet
+fn session_key(skesk: &[u8], passphrase: &[u8]) -> Result<(u8, Vec<u8>), String> {
+    match skesk.first() {
+        Some(4) => {
+            let sym = *skesk.get(1).ok_or_else(|| "truncated SKESK".to_string())?;
+            let (cipher, key_len) = symmetric(sym)?;
+            let (s2k, used) = S2k::parse(&skesk[2..])?;
+            let key = s2k.derive(passphrase, key_len)?;
+            let esk = &skesk[2 + used..];
+            if esk.is_empty() {
+                return Ok((sym, key));
+            }
+            let bs = block_cipher::block_size(cipher)?;
+            let decrypted = block_modes::decrypt(cipher, "cfb", "none", &key, &vec![0u8; bs], esk)?;
+            let inner_sym = decrypted[0];
+            let (_, inner_len) = symmetric(inner_sym)?;
+            if decrypted.len() != 1 + inner_len {
+                return Err("wrong passphrase".to_string());
+            }
+            Ok((inner_sym, decrypted[1..].to_vec()))
+        }
+        Some(6) => {
+            let truncated = || "truncated SKESK".to_string();
+            let header = skesk.get(..5).ok_or_else(truncated)?;
+            let (sym, aead, s2k_len) = (header[2], header[3], header[4] as usize);
+            let (_, key_len) = symmetric(sym)?;
+            let (s2k, _) = S2k::parse(skesk.get(5..5 + s2k_len).ok_or_else(truncated)?)?;
+            let nonce_len = aead_nonce_len(aead)?;
+            let iv = skesk.get(5 + s2k_len..5 + s2k_len + nonce_len).ok_or_else(truncated)?;
+            let encrypted = &skesk[5 + s2k_len + nonce_len..];
+
+            let info = [0xC0 | TAG_SKESK, 6, sym, aead];
+            let kek = hkdf_sha256(&s2k.derive(passphrase, key_len)?, &[], &info, key_len);
+            let key = aead_apply(sym, aead, &kek, iv, encrypted, &info, false).map_err(|_| "wrong passphrase".to_string())?;
+            Ok((sym, key))
+        }
+        _ => Err("unsupported SKESK version".to_string()),
+    }
+}
+
+fn decrypt_seipd(seipd: &[u8], sym: u8, key: &[u8]) -> Result<Vec<u8>, String> {
+    match seipd.first() {
+        Some(1) => {
+            let (cipher, _) = symmetric(sym)?;
+            let bs = block_cipher::block_size(cipher)?;
+            if seipd.len() < 1 + bs + 2 + 22 {
+                return Err("truncated encrypted data".to_string());
+            }
+            let data = block_modes::decrypt(cipher, "cfb", "none", key, &vec![0u8; bs], &seipd[1..])?;
+            if data[bs - 2..bs] != data[bs..bs + 2] {
+                return Err("wrong passphrase".to_string());
+            }
+            let (covered, mdc) = data.split_at(data.len() - 20);
+            if !covered.ends_with(&[0xC0 | TAG_MDC, 20]) || Sha1::digest(covered)[..] != *mdc {
+                return Err("modification detected".to_string());
+            }
+            Ok(covered[bs + 2..covered.len() - 2].to_vec())
+        }
+        Some(2) => {
+            let header = seipd.get(..36).ok_or_else(|| "truncated encrypted data".to_string())?;
+            if header[1] != sym {
+                return Err("session key algorithm mismatch".to_string());
+            }
+            seipd_v2_process(header, key, &seipd[36..], false)
+        }
+        _ => Err("unsupported SEIPD version".to_string()),
+    }
+}
+
+// Unwrap compressed packets down to the literal data
+fn literal_data(packets: &[u8]) -> Result<Vec<u8>, String> {
+    for packet in parse_packets(packets)? {
+        match packet.tag {
+            TAG_LITERAL => {
+                let name_len = *packet.body.get(1).ok_or_else(|| "truncated literal packet".to_string())? as usize;
+                let start = 2 + name_len + 4;
+                return packet.body.get(start..).map(|d| d.to_vec()).ok_or_else(|| "truncated literal packet".to_string());
+            }
+            TAG_COMPRESSED => {
+                let (algo, compressed) = packet.body.split_first().ok_or_else(|| "truncated compressed packet".to_string())?;
+                let mut inflated = Vec::new();
+                match algo {
+                    0 => inflated.extend_from_slice(compressed),
+                    1 => {
+                        flate2::read::DeflateDecoder::new(compressed).read_to_end(&mut inflated).map_err(|_| "decompression failed".to_string())?;
+                    }
+                    2 => {
+                        flate2::read::ZlibDecoder::new(compressed).read_to_end(&mut inflated).map_err(|_| "decompression failed".to_string())?;
+                    }
+                    _ => return Err(format!("unsupported compression algorithm {}", algo)),
+                }
+                return literal_data(&inflated);
+            }
+            TAG_MARKER | TAG_PADDING => continue,
+            other => return Err(format!("unexpected packet type {}", other)),
+        }
+    }
+    Err("no literal data packet".to_string())
+}
+
+// Decrypt a binary or armored message with a passphrase; returns the literal data
+pub fn decrypt(message: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let packets = parse_packets(&binary(message)?)?;
+    let seipd = packets
+        .iter()
+        .find(|p| p.tag == TAG_SEIPD)
+        .ok_or_else(|| "no symmetrically encrypted data packet".to_string())?;
+
+    let mut last_error = "no passphrase-encrypted session key".to_string();
+    for skesk in packets.iter().filter(|p| p.tag == TAG_SKESK) {
+        let attempt = session_key(&skesk.body, passphrase.as_bytes()).and_then(|(sym, key)| decrypt_seipd(&seipd.body, sym, &key));
+        match attempt {
+            Ok(inner) => return literal_data(&inner),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASS: &str = "hashlab";
+
+    // 1000 bytes piped through `gpg --symmetric --compress-algo none`: partial body lengths
+    const PARTIAL_LENGTHS: &str = concat!(
+        "8c0d04090302eac61929e77b6c5660d2ea01ad49476bd4804f4a6445e88aeb2d5a584a25e9caa4ea68ef5d476bc6d640",
+        "c390e5ae809dfb0e08d7f6c151c625752d7da1f6b3fc0ffada41fe0253fd0f919bd06f76337381e3162d832301db195e",
+        "15e6f2b9c9847a40ae467f1c804742ad9a8c9da096a7881bc95eb937204cf7ed2f729f8e854fc578c36cc5c9f75ea6c5",
+        "639e2080bd4ef546090777ef985bf138ccdddc4f7cfb2cc71496d27a348bd380ca9b7302fa8033417a9777f4a34b9198",
+        "e5fb7ec7506d0d4720fd72b1df51f80698c204a051c7f602cbf431bca889ce23d7109115fbb5958607c325f851c004cf",
+        "bd7b5869dca5860e5101e195298d7aef2e1f180a5b1fd68497ba93ab06594715a28a83911abfd2f3bc536612056a8aff",
+        "ade2b0e9999a45a15311de2e4d5b4df3718e950d24fcfe1dc2485561db12d0029d4fef496be2114badd74ff7158f25c6",
+        "7fbfc68e35067782d4ac215282c48c4ea9b95b0e351c42a5621fd81995d60d74dc6cf3edb47b2772a4c9850fed3b3535",
+        "fc09820489a4bb384e910d1058f862b9c9beb6495ecac83e5b174362c9aa5860e9f0fd42d2cd00320b0d1a09a0802321",
+        "37fffd52853f2281cb8a4334e94abe735c497ce5c87282dcf07fc0b001259c6935c2d2386d9312f2a7bec11296eb9309",
+        "6c80c9544e982b7933c75168c6b27adb68a0a6b51b142491f5fc93ae435b51ebe9f428604ce43f2bb3b8abd4a9074407",
+        "e580adf1e67e4e13444ffc41d567c38480be8a98ffac2b6793bb48b9b0a2fad0389f55366d36e5f114a0dc71db8606f3",
+        "2841eae46e68207af6967678a11428c783678cf97c22af066385c55d57f77153070b63c1275b5da749784f3b379de8c0",
+        "cd14c6f2ce69644f6d42e3f804e270ded70d27216e503f8f4c3de43c6e75ff2459c3aca87eaee0327eefabe6020f5f66",
+        "1c0e87c6755ae2f50e5870afcbbf062b11cf2401c5c3eef7f5d364bb3a39065a023143c8465eb17c7dd9036649e33ca1",
+        "43f583cf7dd9561c32e4dc1dff2fb8577ba3226272c0a66c91ce75224194c7edf60614792bda2508a5b5b880d89d7308",
+        "3db22fd855f2fb9449f969800dc770ab8057855aed80ba85021f71d24537bb63fe322a5772171902bf74c98b1d48f7a7",
+        "fb421a5111b853fb882878c1a6342407b00adca24a1e0a9442f01aa97eb9aa789379f3d6a701f1b1df9ca5fa2877d79a",
+        "0b82d678974a542633c31b3192a100d28c5e55128e72ce827356ee6e52e056fc4afee9197f62ae7bf6889499ea92c22d",
+        "88428a6a3f2eeea3f9dc8fd1dd6411e88959651a40a1eb6d7c27aa73fba2d8b6d2309ca2a3e72f8366493bf15c536375",
+        "8a10e118c1ce2722b977a0d94b73579be2c285b7ec1d8118aafe7912a397cbaa865b7b33e7a2fdde7f162f965550420d",
+        "053e91e50a75c05d9ed991659722e11e0af1b4b78c258b0b4a2474bc44d09381351bf0ce8a60f68aa79f8d31ea728700",
+        "8e6b13c68e9dd9614742673eaa",
+    );
+
+    // Messages produced by GnuPG 2.2 `gpg --symmetric --passphrase hashlab` (input "OpenPGP symmetric test\n")
+    #[test]
+    fn test_decrypt_gpg_messages() {
+        let cases = [
+            // default options with --s2k-count 65536 (compressed)
+            "8c0d04090302936ade416423f85860d25301f26aa4f0ed81fd7183e452bdc241024e7019c73cbfb842ef45bb6fa5d3b0bb7ec6c6319b62d02a2d56f19c274face4416068248515f403504ef04c01c3a85307e0ccc7fa4ef13c65ecf55df19f3636b91b1a",
+            // --s2k-digest-algo SHA1 --cipher-algo AES128 --compress-algo none
+            "8c0d0407030290391d75d4da434260d24f0129d7b1b15145664f75d9d6e2d301a1b80804575bd119e2949d34d30aa470ac2247f80d08bf25821c0a508700dc23b03239207dce59cee3624d74c9fcc3d10daf67ee7260b76640f3fb89f3a367a2",
+            // --cipher-algo CAMELLIA256 --compress-algo zip --s2k-digest-algo SHA512
+            "8c0d040d030aabed786e5196f83760d25301386a85cb3ef3fb6c47213005d77c369e280f297338a4b4a88b5cd9074e2ef9842500be0fc3fb1e98426feb8cdfc9a8c5676132b126738374479e165cadc5774b8f02a4fba24ecadd4c9664f3ed27d2986b83",
+            // --cipher-algo CAST5 --compress-algo none
+            "8c0d04030302ff026646a19430eb60d24701cfba149b69372a9530657192df9d172cad1354b2d64685dc0edbd27aa61341fc3750089e0c5e69fe1a22e18221deb620785e6cf6a40f98412cbe5a9e7bd33ae890fb9c32b526",
+        ];
+        for case in cases {
+            assert_eq!(decrypt(&hex::decode(case).unwrap(), PASS).unwrap(), b"OpenPGP symmetric test\n");
+        }
+        assert_eq!(decrypt(&hex::decode(cases[1]).unwrap(), "wrong").unwrap_err(), "wrong passphrase");
+    }
+
+    #[test]
+    fn test_decrypt_gpg_armored_and_zlib() {
+        let armored = "-----BEGIN PGP MESSAGE-----\n\
+                       \n\
+                       jA0ECQMCrwiGwnyxORJg0lMBabg+RgH5QNxf7/PzWy/K4YMsxuoZvmDR8rm2bFD4\n\
+                       1oIpUjYeYtyr4ffwpc+/lnqBgA8AU1c+nNE+wzMLa5yYcJuKnn4yeMgIBibgs8fc\n\
+                       9tMbBg==\n\
+                       =69O1\n\
+                       -----END PGP MESSAGE-----\n";
+        assert_eq!(decrypt(armored.as_bytes(), PASS).unwrap(), b"OpenPGP symmetric test\n");
+        assert_eq!(dearmor(&armor(b"\x8c\x0d")).unwrap(), b"\x8c\x0d");
+        assert!(dearmor(&armored.replace("=69O1", "=69O2")).is_err());
+
+        // echo hi | gpg --symmetric --compress-algo zlib
+        let zlib = "8c0d04090302594fe111aedb6acf60d23e0172ca3518b71cedc6556579c3e3b761a9b03479671bfab9d902c7b50e901f4ec3bc419bac17abc9d82e2822d8f4e9bf536db4a972c170c69932ea0e10bb";
+        assert_eq!(decrypt(&hex::decode(zlib).unwrap(), PASS).unwrap(), b"hi\n");
+    }
+
+    // RFC 9580 appendix A.9 - A.11: "Hello, world!" under SKESK v6 + SEIPD v2 with AES-128 and
+    // the passphrase "password"
+    #[test]
+    fn test_decrypt_rfc9580_aead_samples() {
+        let samples = [
+            // OCB
+            "wz8GHQcCCwMIVqKY0vXjZFP/z8xcEWZO2520JZDX3EawckG2EsOBLP/76gDyNHslZBEj+IeuYNT9YU4IN9gZ02zSaQIHAgYgpmH3MfyaMDK1YjMmAn46XY21dI6+/wsMWRDQns3WQf+f04VidYA1vEl1TOG/P/+n2tCjuBBPUTPPQqQQCoPu9MobSAGohGv0K82nyM6dZeIS8wHLzZj9yt5pSod61CRzI/boVw==",
+            // EAX
+            "w0AGHgcBCwMIpa5XnR/F2Cv/aSJPkZmTs1Bvo7WaanPP+MXvxfQcV/tU4cImgV14KPX5LEVOtl6+AKtZhsaObnxV0mkCBwEGn/kOOzIZZPOkKRPI3MZhkyUBUifvt+rqpJ8EwuZ0F11KPSJu1q/LnKmsEiwUcOEcY9TAqyQcapOK1Iv5mlqZuQu6gyXeYQR1QCWKt5Wala0FHdqW6xVDHf719eIlXKeCYVRuM5o=",
+            // GCM
+            "wzwGGgcDCwMI6dOXhbIHAAj/tC58SD70iERXyzcmubPbn/d25fTZpAlS4kRymIUav/91Jt8t1VRBdXmneZ/SaQIHAwb8uUSQvLmLvcnRBsYJAmaUD3LontwhtVlrFXaxAe0Pn/xvxtZbv9JNzQeQlm5tHoWjAFN4TLHYtqBpnvEhVaeyrWJYUxtXZR/Xd3kS+pXjXZtAIW9ppMJI2yj/QzHxYykHOZ5v+Q==",
+        ];
+        for sample in samples {
+            let message = STANDARD.decode(sample).unwrap();
+            assert_eq!(decrypt(&message, "password").unwrap(), b"Hello, world!");
+        }
+    }
+
+    #[test]
+    fn test_decrypt_partial_body_lengths() {
+        let expected: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        assert_eq!(decrypt(&hex::decode(PARTIAL_LENGTHS).unwrap(), PASS).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_s2k_iterated_salted() {
+        let s2k = S2k { kind: 3, hash: 8, salt: vec![1, 2, 3, 4, 5, 6, 7, 8], count: 0x60 };
+        // 65536 octets of (salt || "hashlab") hashed with SHA-256, first 16 bytes
+        let mut input = Vec::new();
+        while input.len() < 65536 {
+            input.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+            input.extend_from_slice(b"hashlab");
+        }
+        input.truncate(65536);
+        assert_eq!(s2k.derive(b"hashlab", 16).unwrap(), Sha256::digest(&input)[..16]);
+        // Longer keys continue with a context preloaded with one zero octet
+        let mut preloaded = vec![0u8];
+        preloaded.extend_from_slice(&input);
+        let long = s2k.derive(b"hashlab", 40).unwrap();
+        assert_eq!(long[32..], Sha256::digest(&preloaded)[..8]);
+    }
+
+    #[test]
+    fn test_roundtrip_v1_and_v2() {
+        let data: Vec<u8> = (0..70000u32).map(|i| i as u8).collect();
+        for (cipher, aead) in [("aes256", ""), ("twofish", ""), ("tdes", ""), ("aes128", "eax"), ("aes256", "gcm"), ("aes192", "gcm"), ("aes128", "ocb"), ("aes256", "ocb")] {
+            for len in [0, 1, 300, 70000] {
+                let message = encrypt_with_count(&data[..len], PASS, cipher, aead, 0x60, false).unwrap();
+                assert_eq!(decrypt(&message, PASS).unwrap(), &data[..len], "{} {} {}", cipher, aead, len);
+            }
+        }
+        let armored = encrypt_with_count(b"armored", PASS, "aes128", "gcm", 0x60, true).unwrap();
+        assert!(armored.starts_with(ARMOR_BEGIN.as_bytes()));
+        assert_eq!(decrypt(&armored, PASS).unwrap(), b"armored");
+        assert!(encrypt(b"x", PASS, "twofish", "gcm", false).is_err());
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        for aead in ["", "eax", "ocb"] {
+            let mut message = encrypt_with_count(b"integrity protected", PASS, "aes128", aead, 0x60, false).unwrap();
+            let last = message.len() - 1;
+            message[last] ^= 1;
+            assert_eq!(decrypt(&message, PASS).unwrap_err(), "modification detected");
+        }
+        let v2 = encrypt_with_count(b"secret", PASS, "aes128", "gcm", 0x60, false).unwrap();
+        assert_eq!(decrypt(&v2, "wrong").unwrap_err(), "wrong passphrase");
+    }
+}
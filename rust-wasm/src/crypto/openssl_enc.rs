@@ -0,0 +1,284 @@
+// OpenSSL `enc` compatible passphrase encryption
+//
+// Layout: ["Salted__"][SALT8][CIPHERTEXT], optionally Base64 armored (64-column lines, as `-a`).
+// Key and IV are derived together from passphrase and salt, either with
+//   - EVP_BytesToKey (legacy default, one iteration of md5 / sha1 / sha256 / sha384 / sha512), or
+//   - PBKDF2-HMAC (`-pbkdf2` / `-iter N`) with the same digests.
+// Cipher names follow the OpenSSL CLI: aes-256-cbc, camellia-128-ctr, des-ede3-cbc, bf-cbc, chacha20, rc4, ...
+use super::{block_modes, pbkdf2_key};
+use base64::Engine;
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use chacha20::ChaCha20;
+use getrandom::getrandom;
+use rc4::consts::U16;
+use rc4::{KeyInit, Rc4};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+pub const MAGIC: &[u8; 8] = b"Salted__";
+pub const SALT_BYTES: usize = 8;
+
+enum CipherEngine {
+    Block { cipher: &'static str, mode: &'static str },
+    ChaCha20,
+    Rc4,
+}
+
+struct CipherSpec {
+    engine: CipherEngine,
+    key_len: usize,
+    iv_len: usize,
+}
+
+// Map an OpenSSL cipher name (with or without the leading '-') to an implementation
+fn parse_cipher(name: &str) -> Result<CipherSpec, String> {
+    let lower = name.trim_start_matches('-').to_ascii_lowercase();
+    let name = match lower.as_str() {
+        "aes128" => "aes-128-cbc",
+        "aes192" => "aes-192-cbc",
+        "aes256" => "aes-256-cbc",
+        "camellia128" => "camellia-128-cbc",
+        "camellia192" => "camellia-192-cbc",
+        "camellia256" => "camellia-256-cbc",
+        "aria128" => "aria-128-cbc",
+        "aria192" => "aria-192-cbc",
+        "aria256" => "aria-256-cbc",
+        "sm4" => "sm4-cbc",
+        "des" => "des-cbc",
+        "des3" => "des-ede3-cbc",
+        "des-ede" => "des-ede-ecb",
+        "des-ede3" => "des-ede3-ecb",
+        "bf" | "blowfish" => "bf-cbc",
+        "cast" | "cast-cbc" | "cast5" => "cast5-cbc",
+        "idea" => "idea-cbc",
+        other => other,
+    };
+    match name {
+        "chacha20" => return Ok(CipherSpec { engine: CipherEngine::ChaCha20, key_len: 32, iv_len: 16 }),
+        "rc4" => return Ok(CipherSpec { engine: CipherEngine::Rc4, key_len: 16, iv_len: 0 }),
+        _ => {}
+    }
+
+    let unknown = || format!("unsupported cipher: {}", name);
+    let (base, mode) = name.rsplit_once('-').ok_or_else(unknown)?;
+    let mode = match mode {
+        "ecb" => "ecb",
+        "cbc" => "cbc",
+        "ctr" => "ctr",
+        "cfb" => "cfb",
+        "ofb" => "ofb",
+        _ => return Err(unknown()),
+    };
+    let (cipher, key_len) = match base {
+        "aes-128" => ("aes128", 16),
+        "aes-192" => ("aes192", 24),
+        "aes-256" => ("aes256", 32),
+        "camellia-128" => ("camellia128", 16),
+        "camellia-192" => ("camellia192", 24),
+        "camellia-256" => ("camellia256", 32),
+        "aria-128" => ("aria128", 16),
+        "aria-192" => ("aria192", 24),
+        "aria-256" => ("aria256", 32),
+        "sm4" => ("sm4", 16),
+        "des" => ("des", 8),
+        "des-ede" => ("tdes", 16),
+        "des-ede3" => ("tdes", 24),
+        "bf" => ("blowfish", 16),
+        "cast5" => ("cast5", 16),
+        "idea" => ("idea", 16),
+        _ => return Err(unknown()),
+    };
+    let iv_len = if mode == "ecb" { 0 } else { super::block_cipher::block_size(cipher)? };
+    Ok(CipherSpec { engine: CipherEngine::Block { cipher, mode }, key_len, iv_len })
+}
+
+fn hash(md: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    match md {
+        "md5" => Ok(md5::compute(data).0.to_vec()),
+        "sha1" => Ok(Sha1::digest(data).to_vec()),
+        "sha256" => Ok(Sha256::digest(data).to_vec()),
+        "sha384" => Ok(Sha384::digest(data).to_vec()),
+        "sha512" => Ok(Sha512::digest(data).to_vec()),
+        _ => Err(format!("unsupported digest: {}", md)),
+    }
+}
+
+// EVP_BytesToKey with count = 1: D_i = H(D_(i-1) || passphrase || salt)
+pub fn evp_bytes_to_key(md: &str, passphrase: &[u8], salt: &[u8], len: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(len + 64);
+    let mut prev: Vec<u8> = Vec::new();
+    while out.len() < len {
+        let mut input = prev.clone();
+        input.extend_from_slice(passphrase);
+        input.extend_from_slice(salt);
+        prev = hash(md, &input)?;
+        out.extend_from_slice(&prev);
+    }
+    out.truncate(len);
+    Ok(out)
+}
+
+// iterations == 0 selects EVP_BytesToKey, anything else PBKDF2 with that iteration count
+fn derive(spec: &CipherSpec, md: &str, iterations: u32, passphrase: &[u8], salt: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let len = spec.key_len + spec.iv_len;
+    let mut material = if iterations == 0 {
+        evp_bytes_to_key(md, passphrase, salt, len)?
+    } else {
+        pbkdf2_key::derive(md, passphrase, salt, iterations, len)?
+    };
+    let iv = material.split_off(spec.key_len);
+    Ok((material, iv))
+}
+
+fn apply(spec: &CipherSpec, key: &[u8], iv: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    match spec.engine {
+        CipherEngine::Block { cipher, mode } => {
+            let padding = if mode == "ecb" || mode == "cbc" { "pkcs7" } else { "none" };
+            if encrypt {
+                block_modes::encrypt(cipher, mode, padding, key, iv, data)
+            } else {
+                block_modes::decrypt(cipher, mode, padding, key, iv, data).map_err(|_| "bad decrypt".to_string())
+            }
+        }
+        // OpenSSL's 16-byte ChaCha20 IV is [BLOCK_COUNTER32 LE][NONCE12]
+        CipherEngine::ChaCha20 => {
+            let counter = u32::from_le_bytes([iv[0], iv[1], iv[2], iv[3]]);
+            let mut cipher = ChaCha20::new(key.into(), iv[4..].into());
+            let overflow = |_| "ChaCha20 block counter overflows before the end of the data".to_string();
+            cipher.try_seek(counter as u64 * 64).map_err(overflow)?;
+            let mut buf = data.to_vec();
+            cipher.try_apply_keystream(&mut buf).map_err(overflow)?;
+            Ok(buf)
+        }
+        CipherEngine::Rc4 => {
+            let mut cipher = Rc4::<U16>::new(key.into());
+            let mut buf = data.to_vec();
+            cipher.apply_keystream(&mut buf);
+            Ok(buf)
+        }
+    }
+}
+
+pub fn encrypt_with_salt(cipher: &str, md: &str, iterations: u32, passphrase: &[u8], salt: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    if salt.len() != SALT_BYTES {
+        return Err("salt must be 8 bytes".to_string());
+    }
+    let spec = parse_cipher(cipher)?;
+    let (key, iv) = derive(&spec, md, iterations, passphrase, salt)?;
+    let ciphertext = apply(&spec, &key, &iv, plaintext, true)?;
+
+    let mut result = Vec::with_capacity(16 + ciphertext.len());
+    result.extend_from_slice(MAGIC);
+    result.extend_from_slice(salt);
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+// Equivalent of `openssl enc -<cipher> -md <md> [-iter N]`: returns the binary "Salted__" blob
+pub fn encrypt(cipher: &str, md: &str, iterations: u32, passphrase: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; SALT_BYTES];
+    getrandom(&mut salt).expect("random salt generation failed");
+    encrypt_with_salt(cipher, md, iterations, passphrase, &salt, plaintext)
+}
+
+// Equivalent of `openssl enc -d`; accepts the binary blob or its Base64 armor
+pub fn decrypt(cipher: &str, md: &str, iterations: u32, passphrase: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let blob = if data.starts_with(MAGIC) { data.to_vec() } else { dearmor(data)? };
+    if blob.len() < MAGIC.len() + SALT_BYTES || !blob.starts_with(MAGIC) {
+        return Err("missing Salted__ header".to_string());
+    }
+    let spec = parse_cipher(cipher)?;
+    let salt = &blob[MAGIC.len()..MAGIC.len() + SALT_BYTES];
+    let (key, iv) = derive(&spec, md, iterations, passphrase, salt)?;
+    apply(&spec, &key, &iv, &blob[MAGIC.len() + SALT_BYTES..], false)
+}
+
+// Base64 in 64-character lines, each terminated by '\n' (`openssl enc -a`)
+pub fn armor(data: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 64 + 1);
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        out.push('\n');
+    }
+    out
+}
+
+pub fn dearmor(text: &[u8]) -> Result<Vec<u8>, String> {
+    let compact: Vec<u8> = text.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|_| "invalid base64".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASS: &[u8] = b"hashlab";
+    const MESSAGE: &[u8] = b"OpenSSL enc interop test message\n";
+
+    // Blobs produced by `openssl enc <args> -pass pass:hashlab` (OpenSSL 3.5)
+    #[test]
+    fn test_decrypt_openssl_blobs() {
+        let cases = [
+            ("aes-256-cbc", "md5", 0, "53616c7465645f5fb43e7e61546237d07c9c9961166d38c2b90be84385c2b2e69df874a0a02728e93440812f772ee2d2c4ae5d6dd74962bd6d0fd80e91ce1000"),
+            ("aes-256-cbc", "sha256", 10000, "53616c7465645f5fa1c6964be9e39224c193540959819e09780c524a31242ea251e71fde4fb4ce4ebd880e210166d980765593358ac0e744eeb079c739036d90"),
+            ("aes-128-ctr", "sha256", 1000, "53616c7465645f5feb37c0d9d1f8efd7831cad4ffd82ab92d7a017f73a00e89b8ae1d4eb443430a96a41b01bc4fd2674e1"),
+            ("aes-128-cbc", "md5", 1000, "53616c7465645f5f669d609e083a7a39ecc2cd651faa58992548ae1a5cd50c4033e9a66c33d7ef57e9bc8e05e4e8e08e9cebae0c912c75ab30622cb6e3f2b657"),
+            ("aes-256-cbc", "sha384", 0, "53616c7465645f5fafd8f6647eedb5fc11c18853a60680b958a37c619ab4d7bc921fad9ea03afd46aa3c1a6efec1cff5873da519cdb5a8a0f60339e5825daa06"),
+            ("aes-256-cbc", "sha384", 2000, "53616c7465645f5f8d4e67173cc13d8f709809adb682df0672d26f3249927991063a13c31708123cfd378e4c1ca2a54a0311d6cacee944b2bd21a557201a6c5a"),
+            ("camellia-256-cbc", "sha512", 5000, "53616c7465645f5f40a5b8381afc90687779e2c3772cf34748c6fbb1abda21401ae60bbb615368e5eb2afae0b1f1f57d25cb447ffbb9d08a0c651a75cd131a54"),
+            ("chacha20", "sha256", 10000, "53616c7465645f5ff91bbfcde8bb8dcdce04467c5e56699f5dccd788f85f75d531f84972aeefe6ddb89666ee405960860d"),
+            ("des-ede3-cbc", "sha1", 0, "53616c7465645f5f61e101f94b5a4a8482cac60c3ee6d40f150984ea0ba6e96d793f8bd56906588a46c6c3c455efde945061c485376e71c8"),
+            ("sm4-cfb", "sha256", 10000, "53616c7465645f5f7187ea9b8154536e7455c1d254d81c2c72441339f53672e03074982ce3fea2ba2b2582f4d74d288105"),
+            ("bf-cbc", "sha256", 0, "53616c7465645f5fd20463283d831c114b532313298f7d59e317607510446f0bf7350bdf7f947df7d34692bec2942a8279d30700bf669b6a"),
+            ("rc4", "sha256", 0, "53616c7465645f5faeb7a946c9543ef0236c4b6e3f510d447ee24010053b0150e86d23d0f372b8279a500f6f50b6c2c4f8"),
+            ("aria-128-ofb", "sha256", 10000, "53616c7465645f5fd0fdb5ef744f431c848a232389514f0d9ac40e8b15fbe5a9f8bce87ed19a3822b274914a557df1f0b6"),
+            ("idea-cbc", "sha256", 10000, "53616c7465645f5fd98d968c222097962b093aa8ebf3bb8cfa9cff6f4e617a5f76dde6cc6e65c3d85f0e7911074d85e1843abb460390e5a1"),
+            ("cast5-cbc", "sha256", 10000, "53616c7465645f5f37b245bd860ee62a94660b02823b54f3389e1462fea2b8e3c71e20a0c048eab5ac608d9622a8a1ce64b1ab43ce813dfe"),
+            ("des-ecb", "sha256", 10000, "53616c7465645f5f6b86e3682a4e82d308dc21c90ad234e0e376fd02823524313dd0f96e900e25608d23a03b51a664999e5454dab46e51fc"),
+        ];
+        for (cipher, md, iterations, blob) in cases {
+            let plaintext = decrypt(cipher, md, iterations, PASS, &hex::decode(blob).unwrap()).unwrap();
+            assert_eq!(plaintext, MESSAGE, "{}", cipher);
+        }
+    }
+
+    #[test]
+    fn test_encrypt_matches_openssl() {
+        let blob = hex::decode("53616c7465645f5fa1c6964be9e39224c193540959819e09780c524a31242ea251e71fde4fb4ce4ebd880e210166d980765593358ac0e744eeb079c739036d90").unwrap();
+        let ours = encrypt_with_salt("-aes-256-cbc", "sha256", 10000, PASS, &blob[8..16], MESSAGE).unwrap();
+        assert_eq!(ours, blob);
+    }
+
+    #[test]
+    fn test_armor() {
+        // `head -c 100 /dev/zero | openssl enc -aes-256-cbc -pbkdf2 -pass pass:hashlab -a`
+        let armored = "U2FsdGVkX1/MY4FQeF1mzrpFU+IR3G06Py8S2TVdDbEUOCai9ZHj4bFEBpprdT4m\n\
+                       iAaYyHrk+rkkBWUma4cV1DdaFny5iEpCV4UztLno7YDDwT5TZrysul9Wr/WXQwAe\n\
+                       ABVEUjKZI7YTCK3HnlaLyguYcwus72sj/PLpPsKNEXE=\n";
+        let plaintext = decrypt("aes256", "sha256", 10000, PASS, armored.as_bytes()).unwrap();
+        assert_eq!(plaintext, vec![0u8; 100]);
+        assert_eq!(armor(&dearmor(armored.as_bytes()).unwrap()), armored.replace(' ', ""));
+    }
+
+    #[test]
+    fn test_roundtrip_and_errors() {
+        for cipher in ["aes-192-ofb", "des-ede-cbc", "des3", "camellia-128-ecb", "sm4-ctr"] {
+            let blob = encrypt(cipher, "sha256", 100, PASS, MESSAGE).unwrap();
+            assert_eq!(&blob[..8], MAGIC);
+            assert_eq!(decrypt(cipher, "sha256", 100, PASS, &blob).unwrap(), MESSAGE, "{}", cipher);
+        }
+        let blob = encrypt_with_salt("aes-256-cbc", "md5", 0, PASS, &[1, 2, 3, 4, 5, 6, 7, 8], MESSAGE).unwrap();
+        assert_eq!(decrypt("aes-256-cbc", "md5", 0, b"wrong", &blob).unwrap_err(), "bad decrypt");
+        assert!(encrypt("aes-256-gcm", "sha256", 0, PASS, MESSAGE).is_err());
+        assert!(encrypt("aes-256-cbc", "md4", 1000, PASS, MESSAGE).is_err());
+        assert_eq!(decrypt("aes-256-cbc", "sha256", 0, PASS, b"AAAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(), "missing Salted__ header");
+        // The 32-bit block counter in the IV runs out after one block
+        let iv = [[0xfe, 0xff, 0xff, 0xff], [0; 4], [0; 4], [0; 4]].concat();
+        let spec = parse_cipher("chacha20").unwrap();
+        assert_eq!(apply(&spec, &[0; 32], &iv, &[0; 64], false).unwrap().len(), 64);
+        assert!(apply(&spec, &[0; 32], &iv, &[0; 65], false).unwrap_err().contains("overflows"));
+    }
+}
@@ -0,0 +1,285 @@
+// One-time passwords: HOTP (RFC 4226) and TOTP (RFC 6238) over HMAC-SHA1 / SHA-256 / SHA-512,
+// and the otpauth:// key URI format used by authenticator apps (Base32 secret, no padding).
+// Times are Unix seconds passed in by the caller (wasm has no clock).
+use getrandom::getrandom;
+use serde_json::json;
+
+use crate::encoding::base32;
+use crate::hash::hmac;
+
+const DEFAULT_DIGITS: u32 = 6;
+const DEFAULT_PERIOD: u64 = 30;
+
+/// Canonical HMAC name for the otpauth `algorithm` parameter (SHA1 | SHA256 | SHA512)
+fn algorithm(name: &str) -> Result<&'static str, String> {
+    match name.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "" | "sha1" => Ok("sha1"),
+        "sha256" => Ok("sha256"),
+        "sha512" => Ok("sha512"),
+        _ => Err(format!("unsupported OTP algorithm: {} (use SHA1, SHA256 or SHA512)", name)),
+    }
+}
+
+fn check_digits(digits: u32) -> Result<(), String> {
+    if !(6..=10).contains(&digits) {
+        return Err("digits must be between 6 and 10".to_string());
+    }
+    Ok(())
+}
+
+/// Random secret of `bytes` bytes (20 for SHA-1, 32 / 64 for SHA-256 / SHA-512), Base32 encoded
+pub fn generate_secret(bytes: usize) -> Result<String, String> {
+    if !(10..=64).contains(&bytes) {
+        return Err("secret length must be 10-64 bytes".to_string());
+    }
+    let mut secret = vec![0u8; bytes];
+    getrandom(&mut secret).map_err(|e| e.to_string())?;
+    Ok(base32::encode(&secret, false))
+}
+
+/// HOTP: dynamic truncation of HMAC(secret, counter as big-endian u64), modulo 10^digits
+pub fn hotp(secret: &[u8], counter: u64, digits: u32, algorithm_name: &str) -> Result<String, String> {
+    check_digits(digits)?;
+    let mac = hmac::mac(algorithm(algorithm_name)?, secret, &counter.to_be_bytes())?;
+    let offset = (mac[mac.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([mac[offset], mac[offset + 1], mac[offset + 2], mac[offset + 3]]) & 0x7fff_ffff;
+    Ok(format!("{:0width$}", binary as u64 % 10u64.pow(digits), width = digits as usize))
+}
+
+/// TOTP: HOTP with counter floor(now / period)
+pub fn totp(secret: &[u8], now: u64, period: u64, digits: u32, algorithm_name: &str) -> Result<String, String> {
+    if period == 0 {
+        return Err("period must be at least one second".to_string());
+    }
+    hotp(secret, now / period, digits, algorithm_name)
+}
+
+/// Constant-time comparison; spaces in the entered code ("123 456") are ignored
+fn code_matches(expected: &str, code: &str) -> bool {
+    let code: Vec<u8> = code.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    expected.len() == code.len() && expected.bytes().zip(code).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Check an HOTP code against counters counter..=counter + window; returns JSON with valid
+/// and, on success, the matching counter and the counter to store for the next check (null
+/// once the counter is exhausted at u64::MAX)
+pub fn verify_hotp(secret: &[u8], code: &str, counter: u64, window: u32, digits: u32, algorithm_name: &str) -> Result<String, String> {
+    for candidate in (0..=window as u64).filter_map(|step| counter.checked_add(step)) {
+        if code_matches(&hotp(secret, candidate, digits, algorithm_name)?, code) {
+            return Ok(json!({ "valid": true, "counter": candidate, "next_counter": candidate.checked_add(1) }).to_string());
+        }
+    }
+    Ok(json!({ "valid": false }).to_string())
+}
+
+/// Check a TOTP code against the time steps within `window` periods of `now`, nearest first;
+/// returns JSON with valid and, on success, the drift in periods (negative = code from the past)
+pub fn verify_totp(secret: &[u8], code: &str, now: u64, period: u64, digits: u32, algorithm_name: &str, window: u32) -> Result<String, String> {
+    if period == 0 {
+        return Err("period must be at least one second".to_string());
+    }
+    let current = now / period;
+    let drifts = std::iter::once(0i64).chain((1..=window as i64).flat_map(|d| [-d, d]));
+    for drift in drifts {
+        let Some(counter) = current.checked_add_signed(drift) else { continue };
+        if code_matches(&hotp(secret, counter, digits, algorithm_name)?, code) {
+            return Ok(json!({ "valid": true, "drift": drift, "counter": counter }).to_string());
+        }
+    }
+    Ok(json!({ "valid": false }).to_string())
+}
+
+/// Settings of an otpauth:// URI
+pub struct KeyUri {
+    pub kind: String,
+    pub issuer: String,
+    pub account: String,
+    pub secret: Vec<u8>,
+    pub algorithm: &'static str,
+    pub digits: u32,
+    // TOTP period in seconds (ignored for HOTP)
+    pub period: u64,
+    // Initial HOTP counter (0 for TOTP)
+    pub counter: u64,
+}
+
+fn percent_decode(text: &str) -> Result<String, String> {
+    // Query strings may encode spaces as '+'
+    urlencoding::decode(&text.replace('+', " ")).map(|s| s.into_owned()).map_err(|_| "invalid percent-encoding in URI".to_string())
+}
+
+/// Parse otpauth://TYPE/[ISSUER:]ACCOUNT?secret=...&issuer=...&algorithm=...&digits=...&period=|counter=...
+pub fn parse_uri(uri: &str) -> Result<KeyUri, String> {
+    let rest = uri.trim().strip_prefix("otpauth://").ok_or("URI must start with otpauth://")?;
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (kind, label) = path.split_once('/').unwrap_or((path, ""));
+    let kind = kind.to_ascii_lowercase();
+    if kind != "totp" && kind != "hotp" {
+        return Err(format!("unsupported OTP type: {} (use totp or hotp)", kind));
+    }
+    let label = percent_decode(label)?;
+    let (label_issuer, account) = label.split_once(':').map(|(i, a)| (i.trim(), a.trim())).unwrap_or(("", label.trim()));
+    let mut key = KeyUri {
+        kind,
+        issuer: label_issuer.to_string(),
+        account: account.to_string(),
+        secret: Vec::new(),
+        algorithm: "sha1",
+        digits: DEFAULT_DIGITS,
+        period: DEFAULT_PERIOD,
+        counter: 0,
+    };
+    let (mut has_secret, mut has_counter) = (false, false);
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value)?;
+        let number = || value.parse::<u64>().map_err(|_| format!("invalid {} parameter: {}", name, value));
+        match name.to_ascii_lowercase().as_str() {
+            "secret" => {
+                key.secret = base32::decode(&value).map_err(|e| format!("secret: {}", e))?;
+                has_secret = true;
+            }
+            "issuer" => key.issuer = value,
+            "algorithm" => key.algorithm = algorithm(&value)?,
+            "digits" => key.digits = number().and_then(|n| u32::try_from(n).map_err(|_| format!("invalid digits parameter: {}", value)))?,
+            "period" => key.period = number()?,
+            "counter" => {
+                key.counter = number()?;
+                has_counter = true;
+            }
+            // image, color and other app-specific parameters
+            _ => {}
+        }
+    }
+    if !has_secret || key.secret.is_empty() {
+        return Err("URI has no secret parameter".to_string());
+    }
+    if key.kind == "hotp" && !has_counter {
+        return Err("HOTP URIs need a counter parameter".to_string());
+    }
+    check_digits(key.digits)?;
+    if key.period == 0 {
+        return Err("period must be at least one second".to_string());
+    }
+    Ok(key)
+}
+
+/// Parsed URI as JSON (secret as Base32 and hex)
+pub fn describe_uri(uri: &str) -> Result<String, String> {
+    let key = parse_uri(uri)?;
+    let mut out = json!({
+        "type": key.kind,
+        "issuer": key.issuer,
+        "account": key.account,
+        "secret": base32::encode(&key.secret, false),
+        "secret_hex": hex::encode(&key.secret),
+        "algorithm": key.algorithm.to_ascii_uppercase(),
+        "digits": key.digits,
+    });
+    if key.kind == "totp" {
+        out["period"] = json!(key.period);
+    } else {
+        out["counter"] = json!(key.counter);
+    }
+    Ok(out.to_string())
+}
+
+/// Current code for a URI: TOTP at `now`, HOTP at the URI's counter
+pub fn uri_code(uri: &str, now: u64) -> Result<String, String> {
+    let key = parse_uri(uri)?;
+    match key.kind.as_str() {
+        "totp" => totp(&key.secret, now, key.period, key.digits, key.algorithm),
+        _ => hotp(&key.secret, key.counter, key.digits, key.algorithm),
+    }
+}
+
+/// otpauth:// URI; `period_or_counter` is the TOTP period (0 = 30 s) or the initial HOTP counter.
+/// Default parameters are left out, as authenticator apps expect.
+pub fn build_uri(kind: &str, secret: &[u8], account: &str, issuer: &str, algorithm_name: &str, digits: u32, period_or_counter: u64) -> Result<String, String> {
+    let kind = kind.to_ascii_lowercase();
+    if kind != "totp" && kind != "hotp" {
+        return Err(format!("unsupported OTP type: {} (use totp or hotp)", kind));
+    }
+    if secret.is_empty() {
+        return Err("secret is empty".to_string());
+    }
+    if account.contains(':') || issuer.contains(':') {
+        return Err("issuer and account cannot contain ':'".to_string());
+    }
+    check_digits(digits)?;
+    let algorithm = algorithm(algorithm_name)?;
+    let label = if issuer.is_empty() {
+        urlencoding::encode(account).into_owned()
+    } else {
+        format!("{}:{}", urlencoding::encode(issuer), urlencoding::encode(account))
+    };
+    let mut uri = format!("otpauth://{}/{}?secret={}", kind, label, base32::encode(secret, false));
+    if !issuer.is_empty() {
+        uri.push_str(&format!("&issuer={}", urlencoding::encode(issuer)));
+    }
+    if algorithm != "sha1" {
+        uri.push_str(&format!("&algorithm={}", algorithm.to_ascii_uppercase()));
+    }
+    if digits != DEFAULT_DIGITS {
+        uri.push_str(&format!("&digits={}", digits));
+    }
+    if kind == "hotp" {
+        uri.push_str(&format!("&counter={}", period_or_counter));
+    } else if period_or_counter != 0 && period_or_counter != DEFAULT_PERIOD {
+        uri.push_str(&format!("&period={}", period_or_counter));
+    }
+    Ok(uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4226 appendix D and RFC 6238 appendix B
+    #[test]
+    fn test_rfc_vectors() {
+        let secret = b"12345678901234567890";
+        let expected = ["755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489"];
+        for (counter, code) in expected.iter().enumerate() {
+            assert_eq!(hotp(secret, counter as u64, 6, "SHA1").unwrap(), *code);
+        }
+        let secrets: [(&[u8], &str); 3] = [
+            (b"12345678901234567890", "sha1"),
+            (b"12345678901234567890123456789012", "sha256"),
+            (b"1234567890123456789012345678901234567890123456789012345678901234", "sha512"),
+        ];
+        let vectors = [(59, ["94287082", "46119246", "90693936"]), (1111111109, ["07081804", "68084774", "25091201"]), (20000000000, ["65353130", "77737706", "47863826"])];
+        for (now, codes) in vectors {
+            for ((secret, alg), code) in secrets.iter().zip(codes) {
+                assert_eq!(totp(secret, now, 30, 8, alg).unwrap(), code);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_window() {
+        let secret = b"12345678901234567890";
+        let result: serde_json::Value = serde_json::from_str(&verify_totp(secret, "9428 7082", 89, 30, 8, "sha1", 1).unwrap()).unwrap();
+        assert_eq!((result["valid"].as_bool(), result["drift"].as_i64()), (Some(true), Some(-1)));
+        assert!(verify_totp(secret, "94287082", 119, 30, 8, "sha1", 1).unwrap().contains("false"));
+        let result: serde_json::Value = serde_json::from_str(&verify_hotp(secret, "969429", 1, 2, 6, "sha1").unwrap()).unwrap();
+        assert_eq!(result["next_counter"], 4);
+        assert!(verify_hotp(secret, "969429", 0, 2, 6, "sha1").unwrap().contains("false"));
+        let last = hotp(secret, u64::MAX, 6, "sha1").unwrap();
+        let result: serde_json::Value = serde_json::from_str(&verify_hotp(secret, &last, u64::MAX, 2, 6, "sha1").unwrap()).unwrap();
+        assert_eq!((result["counter"].as_u64(), result["next_counter"].is_null()), (Some(u64::MAX), true));
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = build_uri("totp", b"12345678901234567890", "alice@example.com", "Example Co", "SHA256", 8, 60).unwrap();
+        assert_eq!(uri, "otpauth://totp/Example%20Co:alice%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Example%20Co&algorithm=SHA256&digits=8&period=60");
+        let key = parse_uri(&uri).unwrap();
+        assert_eq!((key.issuer.as_str(), key.account.as_str(), key.algorithm, key.digits, key.period), ("Example Co", "alice@example.com", "sha256", 8, 60));
+        assert_eq!(key.secret, b"12345678901234567890");
+        assert_eq!(uri_code("otpauth://hotp/ACME:bob?secret=gezdgnbvgy3tqojqgezdgnbvgy3tqojq&counter=3", 0).unwrap(), "969429");
+        assert_eq!(parse_uri("otpauth://hotp/x?secret=GEZDGNBV").err().unwrap(), "HOTP URIs need a counter parameter");
+        assert!(parse_uri("otpauth://totp/x?secret=GEZ1").is_err());
+        assert_eq!(parse_uri("otpauth://totp/x?secret=GEZDGNBV&digits=4294967302").err().unwrap(), "invalid digits parameter: 4294967302");
+    }
+}
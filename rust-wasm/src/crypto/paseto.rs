@@ -0,0 +1,465 @@
+// PASETO v3 / v4 tokens and PASERK key serialization.
+//
+// v4.local:  XChaCha20 with keys from keyed BLAKE2b, BLAKE2b-256 MAC
+// v4.public: Ed25519
+// v3.local:  AES-256-CTR with keys from HKDF-SHA384, HMAC-SHA384
+// v3.public: ECDSA P-384 / SHA-384 over the compressed public key and the message
+// Footers and implicit assertions are authenticated through PAE (pre-authentication encoding).
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use getrandom::getrandom;
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+use serde_json::json;
+
+use super::ecdh::Curve;
+use super::{block_modes, chacha20_cipher, ecdsa, eddsa, kdf};
+use crate::encoding::key_container::{self, Key};
+use crate::hash::{blake2b, digest, hmac};
+
+const NONCE_BYTES: usize = 32;
+const V4_TAG_BYTES: usize = 32;
+const V3_TAG_BYTES: usize = 48;
+const LOCAL_KEY_BYTES: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Version {
+    V3,
+    V4,
+}
+
+impl Version {
+    fn parse(name: &str) -> Result<Version, String> {
+        match name {
+            "v3" | "3" => Ok(Version::V3),
+            "v4" | "4" => Ok(Version::V4),
+            _ => Err(format!("unsupported PASETO version: {}", name)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Version::V3 => "v3",
+            Version::V4 => "v4",
+        }
+    }
+}
+
+/// Key material for either version, from raw bytes, PASERK, PEM, DER or JWK
+enum PasetoKey {
+    /// Symmetric key; the version is known when it came from a PASERK
+    Local(Option<Version>, Vec<u8>),
+    V4Public(Vec<u8>),
+    V4Secret(Vec<u8>),
+    /// Compressed P-384 point
+    V3Public(Vec<u8>),
+    V3Secret(Vec<u8>),
+}
+
+fn b64(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn unb64(part: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_NO_PAD.decode(part).map_err(|_| "invalid base64url".to_string())
+}
+
+/// PAE: the piece count and each piece's length as little-endian u64 (top bit clear), then the piece
+fn pae(pieces: &[&[u8]]) -> Vec<u8> {
+    let le64 = |n: usize| ((n as u64) & (u64::MAX >> 1)).to_le_bytes();
+    let mut out = le64(pieces.len()).to_vec();
+    for piece in pieces {
+        out.extend_from_slice(&le64(piece.len()));
+        out.extend_from_slice(piece);
+    }
+    out
+}
+
+fn compress_p384(point: &[u8]) -> Result<Vec<u8>, String> {
+    let key = p384::PublicKey::from_sec1_bytes(point).map_err(|_| "invalid P-384 public key".to_string())?;
+    Ok(key.to_encoded_point(true).as_bytes().to_vec())
+}
+
+// ----------------------------------------------------------------------------
+// Keys and PASERK
+// ----------------------------------------------------------------------------
+
+fn read_paserk(text: &str) -> Result<PasetoKey, String> {
+    let mut parts = text.splitn(3, '.');
+    let (version, kind, data) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(v), Some(k), Some(d)) => (v, k, unb64(d)?),
+        _ => return Err("invalid PASERK".to_string()),
+    };
+    let version = match version {
+        "k3" => Version::V3,
+        "k4" => Version::V4,
+        _ => return Err(format!("unsupported PASERK version: {}", version)),
+    };
+    let expect = |len: usize| {
+        if data.len() == len { Ok(()) } else { Err(format!("{}.{} key must be {} bytes", version.name(), kind, len)) }
+    };
+    match (version, kind) {
+        (_, "local") => expect(LOCAL_KEY_BYTES).map(|_| PasetoKey::Local(Some(version), data.clone())),
+        (Version::V4, "public") => expect(32).map(|_| PasetoKey::V4Public(data.clone())),
+        (Version::V4, "secret") => {
+            // Seed followed by the public key, which must match
+            expect(64)?;
+            if eddsa::public_key(eddsa::Curve::Ed25519, &data[..32])? != data[32..] {
+                return Err("k4.secret public key half does not match the seed".to_string());
+            }
+            Ok(PasetoKey::V4Secret(data[..32].to_vec()))
+        }
+        (Version::V3, "public") => {
+            expect(49)?;
+            compress_p384(&data).map(PasetoKey::V3Public)
+        }
+        (Version::V3, "secret") => {
+            expect(48)?;
+            ecdsa::public_key(Curve::P384, &data, true)?;
+            Ok(PasetoKey::V3Secret(data))
+        }
+        _ => Err(format!("unsupported PASERK type: k{}.{}", &version.name()[1..], kind)),
+    }
+}
+
+/// PASERK text, then PEM / DER / JWK keys (Ed25519 or P-384), otherwise raw symmetric key bytes
+fn read_key(input: &[u8]) -> Result<PasetoKey, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    if text.starts_with("k3.") || text.starts_with("k4.") {
+        return read_paserk(text);
+    }
+    let Ok(container) = key_container::parse(input, "") else {
+        return Ok(PasetoKey::Local(None, input.to_vec()));
+    };
+    match container.key {
+        Key::Ed(eddsa::ImportedKey { curve: eddsa::Curve::Ed25519, private, public }) => {
+            Ok(private.map_or(PasetoKey::V4Public(public), PasetoKey::V4Secret))
+        }
+        Key::Ec { curve: Curve::P384, private, public } => match private {
+            Some(d) => Ok(PasetoKey::V3Secret(d)),
+            None => compress_p384(&public).map(PasetoKey::V3Public),
+        },
+        _ => Err("PASETO public tokens need an Ed25519 (v4) or P-384 (v3) key".to_string()),
+    }
+}
+
+fn local_key(input: &[u8], version: Version) -> Result<Vec<u8>, String> {
+    match read_key(input)? {
+        PasetoKey::Local(Some(v), _) if v != version => Err(format!("key is for {}, token is {}", v.name(), version.name())),
+        PasetoKey::Local(_, key) if key.len() == LOCAL_KEY_BYTES => Ok(key),
+        PasetoKey::Local(..) => Err(format!("local keys must be {} bytes", LOCAL_KEY_BYTES)),
+        _ => Err("local tokens need a symmetric key".to_string()),
+    }
+}
+
+/// PASERK for a key: kind is local, public, secret or the identifiers lid, pid, sid. Public
+/// PASERKs and pids can be derived from secret keys. `version` only matters for raw local keys.
+pub fn paserk(key: &[u8], version: &str, kind: &str) -> Result<String, String> {
+    let key = read_key(key)?;
+    let base_kind = match kind {
+        "lid" => "local",
+        "pid" => "public",
+        "sid" => "secret",
+        other => other,
+    };
+    let (version, data) = match (key, base_kind) {
+        (PasetoKey::Local(v, key), "local") => {
+            let v = v.map_or_else(|| Version::parse(version), Ok)?;
+            if key.len() != LOCAL_KEY_BYTES {
+                return Err(format!("local keys must be {} bytes", LOCAL_KEY_BYTES));
+            }
+            (v, key)
+        }
+        (PasetoKey::V4Secret(seed), "secret") => {
+            let public = eddsa::public_key(eddsa::Curve::Ed25519, &seed)?;
+            (Version::V4, [seed, public].concat())
+        }
+        (PasetoKey::V4Secret(seed), "public") => (Version::V4, eddsa::public_key(eddsa::Curve::Ed25519, &seed)?),
+        (PasetoKey::V4Public(public), "public") => (Version::V4, public),
+        (PasetoKey::V3Secret(d), "secret") => (Version::V3, d),
+        (PasetoKey::V3Secret(d), "public") => (Version::V3, ecdsa::public_key(Curve::P384, &d, true)?),
+        (PasetoKey::V3Public(public), "public") => (Version::V3, public),
+        (_, "local" | "public" | "secret") => return Err(format!("key cannot be serialized as {}", kind)),
+        _ => return Err(format!("unknown PASERK type: {}", kind)),
+    };
+    let k = format!("k{}", &version.name()[1..]);
+    let serialized = format!("{}.{}.{}", k, base_kind, b64(&data));
+    if kind == base_kind {
+        return Ok(serialized);
+    }
+    // ID: header || base64url(H(header || PASERK)) with a 33-byte BLAKE2b (v4) or truncated SHA-384 (v3)
+    let header = format!("{}.{}.", k, kind);
+    let input = format!("{}{}", header, serialized);
+    let id = match version {
+        Version::V4 => blake2b::digest_var(input.as_bytes(), 33)?,
+        Version::V3 => digest::digest("sha384", input.as_bytes())?[..33].to_vec(),
+    };
+    Ok(format!("{}{}", header, b64(&id)))
+}
+
+// ----------------------------------------------------------------------------
+// Tokens
+// ----------------------------------------------------------------------------
+
+struct Token {
+    version: Version,
+    local: bool,
+    payload: Vec<u8>,
+    footer: Vec<u8>,
+}
+
+impl Token {
+    fn header(&self) -> String {
+        format!("{}.{}.", self.version.name(), if self.local { "local" } else { "public" })
+    }
+}
+
+fn parse_token(token: &str) -> Result<Token, String> {
+    let parts: Vec<&str> = token.trim().split('.').collect();
+    if !(3..=4).contains(&parts.len()) {
+        return Err("a PASETO has 3 or 4 dot-separated parts".to_string());
+    }
+    let local = match parts[1] {
+        "local" => true,
+        "public" => false,
+        other => return Err(format!("unknown PASETO purpose: {}", other)),
+    };
+    Ok(Token {
+        version: Version::parse(parts[0])?,
+        local,
+        payload: unb64(parts[2])?,
+        footer: parts.get(3).map_or(Ok(Vec::new()), |f| unb64(f))?,
+    })
+}
+
+fn assemble(header: &str, body: &[u8], footer: &[u8]) -> String {
+    if footer.is_empty() {
+        format!("{}{}", header, b64(body))
+    } else {
+        format!("{}{}.{}", header, b64(body), b64(footer))
+    }
+}
+
+/// (encryption key, counter nonce, authentication key)
+type LocalKeys = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+/// Per-token keys for a local token, derived from the shared key and the random nonce
+fn local_keys(version: Version, key: &[u8], nonce: &[u8]) -> Result<LocalKeys, String> {
+    let info = |label: &[u8]| [label, nonce].concat();
+    match version {
+        Version::V4 => {
+            let mut tmp = blake2b::mac(key, &info(b"paseto-encryption-key"), 56)?;
+            let n2 = tmp.split_off(32);
+            Ok((tmp, n2, blake2b::mac(key, &info(b"paseto-auth-key-for-aead"), 32)?))
+        }
+        Version::V3 => {
+            let mut tmp = kdf::hkdf("sha384", key, &[], &info(b"paseto-encryption-key"), 48)?;
+            let n2 = tmp.split_off(32);
+            Ok((tmp, n2, kdf::hkdf("sha384", key, &[], &info(b"paseto-auth-key-for-aead"), 48)?))
+        }
+    }
+}
+
+fn local_cipher(version: Version, key: &[u8], n2: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    match version {
+        Version::V4 => {
+            let mut out = data.to_vec();
+            chacha20_cipher::xchacha20_apply(key, n2, &mut out)?;
+            Ok(out)
+        }
+        Version::V3 => block_modes::encrypt("aes256", "ctr", "none", key, n2, data),
+    }
+}
+
+fn local_tag(version: Version, auth_key: &[u8], pre_auth: &[u8]) -> Result<Vec<u8>, String> {
+    match version {
+        Version::V4 => blake2b::mac(auth_key, pre_auth, V4_TAG_BYTES),
+        Version::V3 => hmac::mac("sha384", auth_key, pre_auth),
+    }
+}
+
+fn encrypt_with_nonce(version: Version, key: &[u8], message: &[u8], footer: &[u8], implicit: &[u8], nonce: &[u8]) -> Result<String, String> {
+    let header = format!("{}.local.", version.name());
+    let (enc_key, n2, auth_key) = local_keys(version, key, nonce)?;
+    let ciphertext = local_cipher(version, &enc_key, &n2, message)?;
+    let tag = local_tag(version, &auth_key, &pae(&[header.as_bytes(), nonce, &ciphertext, footer, implicit]))?;
+    Ok(assemble(&header, &[nonce, &ciphertext, &tag].concat(), footer))
+}
+
+/// Encrypt a local token; version: v3 | v4. `key` is 32 raw bytes or a k3/k4.local PASERK.
+pub fn encrypt(version: &str, key: &[u8], message: &[u8], footer: &[u8], implicit: &[u8]) -> Result<String, String> {
+    let version = Version::parse(version)?;
+    let key = local_key(key, version)?;
+    let mut nonce = [0u8; NONCE_BYTES];
+    getrandom(&mut nonce).map_err(|e| e.to_string())?;
+    encrypt_with_nonce(version, &key, message, footer, implicit, &nonce)
+}
+
+/// Decrypt a v3 / v4 local token; returns (message, footer)
+pub fn decrypt(key: &[u8], token: &str, implicit: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let token = parse_token(token)?;
+    if !token.local {
+        return Err("not a local token".to_string());
+    }
+    let key = local_key(key, token.version)?;
+    let tag_len = if token.version == Version::V4 { V4_TAG_BYTES } else { V3_TAG_BYTES };
+    if token.payload.len() < NONCE_BYTES + tag_len {
+        return Err("token is too short".to_string());
+    }
+    let (nonce, rest) = token.payload.split_at(NONCE_BYTES);
+    let (ciphertext, tag) = rest.split_at(rest.len() - tag_len);
+    let (enc_key, n2, auth_key) = local_keys(token.version, &key, nonce)?;
+    let expected = local_tag(token.version, &auth_key, &pae(&[token.header().as_bytes(), nonce, ciphertext, &token.footer, implicit]))?;
+    if expected.iter().zip(tag).fold(0u8, |acc, (a, b)| acc | (a ^ b)) != 0 {
+        return Err("invalid token: authentication failed".to_string());
+    }
+    Ok((local_cipher(token.version, &enc_key, &n2, ciphertext)?, token.footer))
+}
+
+/// Sign a public token; the version follows the key (Ed25519 → v4, P-384 → v3)
+pub fn sign(key: &[u8], message: &[u8], footer: &[u8], implicit: &[u8]) -> Result<String, String> {
+    let (header, signature) = match read_key(key)? {
+        PasetoKey::V4Secret(seed) => {
+            let header = "v4.public.";
+            (header, eddsa::sign("ed25519", &seed, &pae(&[header.as_bytes(), message, footer, implicit]), b"")?)
+        }
+        PasetoKey::V3Secret(d) => {
+            let header = "v3.public.";
+            let public = ecdsa::public_key(Curve::P384, &d, true)?;
+            let m2 = pae(&[&public, header.as_bytes(), message, footer, implicit]);
+            (header, ecdsa::sign(Curve::P384, &d, &m2, "sha384", false)?)
+        }
+        PasetoKey::V4Public(_) | PasetoKey::V3Public(_) => return Err("a secret key is required".to_string()),
+        PasetoKey::Local(..) => return Err("public tokens need an Ed25519 or P-384 secret key".to_string()),
+    };
+    Ok(assemble(header, &[message, &signature].concat(), footer))
+}
+
+/// Verify a public token with the public (or secret) key; returns (message, footer)
+pub fn verify(key: &[u8], token: &str, implicit: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let token = parse_token(token)?;
+    if token.local {
+        return Err("not a public token".to_string());
+    }
+    let header = token.header();
+    let valid = match (token.version, read_key(key)?) {
+        (Version::V4, key @ (PasetoKey::V4Public(_) | PasetoKey::V4Secret(_))) => {
+            let public = match key {
+                PasetoKey::V4Secret(seed) => eddsa::public_key(eddsa::Curve::Ed25519, &seed)?,
+                PasetoKey::V4Public(public) => public,
+                _ => unreachable!(),
+            };
+            let split = token.payload.len().checked_sub(64).ok_or("token is too short")?;
+            let (message, signature) = token.payload.split_at(split);
+            let m2 = pae(&[header.as_bytes(), message, &token.footer, implicit]);
+            eddsa::verify("ed25519", &public, &m2, signature, b"")?.then_some(message)
+        }
+        (Version::V3, key @ (PasetoKey::V3Public(_) | PasetoKey::V3Secret(_))) => {
+            let public = match key {
+                PasetoKey::V3Secret(d) => ecdsa::public_key(Curve::P384, &d, true)?,
+                PasetoKey::V3Public(public) => public,
+                _ => unreachable!(),
+            };
+            let split = token.payload.len().checked_sub(96).ok_or("token is too short")?;
+            let (message, signature) = token.payload.split_at(split);
+            let m2 = pae(&[&public, header.as_bytes(), message, &token.footer, implicit]);
+            ecdsa::verify(Curve::P384, &public, &m2, "sha384", signature)?.then_some(message)
+        }
+        (version, _) => return Err(format!("key cannot verify {} public tokens", version.name())),
+    };
+    match valid {
+        Some(message) => Ok((message.to_vec(), token.footer.clone())),
+        None => Err("invalid token: signature verification failed".to_string()),
+    }
+}
+
+/// UTF-8 text, or base64url for binary data
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| b64(bytes))
+}
+
+/// JSON `{message, footer}` for the (message, footer) returned by `decrypt` / `verify`
+pub fn report((message, footer): (Vec<u8>, Vec<u8>)) -> String {
+    json!({ "message": text(&message), "footer": text(&footer) }).to_string()
+}
+
+/// Version, purpose and (unauthenticated) footer of a token; public token messages are
+/// included as well, although they are not verified here
+pub fn inspect(token: &str) -> Result<String, String> {
+    let parsed = parse_token(token)?;
+    let signature_len = if parsed.version == Version::V4 { 64 } else { 96 };
+    let message = (!parsed.local).then(|| text(&parsed.payload[..parsed.payload.len().saturating_sub(signature_len)]));
+    Ok(json!({
+        "version": parsed.version.name(),
+        "purpose": if parsed.local { "local" } else { "public" },
+        "footer": text(&parsed.footer),
+        "message": message,
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &[u8] = br#"{"data":"this is a secret message","exp":"2022-01-01T00:00:00+00:00"}"#;
+
+    fn local_test_key() -> Vec<u8> {
+        (0x70u8..0x90).collect()
+    }
+
+    // Local tokens from a Python reference built on hashlib / cryptography primitives; the first
+    // is the official 4-E-1 vector (zero nonce)
+    #[test]
+    fn test_local_vectors() {
+        let key = local_test_key();
+        let token = "v4.local.AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAQAr68PS4AXe7If_ZgesdkUMvSwscFlAl1pk5HC0e8kApeaqMfGo_7OpBnwJOAbY9V7WU6abu74MmcUE8YWAiaArVI8XJ5hOb_4v9RmDkneN0S92dx0OW4pgy7omxgf3S8c3LlQg";
+        assert_eq!(encrypt_with_nonce(Version::V4, &key, MESSAGE, b"", b"", &[0u8; 32]).unwrap(), token);
+        assert_eq!(decrypt(&key, token, b"").unwrap().0, MESSAGE);
+
+        let footer = br#"{"kid":"zVhMiPBP9fRf2snEcT7gFTioeA9COcSy9DfZ8Bqdd8O"}"#;
+        let token = "v4.local.AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh_SGDTXZedISBSPYmj5y6y2-w71UR-96tD6RzBfiy1KVaF_KLNV7Zr9TRpRprIDrEIcEIwoZvTafEjSES9nfrwGpNA_XX8J1ehnJkdPsP7JC31kSVeFTF7yYjBpwB9OoAG73b2g4g.eyJraWQiOiJ6VmhNaVBCUDlmUmYyc25FY1Q3Z0ZUaW9lQTlDT2NTeTlEZlo4QnFkZDhPIn0";
+        let (message, got_footer) = decrypt(&key, token, br#"{"test-vector":"4-E-9"}"#).unwrap();
+        assert_eq!((message.as_slice(), got_footer.as_slice()), (MESSAGE, &footer[..]));
+        assert!(decrypt(&key, token, b"").is_err());
+
+        let token = "v3.local.AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh-wjdU5zeIxLUPOs-uBsk9VkTHCgRQYBEzijVhv9SMaKwyLbBGNj7GQibM_DIGkD4w7TX-cL3t4LQluqKfC-HfJwfnPHOYd37RYNfWE4-3VAqVkDw18pA5KpRIKfmIbR5yOD4EEAGY0lnprlhTqQnMB0u326gE.eyJraWQiOiJ4In0";
+        let nonce: Vec<u8> = (0u8..32).collect();
+        assert_eq!(encrypt_with_nonce(Version::V3, &key, MESSAGE, br#"{"kid":"x"}"#, br#"{"i":1}"#, &nonce).unwrap(), token);
+        assert_eq!(decrypt(&key, token, br#"{"i":1}"#).unwrap().0, MESSAGE);
+
+        // PASERK keys carry their version
+        let paserk_key = paserk(&key, "v4", "local").unwrap();
+        assert_eq!(paserk_key, "k4.local.cHFyc3R1dnd4eXp7fH1-f4CBgoOEhYaHiImKi4yNjo8");
+        assert_eq!(paserk(&key, "v4", "lid").unwrap(), "k4.lid.iVtYQDjr5gEijCSjJC3fQaJm7nCeQSeaty0Jixy8dbsk");
+        assert_eq!(decrypt(paserk_key.as_bytes(), &encrypt("v4", paserk_key.as_bytes(), b"hi", b"", b"").unwrap(), b"").unwrap().0, b"hi");
+        assert!(encrypt("v3", paserk_key.as_bytes(), b"hi", b"", b"").is_err());
+    }
+
+    #[test]
+    fn test_public_vectors() {
+        // v4.public is deterministic (Ed25519 seed 00..1f) and matches Python cryptography
+        let secret = "k4.secret.AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8DoQe_884Qvh1w3RjnS8CZZ-TWMJulDV8d3IZkElUxuA";
+        let token = "v4.public.eyJkYXRhIjoidGhpcyBpcyBhIHNlY3JldCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9cabdhWQcNw_lL1AaUI9Xu79R6qyChZb65csh-ssazvM6lGULHuHpZKm5610jQ9wnXpY5yWFxwqUqmkR_71wjAw.eyJraWQiOiJwIn0";
+        assert_eq!(sign(secret.as_bytes(), MESSAGE, br#"{"kid":"p"}"#, b"imp").unwrap(), token);
+        let public = paserk(secret.as_bytes(), "", "public").unwrap();
+        assert_eq!(public, "k4.public.A6EHv_POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg");
+        assert_eq!(verify(public.as_bytes(), token, b"imp").unwrap().0, MESSAGE);
+        assert!(verify(public.as_bytes(), token, b"other").is_err());
+        let info: serde_json::Value = serde_json::from_str(&inspect(token).unwrap()).unwrap();
+        assert_eq!(info["footer"], r#"{"kid":"p"}"#);
+        let report: serde_json::Value = serde_json::from_str(&report(verify(public.as_bytes(), token, b"imp").unwrap())).unwrap();
+        assert_eq!(report["message"].as_str().unwrap().as_bytes(), MESSAGE);
+        assert_eq!(report["footer"], r#"{"kid":"p"}"#);
+
+        // v3.public signed by Python cryptography (randomized ECDSA)
+        let token = "v3.public.eyJkYXRhIjoidGhpcyBpcyBhIHNlY3JldCBtZXNzYWdlIiwiZXhwIjoiMjAyMi0wMS0wMVQwMDowMDowMCswMDowMCJ9YPdYyDCbWW0g4v6lc0MGORt9P_SgP6vBvJhtZ1zR8_sLbEbdmKstDX5CQit3a6q9ArZGaP7JKN2qg4xY0Qo0K-cu7x0B14jEJ4hRIxFbQfiTaAddfcUFvYuoEq2FKpx1";
+        let secret = "k3.secret.AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyAhIiMkJSYnKCkqKywtLi8w";
+        let public = "k3.public.A8dvIoPdqVzUmw7Z5zPSkER043IW8SThPSyatM8BAhxJrZyrs9C5dJmu8vCrMT-gKA";
+        assert_eq!(paserk(secret.as_bytes(), "", "public").unwrap(), public);
+        assert_eq!(paserk(public.as_bytes(), "", "pid").unwrap(), "k3.pid.6CH87KZt4TUbC9s09D8lMT5r4MbZeNS-eb8co63l4541");
+        assert_eq!(verify(public.as_bytes(), token, b"").unwrap().0, MESSAGE);
+        let ours = sign(secret.as_bytes(), b"msg", b"f", b"i").unwrap();
+        assert_eq!(verify(public.as_bytes(), &ours, b"i").unwrap(), (b"msg".to_vec(), b"f".to_vec()));
+        assert!(verify(public.as_bytes(), token.replacen("v3.public.eyJ", "v3.public.eyK", 1).as_str(), b"").is_err());
+    }
+}
@@ -1,7 +1,9 @@
 // PBKDF2 key derivation for AES keys
 
+use md5_digest::Md5;
 use pbkdf2::pbkdf2_hmac;
-use sha2::Sha256;
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
 
 pub fn derive_aes128_key(passphrase: &str, salt: &[u8]) -> [u8; 16] {
     let mut key = [0u8; 16];
@@ -33,3 +35,17 @@ pub fn derive_tdes_key(passphrase: &str, salt: &[u8], three_key: bool) -> Vec<u8
     pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, 10000, &mut key);
     key
 }
+
+/// PBKDF2-HMAC with the hash chosen by name: md5 | sha1 | sha256 | sha384 | sha512
+pub fn derive(hash: &str, password: &[u8], salt: &[u8], iterations: u32, len: usize) -> Result<Vec<u8>, String> {
+    let mut key = vec![0u8; len];
+    match hash {
+        "md5" => pbkdf2_hmac::<Md5>(password, salt, iterations, &mut key),
+        "sha1" => pbkdf2_hmac::<Sha1>(password, salt, iterations, &mut key),
+        "sha256" => pbkdf2_hmac::<Sha256>(password, salt, iterations, &mut key),
+        "sha384" => pbkdf2_hmac::<Sha384>(password, salt, iterations, &mut key),
+        "sha512" => pbkdf2_hmac::<Sha512>(password, salt, iterations, &mut key),
+        _ => return Err(format!("unsupported digest for PBKDF2: {}", hash)),
+    }
+    Ok(key)
+}
@@ -0,0 +1,122 @@
+// Salsa20 family stream ciphers - Salsa20/20, Salsa20/12, Salsa20/8 (8-byte nonce)
+// and XSalsa20 (24-byte extended nonce, as used by NaCl)
+use getrandom::getrandom;
+use salsa20::cipher::{KeyIvInit, StreamCipher};
+use salsa20::{Salsa12, Salsa20, Salsa8, XSalsa20};
+
+// Nonce length for a variant name
+pub fn nonce_len(variant: &str) -> Result<usize, String> {
+    match variant {
+        "salsa20" | "salsa20/12" | "salsa20/8" => Ok(8),
+        "xsalsa20" => Ok(24),
+        _ => Err(format!("unknown Salsa20 variant: {}", variant)),
+    }
+}
+
+pub fn random_nonce(variant: &str) -> Result<Vec<u8>, String> {
+    let mut nonce = vec![0u8; nonce_len(variant)?];
+    getrandom(&mut nonce).expect("random nonce generation failed");
+    Ok(nonce)
+}
+
+// XOR the keystream into `data` (encryption and decryption are the same operation)
+pub fn apply_keystream(variant: &str, key: &[u8], nonce: &[u8], data: &mut [u8]) -> Result<(), String> {
+    if key.len() != 32 {
+        return Err("key must be 32 bytes".to_string());
+    }
+    if nonce.len() != nonce_len(variant)? {
+        return Err(format!("nonce must be {} bytes for {}", nonce_len(variant)?, variant));
+    }
+    match variant {
+        "salsa20" => Salsa20::new(key.into(), nonce.into()).apply_keystream(data),
+        "salsa20/12" => Salsa12::new(key.into(), nonce.into()).apply_keystream(data),
+        "salsa20/8" => Salsa8::new(key.into(), nonce.into()).apply_keystream(data),
+        _ => XSalsa20::new(key.into(), nonce.into()).apply_keystream(data),
+    }
+    Ok(())
+}
+
+pub fn process(variant: &str, key: &[u8], nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut buf = data.to_vec();
+    apply_keystream(variant, key, nonce, &mut buf)?;
+    Ok(buf)
+}
+
+fn key_from_text(key_text: &str) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    let key_bytes = key_text.as_bytes();
+    let len = key_bytes.len().min(32);
+    key[..len].copy_from_slice(&key_bytes[..len]);
+    key
+}
+
+// Auto-nonce version: generates a nonce and prepends it: [NONCE][CIPHERTEXT]
+pub fn encrypt_auto_nonce(variant: &str, plaintext: &str, key_text: &str) -> Result<Vec<u8>, String> {
+    let key = key_from_text(key_text);
+    let nonce = random_nonce(variant)?;
+    let ciphertext = process(variant, &key, &nonce, plaintext.as_bytes())?;
+
+    let mut result = Vec::with_capacity(nonce.len() + ciphertext.len());
+    result.extend_from_slice(&nonce);
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+pub fn decrypt_auto_nonce(variant: &str, combined: &[u8], key_text: &str) -> Result<String, String> {
+    let n = nonce_len(variant)?;
+    if combined.len() < n {
+        return Err("ciphertext too short".to_string());
+    }
+    let key = key_from_text(key_text);
+    let data = process(variant, &key, &combined[..n], &combined[n..])?;
+    String::from_utf8(data).map_err(|_| "not utf-8".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Vec<u8> {
+        (0u8..32).collect()
+    }
+
+    // Keystreams cross-checked against libsodium crypto_stream_salsa20{,12,8} / crypto_stream_xsalsa20
+    #[test]
+    fn test_libsodium_keystreams() {
+        let zeros = [0u8; 64];
+        let nonce8: Vec<u8> = (0u8..8).collect();
+        let nonce24: Vec<u8> = (0u8..24).collect();
+        assert_eq!(
+            hex::encode(process("salsa20", &key(), &nonce8, &zeros).unwrap()),
+            "2ead0f5f185729ced672b3a928e454f72fdb44a87b9cd8d219e4ec14aef9c6bc77bf057f5659d7753848f8d3fe769ca5fdd8057d46326990e5f136e2fcb7bb7c"
+        );
+        assert_eq!(
+            hex::encode(process("salsa20/12", &key(), &nonce8, &zeros).unwrap()),
+            "06c9dd540af341e7e77e5d604594247d13accb164c02b45db37d1abdcddb501e7bdf1a99c6ac8ad2d71c14424f03a056acfb41cfbaea8c84881e7fcbf0576c33"
+        );
+        assert_eq!(
+            hex::encode(process("salsa20/8", &key(), &nonce8, &zeros).unwrap()),
+            "6f305a9a55da5f8a79a7e372135db532d05c6574de2623a23edb4d955062cbd68d9324c1db60747f6713d9d2f9c446a743ba8351e9c7cc064a114dce38de5c56"
+        );
+        assert_eq!(
+            hex::encode(process("xsalsa20", &key(), &nonce24, &zeros).unwrap()),
+            "7cb660afdd9ec6468f57dd6d2433f93428fd82cd7386c5471a24d8ad2a525b6e5eff384fc7caa210bb3c8f3e688f4a9752a546df8c253fef17a2679455c7a1e1"
+        );
+    }
+
+    #[test]
+    fn test_auto_nonce_roundtrip() {
+        for variant in ["salsa20", "salsa20/12", "salsa20/8", "xsalsa20"] {
+            let combined = encrypt_auto_nonce(variant, "Hello, Salsa20!", "passphrase").unwrap();
+            assert_eq!(combined.len(), nonce_len(variant).unwrap() + 15);
+            assert_eq!(decrypt_auto_nonce(variant, &combined, "passphrase").unwrap(), "Hello, Salsa20!");
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(process("salsa20", &[0u8; 16], &[0u8; 8], b"x").is_err());
+        assert!(process("xsalsa20", &[0u8; 32], &[0u8; 8], b"x").is_err());
+        assert!(process("salsa20/4", &[0u8; 32], &[0u8; 8], b"x").is_err());
+    }
+}
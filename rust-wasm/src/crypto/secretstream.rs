@@ -0,0 +1,265 @@
+// libsodium crypto_secretstream_xchacha20poly1305
+//
+// Header: 24 random bytes. Subkey = HChaCha20(key, header[0..16]),
+// nonce = [COUNTER32 LE][INONCE8] with INONCE = header[16..24] and the counter starting at 1.
+// Each message: [ENCRYPTED_TAG1][CIPHERTEXT][MAC16], authenticated with Poly1305 over the
+// ChaCha20-IETF keystream; the MAC is folded back into INONCE after every message.
+use chacha20::cipher::consts::U10;
+use chacha20::cipher::{KeyIvInit, StreamCipher, StreamCipherSeek};
+use chacha20::{hchacha, ChaCha20};
+use getrandom::getrandom;
+use poly1305::universal_hash::KeyInit;
+use poly1305::Poly1305;
+
+pub const KEY_BYTES: usize = 32;
+pub const HEADER_BYTES: usize = 24;
+pub const ABYTES: usize = 17;
+
+pub const TAG_MESSAGE: u8 = 0x00;
+pub const TAG_PUSH: u8 = 0x01;
+pub const TAG_REKEY: u8 = 0x02;
+pub const TAG_FINAL: u8 = TAG_PUSH | TAG_REKEY;
+
+const COUNTER_BYTES: usize = 4;
+const INONCE_BYTES: usize = 8;
+
+struct State {
+    k: [u8; 32],
+    nonce: [u8; 12],
+}
+
+impl State {
+    fn from_header(key: &[u8], header: &[u8]) -> Result<Self, String> {
+        if key.len() != KEY_BYTES {
+            return Err("key must be 32 bytes".to_string());
+        }
+        if header.len() != HEADER_BYTES {
+            return Err("header must be 24 bytes".to_string());
+        }
+        let subkey = hchacha::<U10>(key.into(), header[..16].into());
+        let mut state = State { k: subkey.into(), nonce: [0u8; 12] };
+        state.nonce[COUNTER_BYTES..].copy_from_slice(&header[16..]);
+        state.counter_reset();
+        Ok(state)
+    }
+
+    fn counter_reset(&mut self) {
+        self.nonce[..COUNTER_BYTES].copy_from_slice(&1u32.to_le_bytes());
+    }
+
+    fn keystream_xor(&self, block_counter: u32, data: &mut [u8]) {
+        let mut cipher = ChaCha20::new(&self.k.into(), &self.nonce.into());
+        cipher.seek(block_counter as u64 * 64);
+        cipher.apply_keystream(data);
+    }
+
+    fn rekey(&mut self) {
+        let mut new_key_and_inonce = [0u8; KEY_BYTES + INONCE_BYTES];
+        new_key_and_inonce[..KEY_BYTES].copy_from_slice(&self.k);
+        new_key_and_inonce[KEY_BYTES..].copy_from_slice(&self.nonce[COUNTER_BYTES..]);
+        self.keystream_xor(0, &mut new_key_and_inonce);
+        self.k.copy_from_slice(&new_key_and_inonce[..KEY_BYTES]);
+        self.nonce[COUNTER_BYTES..].copy_from_slice(&new_key_and_inonce[KEY_BYTES..]);
+        self.counter_reset();
+    }
+
+    // Poly1305 over: ad, pad, the 64-byte tag block, ciphertext, pad, le64(adlen), le64(64 + mlen)
+    fn mac(&self, ad: &[u8], block: &[u8; 64], ciphertext: &[u8]) -> [u8; 16] {
+        let mut poly_key = [0u8; 32];
+        self.keystream_xor(0, &mut poly_key);
+
+        let mut input = Vec::with_capacity(ad.len() + ciphertext.len() + 112);
+        input.extend_from_slice(ad);
+        input.resize(input.len() + ((0x10 - ad.len()) & 0xf), 0);
+        input.extend_from_slice(block);
+        input.extend_from_slice(ciphertext);
+        // libsodium computes this pad as (0x10 - 64 + mlen) & 0xf
+        input.resize(input.len() + (0x10usize.wrapping_sub(64).wrapping_add(ciphertext.len()) & 0xf), 0);
+        input.extend_from_slice(&(ad.len() as u64).to_le_bytes());
+        input.extend_from_slice(&((64 + ciphertext.len()) as u64).to_le_bytes());
+
+        Poly1305::new(&poly_key.into()).compute_unpadded(&input).into()
+    }
+
+    fn advance(&mut self, mac: &[u8; 16], tag: u8) {
+        for (n, m) in self.nonce[COUNTER_BYTES..].iter_mut().zip(mac) {
+            *n ^= m;
+        }
+        let mut counter = [0u8; COUNTER_BYTES];
+        counter.copy_from_slice(&self.nonce[..COUNTER_BYTES]);
+        let next = u32::from_le_bytes(counter).wrapping_add(1);
+        self.nonce[..COUNTER_BYTES].copy_from_slice(&next.to_le_bytes());
+        if tag & TAG_REKEY != 0 || next == 0 {
+            self.rekey();
+        }
+    }
+}
+
+// Encrypting side: init_push / push
+pub struct Push {
+    state: State,
+    header: [u8; HEADER_BYTES],
+}
+
+impl Push {
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        let mut header = [0u8; HEADER_BYTES];
+        getrandom(&mut header).expect("random header generation failed");
+        Self::with_header(key, &header)
+    }
+
+    pub fn with_header(key: &[u8], header: &[u8]) -> Result<Self, String> {
+        let state = State::from_header(key, header)?;
+        let mut h = [0u8; HEADER_BYTES];
+        h.copy_from_slice(header);
+        Ok(Push { state, header: h })
+    }
+
+    pub fn header(&self) -> Vec<u8> {
+        self.header.to_vec()
+    }
+
+    pub fn push(&mut self, message: &[u8], ad: &[u8], tag: u8) -> Vec<u8> {
+        let mut block = [0u8; 64];
+        block[0] = tag;
+        self.state.keystream_xor(1, &mut block);
+
+        let mut out = Vec::with_capacity(message.len() + ABYTES);
+        out.push(block[0]);
+        let mut ciphertext = message.to_vec();
+        self.state.keystream_xor(2, &mut ciphertext);
+
+        let mac = self.state.mac(ad, &block, &ciphertext);
+        out.extend_from_slice(&ciphertext);
+        out.extend_from_slice(&mac);
+        self.state.advance(&mac, tag);
+        out
+    }
+
+    pub fn rekey(&mut self) {
+        self.state.rekey();
+    }
+}
+
+// Decrypting side: init_pull / pull
+pub struct Pull {
+    state: State,
+}
+
+impl Pull {
+    pub fn new(key: &[u8], header: &[u8]) -> Result<Self, String> {
+        Ok(Pull { state: State::from_header(key, header)? })
+    }
+
+    // Returns (message, tag)
+    pub fn pull(&mut self, input: &[u8], ad: &[u8]) -> Result<(Vec<u8>, u8), String> {
+        if input.len() < ABYTES {
+            return Err("ciphertext too short".to_string());
+        }
+        let mlen = input.len() - ABYTES;
+
+        let mut block = [0u8; 64];
+        block[0] = input[0];
+        self.state.keystream_xor(1, &mut block);
+        let tag = block[0];
+        block[0] = input[0];
+
+        let ciphertext = &input[1..1 + mlen];
+        let mac = self.state.mac(ad, &block, ciphertext);
+        let stored_mac = &input[1 + mlen..];
+        let diff = mac.iter().zip(stored_mac).fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return Err("authentication failed".to_string());
+        }
+
+        let mut message = ciphertext.to_vec();
+        self.state.keystream_xor(2, &mut message);
+        self.state.advance(&mac, tag);
+        Ok((message, tag))
+    }
+
+    pub fn rekey(&mut self) {
+        self.state.rekey();
+    }
+}
+
+/// Tag value for "message", "push", "rekey" or "final"
+pub fn tag(name: &str) -> Result<u8, String> {
+    match name {
+        "message" => Ok(TAG_MESSAGE),
+        "push" => Ok(TAG_PUSH),
+        "rekey" => Ok(TAG_REKEY),
+        "final" => Ok(TAG_FINAL),
+        _ => Err(format!("unknown secretstream tag: {}", name)),
+    }
+}
+
+pub fn keygen() -> Vec<u8> {
+    let mut key = vec![0u8; KEY_BYTES];
+    getrandom(&mut key).expect("random key generation failed");
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Vec<u8> {
+        (0u8..32).collect()
+    }
+
+    const HEADER: &str = "806380f0e7564be1ea1ffebdff8ac4c40a57a18f92782369";
+
+    // Stream produced by libsodium crypto_secretstream_xchacha20poly1305_push with key 00..1f
+    fn libsodium_chunks() -> Vec<(&'static [u8], &'static [u8], u8, &'static str)> {
+        vec![
+            (b"first chunk", b"", TAG_MESSAGE, "0bc361c97d90f01df6fa223d6156d4492a6191afd6ff2fdf24e83a14"),
+            (b"", b"ad", TAG_MESSAGE, "3841cb0d3c93b9f021c166ae7eeee6c930"),
+            (
+                b"xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+                b"",
+                TAG_REKEY,
+                "1d148baef9eb257a3bcfc6bc4bc8e1762af797da548df42ae1643716a63a31e3badfbe8106fa58863f60e565309e4989497a2ab324149879ca",
+            ),
+            (b"the end", b"", TAG_FINAL, "6e9975a97d28b245f25dfa9002445a40d9a6fd2e21160736"),
+        ]
+    }
+
+    #[test]
+    fn test_push_matches_libsodium() {
+        let mut push = Push::with_header(&key(), &hex::decode(HEADER).unwrap()).unwrap();
+        for (message, ad, tag, expected) in libsodium_chunks() {
+            assert_eq!(hex::encode(push.push(message, ad, tag)), expected);
+        }
+    }
+
+    #[test]
+    fn test_pull_libsodium_stream() {
+        let mut pull = Pull::new(&key(), &hex::decode(HEADER).unwrap()).unwrap();
+        for (message, ad, tag, chunk) in libsodium_chunks() {
+            let (m, t) = pull.pull(&hex::decode(chunk).unwrap(), ad).unwrap();
+            assert_eq!(m, message);
+            assert_eq!(t, tag);
+        }
+    }
+
+    #[test]
+    fn test_reordered_chunks_fail() {
+        let mut push = Push::new(&key()).unwrap();
+        let c1 = push.push(b"one", b"", TAG_MESSAGE);
+        let c2 = push.push(b"two", b"", TAG_FINAL);
+        let mut pull = Pull::new(&key(), &push.header()).unwrap();
+        assert_eq!(pull.pull(&c2, b"").unwrap_err(), "authentication failed");
+        let mut pull = Pull::new(&key(), &push.header()).unwrap();
+        assert_eq!(pull.pull(&c1, b"").unwrap().0, b"one");
+        assert_eq!(pull.pull(&c2, b"").unwrap(), (b"two".to_vec(), TAG_FINAL));
+    }
+
+    // Values of crypto_secretstream_xchacha20poly1305_TAG_*
+    #[test]
+    fn test_tag_names() {
+        let tags: Vec<u8> = ["message", "push", "rekey", "final"].iter().map(|name| tag(name).unwrap()).collect();
+        assert_eq!(tags, [0, 1, 2, 3]);
+        assert_eq!(tag("close").unwrap_err(), "unknown secretstream tag: close");
+    }
+}
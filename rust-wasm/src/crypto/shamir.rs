@@ -0,0 +1,497 @@
+// Shamir's Secret Sharing over GF(256) (the AES field, x^8 + x^4 + x^3 + x + 1).
+//
+// Two share formats:
+// - raw shares as hex or Base64, laid out like HashiCorp Vault's unseal keys: the y bytes
+//   followed by the x coordinate
+// - SLIP-39 mnemonics (single group, K of N): passphrase-encrypted master secret, digest
+//   share, RS1024 checksum and the 1024-word SLIP-39 wordlist. Combining also accepts
+//   multi-group share sets.
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use getrandom::getrandom;
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+
+use crate::hash::hmac;
+
+const SLIP39_WORDS: &str = include_str!("wordlists/slip39_english.txt");
+const RADIX_BITS: usize = 10;
+const ID_BITS: usize = 15;
+const CHECKSUM_WORDS: usize = 3;
+/// Words before the share value: id + ext + exponent, then the group and member fields
+const HEADER_WORDS: usize = 4;
+const DIGEST_BYTES: usize = 4;
+const DIGEST_INDEX: u8 = 254;
+const SECRET_INDEX: u8 = 255;
+const MAX_SHARES: usize = 16;
+/// PBKDF2 iterations per Feistel round are 2500 << e; e = 1 as in the reference implementation
+const ITERATION_EXPONENT: u8 = 1;
+const FEISTEL_ROUNDS: u8 = 4;
+
+// ----------------------------------------------------------------------------
+// GF(256)
+// ----------------------------------------------------------------------------
+
+/// (exp, log) tables for generator 3
+const fn gf_tables() -> ([u8; 255], [u8; 256]) {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = x as u8;
+        log[x as usize] = i as u8;
+        // multiply by 3 = x * 2 + x, reduced by the AES polynomial
+        x ^= x << 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11b;
+        }
+        i += 1;
+    }
+    (exp, log)
+}
+
+const GF: ([u8; 255], [u8; 256]) = gf_tables();
+
+/// Evaluate at `x` the polynomial through `points` (distinct x coordinates, equal lengths)
+fn interpolate(points: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, y)) = points.iter().find(|(px, _)| *px == x) {
+        return y.clone();
+    }
+    let (exp, log) = (&GF.0, &GF.1);
+    let log_of = |v: u8| log[v as usize] as u32;
+    let log_prod: u32 = points.iter().map(|(px, _)| log_of(px ^ x)).sum();
+    let mut result = vec![0u8; points[0].1.len()];
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        // log of basis_i(x) = prod_{j != i} (x - xj) / (xi - xj)
+        let denominator: u32 = points.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, (xj, _))| log_of(xi ^ xj)).sum();
+        let log_basis = (log_prod + 255 * 255 - log_of(xi ^ x) - denominator) % 255;
+        for (r, &y) in result.iter_mut().zip(yi) {
+            if y != 0 {
+                *r ^= exp[((log_of(y) + log_basis) % 255) as usize];
+            }
+        }
+    }
+    result
+}
+
+fn random_bytes(len: usize) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![0u8; len];
+    getrandom(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn gf_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    GF.0[((GF.1[a as usize] as usize) + (GF.1[b as usize] as usize)) % 255]
+}
+
+/// Random polynomial of degree threshold - 1 with the secret as constant term, evaluated at `xs`
+fn split_raw(secret: &[u8], threshold: usize, xs: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    // coefficients[k] is the x^k coefficient for every byte of the secret
+    let mut coefficients = vec![secret.to_vec()];
+    for _ in 1..threshold {
+        coefficients.push(random_bytes(secret.len())?);
+    }
+    let evaluate = |x: u8| {
+        // Horner's rule, highest coefficient first
+        coefficients.iter().rev().fold(vec![0u8; secret.len()], |acc, c| acc.iter().zip(c).map(|(&a, &c)| gf_mul(a, x) ^ c).collect())
+    };
+    Ok(xs.iter().map(|&x| (x, evaluate(x))).collect())
+}
+
+// ----------------------------------------------------------------------------
+// Raw (hex / Base64) shares
+// ----------------------------------------------------------------------------
+
+fn split_plain(secret: &[u8], threshold: usize, count: usize) -> Result<Vec<Vec<u8>>, String> {
+    if !(2..=255).contains(&count) || !(2..=count).contains(&threshold) {
+        return Err("need 2 <= threshold <= shares <= 255".to_string());
+    }
+    // Distinct random non-zero x coordinates
+    let mut xs: Vec<u8> = Vec::with_capacity(count);
+    while xs.len() < count {
+        let x = random_bytes(1)?[0];
+        if x != 0 && !xs.contains(&x) {
+            xs.push(x);
+        }
+    }
+    let shares = split_raw(secret, threshold, &xs)?;
+    Ok(shares.into_iter().map(|(x, y)| [y, vec![x]].concat()).collect())
+}
+
+fn combine_plain(shares: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let len = shares[0].len();
+    if len < 2 || shares.iter().any(|s| s.len() != len) {
+        return Err("shares must all have the same length".to_string());
+    }
+    let mut points: Vec<(u8, Vec<u8>)> = Vec::new();
+    for share in shares {
+        let (y, x) = share.split_at(len - 1);
+        match points.iter().find(|(px, _)| *px == x[0]) {
+            Some((_, py)) if py == y => continue,
+            Some(_) => return Err("two different shares have the same index".to_string()),
+            None if x[0] == 0 => return Err("invalid share index 0".to_string()),
+            None => points.push((x[0], y.to_vec())),
+        }
+    }
+    if points.len() < 2 {
+        return Err("at least 2 distinct shares are required".to_string());
+    }
+    Ok(interpolate(&points, 0))
+}
+
+// ----------------------------------------------------------------------------
+// SLIP-39
+// ----------------------------------------------------------------------------
+
+fn wordlist() -> Vec<&'static str> {
+    SLIP39_WORDS.lines().collect()
+}
+
+fn rs1024_polymod(values: impl IntoIterator<Item = u32>) -> u32 {
+    const GEN: [u32; 10] = [
+        0xE0E040, 0x1C1C080, 0x3838100, 0x7070200, 0xE0E0009, 0x1C0C2412, 0x38086C24, 0x3090FC48, 0x21B1F890, 0x3F3F120,
+    ];
+    let mut chk = 1u32;
+    for v in values {
+        let b = chk >> 20;
+        chk = ((chk & 0xFFFFF) << 10) ^ v;
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable { b"shamir_extendable" } else { b"shamir" }
+}
+
+fn rs1024_checksum(extendable: bool, data: &[u16]) -> [u16; CHECKSUM_WORDS] {
+    let values = customization(extendable).iter().map(|&c| c as u32).chain(data.iter().map(|&w| w as u32));
+    let polymod = rs1024_polymod(values.chain([0; CHECKSUM_WORDS])) ^ 1;
+    std::array::from_fn(|i| ((polymod >> (RADIX_BITS * (CHECKSUM_WORDS - 1 - i))) & 1023) as u16)
+}
+
+/// Feistel network over the master secret with PBKDF2-HMAC-SHA256 round functions
+fn feistel(secret: &[u8], passphrase: &str, exponent: u8, id: u16, extendable: bool, decrypt: bool) -> Vec<u8> {
+    let half = secret.len() / 2;
+    let (mut left, mut right) = (secret[..half].to_vec(), secret[half..].to_vec());
+    let salt_prefix: Vec<u8> = if extendable { Vec::new() } else { [b"shamir".as_slice(), &id.to_be_bytes()].concat() };
+    let iterations = 2500u32 << exponent;
+    for round in 0..FEISTEL_ROUNDS {
+        let round = if decrypt { FEISTEL_ROUNDS - 1 - round } else { round };
+        let password = [&[round], passphrase.as_bytes()].concat();
+        let mut f = vec![0u8; half];
+        pbkdf2_hmac::<Sha256>(&password, &[salt_prefix.as_slice(), &right].concat(), iterations, &mut f);
+        let next_right: Vec<u8> = left.iter().zip(&f).map(|(a, b)| a ^ b).collect();
+        left = std::mem::replace(&mut right, next_right);
+    }
+    [right, left].concat()
+}
+
+fn digest(random: &[u8], secret: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(hmac::mac("sha256", random, secret)?[..DIGEST_BYTES].to_vec())
+}
+
+/// SLIP-39 split of one level: threshold 1 copies the secret, otherwise the polynomial also
+/// passes through a digest share at index 254 and the secret at 255
+fn slip39_split_level(threshold: usize, count: usize, secret: &[u8]) -> Result<Vec<(u8, Vec<u8>)>, String> {
+    if threshold == 1 {
+        return Ok((0..count as u8).map(|i| (i, secret.to_vec())).collect());
+    }
+    let random = random_bytes(secret.len() - DIGEST_BYTES)?;
+    let mut points: Vec<(u8, Vec<u8>)> = (0..threshold as u8 - 2).map(|i| Ok((i, random_bytes(secret.len())?))).collect::<Result<_, String>>()?;
+    points.push((DIGEST_INDEX, [digest(&random, secret)?, random].concat()));
+    points.push((SECRET_INDEX, secret.to_vec()));
+    Ok((0..count as u8).map(|i| (i, interpolate(&points, i))).collect())
+}
+
+fn slip39_recover_level(threshold: usize, points: &[(u8, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    if threshold == 1 {
+        return Ok(points[0].1.clone());
+    }
+    let secret = interpolate(points, SECRET_INDEX);
+    let digest_share = interpolate(points, DIGEST_INDEX);
+    let (expected, random) = digest_share.split_at(DIGEST_BYTES);
+    if digest(random, &secret)? != expected {
+        return Err("invalid digest of the shared secret; the shares do not belong together".to_string());
+    }
+    Ok(secret)
+}
+
+struct Slip39Share {
+    id: u16,
+    extendable: bool,
+    exponent: u8,
+    group_index: u8,
+    group_threshold: usize,
+    group_count: usize,
+    member_index: u8,
+    member_threshold: usize,
+    value: Vec<u8>,
+}
+
+struct BitWriter {
+    words: Vec<u16>,
+    acc: u64,
+    bits: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u64, bits: usize) {
+        self.acc = (self.acc << bits) | value;
+        self.bits += bits;
+        while self.bits >= RADIX_BITS {
+            self.bits -= RADIX_BITS;
+            self.words.push(((self.acc >> self.bits) & 1023) as u16);
+        }
+    }
+}
+
+impl Slip39Share {
+    fn to_mnemonic(&self) -> String {
+        let mut w = BitWriter { words: Vec::new(), acc: 0, bits: 0 };
+        w.push(self.id as u64, ID_BITS);
+        w.push(self.extendable as u64, 1);
+        w.push(self.exponent as u64, 4);
+        w.push(self.group_index as u64, 4);
+        w.push(self.group_threshold as u64 - 1, 4);
+        w.push(self.group_count as u64 - 1, 4);
+        w.push(self.member_index as u64, 4);
+        w.push(self.member_threshold as u64 - 1, 4);
+        // The share value is left-padded with zero bits to a whole number of words
+        let padding = (RADIX_BITS - (self.value.len() * 8) % RADIX_BITS) % RADIX_BITS;
+        w.push(0, padding);
+        for &byte in &self.value {
+            w.push(byte as u64, 8);
+        }
+        let checksum = rs1024_checksum(self.extendable, &w.words);
+        let list = wordlist();
+        w.words.iter().chain(&checksum).map(|&i| list[i as usize]).collect::<Vec<_>>().join(" ")
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> Result<Slip39Share, String> {
+        let list = wordlist();
+        // Words are unique in their first four letters, so prefixes are accepted
+        let indices = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                list.iter()
+                    .position(|w| *w == word || (word.len() >= 4 && w.starts_with(&word)))
+                    .map(|i| i as u16)
+                    .ok_or(format!("unknown SLIP-39 word: {}", word))
+            })
+            .collect::<Result<Vec<u16>, String>>()?;
+        if indices.len() < HEADER_WORDS + CHECKSUM_WORDS + 13 {
+            return Err("SLIP-39 share is too short".to_string());
+        }
+        let extendable = (indices[1] >> 4) & 1 == 1;
+        let values = customization(extendable).iter().map(|&c| c as u32).chain(indices.iter().map(|&w| w as u32));
+        if rs1024_polymod(values) != 1 {
+            return Err("invalid SLIP-39 checksum".to_string());
+        }
+        let field = |bit_offset: usize, bits: usize| -> usize {
+            let header = indices[..HEADER_WORDS].iter().fold(0u64, |acc, &w| (acc << RADIX_BITS) | w as u64);
+            ((header >> (HEADER_WORDS * RADIX_BITS - bit_offset - bits)) & ((1 << bits) - 1)) as usize
+        };
+        let data = &indices[HEADER_WORDS..indices.len() - CHECKSUM_WORDS];
+        let padding = (data.len() * RADIX_BITS) % 16;
+        if padding > 8 {
+            return Err("invalid SLIP-39 share length".to_string());
+        }
+        let mut bits: Vec<bool> = data.iter().flat_map(|&w| (0..RADIX_BITS).rev().map(move |b| (w >> b) & 1 == 1)).collect();
+        if bits.drain(..padding).any(|b| b) {
+            return Err("invalid SLIP-39 share padding".to_string());
+        }
+        let value = bits.chunks(8).map(|byte| byte.iter().fold(0u8, |acc, &b| (acc << 1) | b as u8)).collect();
+        let share = Slip39Share {
+            id: field(0, ID_BITS) as u16,
+            extendable,
+            exponent: field(16, 4) as u8,
+            group_index: field(20, 4) as u8,
+            group_threshold: field(24, 4) + 1,
+            group_count: field(28, 4) + 1,
+            member_index: field(32, 4) as u8,
+            member_threshold: field(36, 4) + 1,
+            value,
+        };
+        if share.group_threshold > share.group_count {
+            return Err("SLIP-39 group threshold exceeds the group count".to_string());
+        }
+        Ok(share)
+    }
+}
+
+fn slip39_split(secret: &[u8], threshold: usize, count: usize, passphrase: &str) -> Result<Vec<String>, String> {
+    if secret.len() < 16 || !secret.len().is_multiple_of(2) {
+        return Err("SLIP-39 secrets must be an even number of bytes, at least 16".to_string());
+    }
+    if !(1..=MAX_SHARES).contains(&count) || !(1..=count).contains(&threshold) {
+        return Err(format!("need 1 <= threshold <= shares <= {} for SLIP-39", MAX_SHARES));
+    }
+    if threshold == 1 && count > 1 {
+        return Err("SLIP-39 does not allow several shares with threshold 1; use 1 of 1".to_string());
+    }
+    if passphrase.chars().any(|c| !(' '..='~').contains(&c)) {
+        return Err("SLIP-39 passphrases must be printable ASCII".to_string());
+    }
+    let id_bytes = random_bytes(2)?;
+    let id = u16::from_be_bytes([id_bytes[0], id_bytes[1]]) & 0x7FFF;
+    let encrypted = feistel(secret, passphrase, ITERATION_EXPONENT, id, false, false);
+    // Single group (1 of 1) holding K of N member shares
+    let members = slip39_split_level(threshold, count, &encrypted)?;
+    Ok(members
+        .into_iter()
+        .map(|(member_index, value)| {
+            Slip39Share {
+                id,
+                extendable: false,
+                exponent: ITERATION_EXPONENT,
+                group_index: 0,
+                group_threshold: 1,
+                group_count: 1,
+                member_index,
+                member_threshold: threshold,
+                value,
+            }
+            .to_mnemonic()
+        })
+        .collect())
+}
+
+fn slip39_combine(mnemonics: &[&str], passphrase: &str) -> Result<Vec<u8>, String> {
+    let shares = mnemonics.iter().map(|m| Slip39Share::from_mnemonic(m)).collect::<Result<Vec<_>, String>>()?;
+    let first = &shares[0];
+    let consistent = |s: &Slip39Share| {
+        (s.id, s.extendable, s.exponent, s.group_threshold, s.group_count, s.value.len())
+            == (first.id, first.extendable, first.exponent, first.group_threshold, first.group_count, first.value.len())
+    };
+    if !shares.iter().all(consistent) {
+        return Err("shares are from different SLIP-39 share sets".to_string());
+    }
+    let mut groups: Vec<(u8, Vec<u8>)> = Vec::new();
+    let mut incomplete = Vec::new();
+    for group_index in 0..first.group_count as u8 {
+        let members: Vec<&Slip39Share> = shares.iter().filter(|s| s.group_index == group_index).collect();
+        let Some(member) = members.first() else { continue };
+        if members.iter().any(|s| s.member_threshold != member.member_threshold) {
+            return Err(format!("group {} has inconsistent member thresholds", group_index + 1));
+        }
+        let mut points: Vec<(u8, Vec<u8>)> = Vec::new();
+        for s in &members {
+            if !points.iter().any(|(x, _)| *x == s.member_index) {
+                points.push((s.member_index, s.value.clone()));
+            }
+        }
+        if points.len() < member.member_threshold {
+            incomplete.push(format!("group {} has {} of {} shares", group_index + 1, points.len(), member.member_threshold));
+            continue;
+        }
+        points.truncate(member.member_threshold);
+        groups.push((group_index, slip39_recover_level(member.member_threshold, &points)?));
+    }
+    if groups.len() < first.group_threshold {
+        let detail = if incomplete.is_empty() { String::new() } else { format!(" ({})", incomplete.join(", ")) };
+        return Err(format!("need {} complete groups, got {}{}", first.group_threshold, groups.len(), detail));
+    }
+    groups.truncate(first.group_threshold);
+    let encrypted = slip39_recover_level(first.group_threshold, &groups)?;
+    Ok(feistel(&encrypted, passphrase, first.exponent, first.id, first.extendable, true))
+}
+
+// ----------------------------------------------------------------------------
+// Public API
+// ----------------------------------------------------------------------------
+
+/// Split `secret` into `count` shares, any `threshold` of which recover it.
+/// format: hex | base64 (raw GF(256) shares) | slip39 (mnemonic words; the secret must be an
+/// even number of bytes, at least 16, and is encrypted with `passphrase`)
+pub fn split(secret: &[u8], threshold: usize, count: usize, format: &str, passphrase: &str) -> Result<Vec<String>, String> {
+    if secret.is_empty() {
+        return Err("secret is empty".to_string());
+    }
+    if format != "slip39" && !passphrase.is_empty() {
+        return Err("a passphrase is only used by SLIP-39 shares".to_string());
+    }
+    match format {
+        "hex" => Ok(split_plain(secret, threshold, count)?.iter().map(hex::encode).collect()),
+        "base64" => Ok(split_plain(secret, threshold, count)?.iter().map(|s| STANDARD.encode(s)).collect()),
+        "slip39" => slip39_split(secret, threshold, count, passphrase),
+        _ => Err(format!("unknown share format: {}", format)),
+    }
+}
+
+/// Recover the secret from shares of one format (detected: SLIP-39 mnemonics, hex or Base64).
+/// Raw shares carry no threshold or checksum, so too few shares give a wrong secret.
+pub fn combine(shares: &[&str], passphrase: &str) -> Result<Vec<u8>, String> {
+    let shares: Vec<&str> = shares.iter().map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+    if shares.is_empty() {
+        return Err("no shares given".to_string());
+    }
+    if shares[0].contains(char::is_whitespace) {
+        return slip39_combine(&shares, passphrase);
+    }
+    if !passphrase.is_empty() {
+        return Err("a passphrase is only used by SLIP-39 shares".to_string());
+    }
+    let is_hex = shares.iter().all(|s| s.len() % 2 == 0 && s.bytes().all(|b| b.is_ascii_hexdigit()));
+    let raw = shares
+        .iter()
+        .map(|s| if is_hex { hex::decode(s).map_err(|e| e.to_string()) } else { STANDARD.decode(s).map_err(|_| "invalid share encoding".to_string()) })
+        .collect::<Result<Vec<_>, String>>()?;
+    combine_plain(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SLIP-39 test vectors (passphrase "TREZOR")
+    #[test]
+    fn test_slip39_vectors() {
+        let single = "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard";
+        assert_eq!(hex::encode(combine(&[single], "TREZOR").unwrap()), "bb54aac4b89dc868ba37d9cc21b2cece");
+        let shares = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(hex::encode(combine(&shares, "TREZOR").unwrap()), "b43ceb7e57a0ea8766221624d01b0864");
+        assert!(combine(&shares[..1], "TREZOR").unwrap_err().contains("1 of 2"));
+        let typo = single.replace("keyboard", "kidney");
+        assert_eq!(combine(&[typo.as_str()], "TREZOR").unwrap_err(), "invalid SLIP-39 checksum");
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let secret = b"correct horse battery staple!!";
+        for format in ["hex", "base64"] {
+            let shares = split(secret, 3, 5, format, "").unwrap();
+            let picked: Vec<&str> = [4, 0, 2].iter().map(|&i| shares[i].as_str()).collect();
+            assert_eq!(combine(&picked, "").unwrap(), secret);
+            assert_ne!(combine(&picked[..2], "").unwrap(), secret);
+        }
+
+        // Large splits: every one of the threshold shares is needed
+        let shares = split(secret, 200, 200, "hex", "").unwrap();
+        let picked: Vec<&str> = shares.iter().map(String::as_str).collect();
+        assert_eq!(combine(&picked, "").unwrap(), secret);
+        assert_ne!(combine(&picked[1..], "").unwrap(), secret);
+        let shares = split(secret, 255, 255, "hex", "").unwrap();
+        assert!(shares.iter().all(|s| !s.starts_with(&hex::encode(secret))));
+
+        let shares = split(secret, 2, 3, "slip39", "pass").unwrap();
+        assert_eq!(shares[0].split(' ').count(), 31);
+        let picked = [shares[2].as_str(), shares[1].as_str()];
+        assert_eq!(combine(&picked, "pass").unwrap(), secret);
+        assert_ne!(combine(&picked, "").unwrap(), secret);
+        assert!(split(b"short", 2, 3, "slip39", "").is_err());
+        assert!(split(secret, 1, 3, "slip39", "").is_err());
+    }
+}
@@ -0,0 +1,418 @@
+// Streaming (segmented) AEAD - the STREAM construction of Hoang, Reyhanitabar, Rogaway and Vizár
+//
+// The plaintext is cut into fixed-size segments and each segment is sealed with
+//   nonce = [NONCE_PREFIX][SEGMENT_COUNTER32 BE][LAST_FLAG1]
+// so dropping, reordering or truncating segments makes decryption fail.
+//
+// Output: [HEADER][SEGMENT0+TAG16][SEGMENT1+TAG16]...[LAST_SEGMENT+TAG16]
+// Header: ["HLS1"][ALGORITHM1][SEGMENT_SIZE32 BE][NONCE_PREFIX]; it is also the
+// associated data of every segment. The last segment may be empty.
+//
+// Encryptor and Decryptor accept input in arbitrary chunks and only ever buffer
+// one segment, so memory use does not depend on the total stream length.
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::XChaCha20Poly1305;
+use getrandom::getrandom;
+
+pub const KEY_BYTES: usize = 32;
+pub const TAG_BYTES: usize = 16;
+pub const DEFAULT_SEGMENT_SIZE: usize = 64 * 1024;
+pub const MAX_SEGMENT_SIZE: usize = 16 * 1024 * 1024;
+
+const MAGIC: &[u8; 4] = b"HLS1";
+const FIXED_HEADER_BYTES: usize = 9;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Algorithm {
+    Aes256Gcm,
+    XChaCha20Poly1305,
+}
+
+impl Algorithm {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "aes256gcm" => Ok(Algorithm::Aes256Gcm),
+            "xchacha20poly1305" => Ok(Algorithm::XChaCha20Poly1305),
+            _ => Err(format!("unknown algorithm: {}", name)),
+        }
+    }
+
+    fn id(self) -> u8 {
+        match self {
+            Algorithm::Aes256Gcm => 1,
+            Algorithm::XChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, String> {
+        match id {
+            1 => Ok(Algorithm::Aes256Gcm),
+            2 => Ok(Algorithm::XChaCha20Poly1305),
+            _ => Err("unsupported stream algorithm".to_string()),
+        }
+    }
+
+    // Nonce length minus the 4-byte counter and the last-segment flag
+    fn prefix_len(self) -> usize {
+        match self {
+            Algorithm::Aes256Gcm => 7,
+            Algorithm::XChaCha20Poly1305 => 19,
+        }
+    }
+}
+
+enum Cipher {
+    Aes(Box<Aes256Gcm>),
+    XChaCha(XChaCha20Poly1305),
+}
+
+// Per-stream state shared by both directions
+struct Segments {
+    cipher: Cipher,
+    header: Vec<u8>,
+    counter: u32,
+    exhausted: bool,
+}
+
+impl Segments {
+    fn new(algorithm: Algorithm, key: &[u8], header: Vec<u8>) -> Result<Self, String> {
+        if key.len() != KEY_BYTES {
+            return Err("key must be 32 bytes".to_string());
+        }
+        let cipher = match algorithm {
+            Algorithm::Aes256Gcm => Cipher::Aes(Box::new(Aes256Gcm::new(key.into()))),
+            Algorithm::XChaCha20Poly1305 => Cipher::XChaCha(XChaCha20Poly1305::new(key.into())),
+        };
+        Ok(Segments { cipher, header, counter: 0, exhausted: false })
+    }
+
+    fn nonce(&mut self, last: bool) -> Result<Vec<u8>, String> {
+        if self.exhausted {
+            return Err("segment counter overflow".to_string());
+        }
+        let mut nonce = self.header[FIXED_HEADER_BYTES..].to_vec();
+        nonce.extend_from_slice(&self.counter.to_be_bytes());
+        nonce.push(last as u8);
+        match self.counter.checked_add(1) {
+            Some(next) => self.counter = next,
+            None => self.exhausted = true,
+        }
+        Ok(nonce)
+    }
+
+    fn seal(&mut self, segment: &[u8], last: bool) -> Result<Vec<u8>, String> {
+        let nonce = self.nonce(last)?;
+        let payload = Payload { msg: segment, aad: &self.header };
+        match &self.cipher {
+            Cipher::Aes(c) => c.encrypt(nonce.as_slice().into(), payload),
+            Cipher::XChaCha(c) => c.encrypt(nonce.as_slice().into(), payload),
+        }
+        .map_err(|_| "encryption failed".to_string())
+    }
+
+    fn open(&mut self, segment: &[u8], last: bool) -> Result<Vec<u8>, String> {
+        let nonce = self.nonce(last)?;
+        let payload = Payload { msg: segment, aad: &self.header };
+        match &self.cipher {
+            Cipher::Aes(c) => c.decrypt(nonce.as_slice().into(), payload),
+            Cipher::XChaCha(c) => c.decrypt(nonce.as_slice().into(), payload),
+        }
+        .map_err(|_| "authentication failed".to_string())
+    }
+}
+
+fn check_segment_size(segment_size: usize) -> Result<(), String> {
+    if segment_size == 0 || segment_size > MAX_SEGMENT_SIZE {
+        return Err(format!("segment size must be between 1 and {} bytes", MAX_SEGMENT_SIZE));
+    }
+    Ok(())
+}
+
+pub struct Encryptor {
+    segments: Segments,
+    segment_size: usize,
+    buffer: Vec<u8>,
+    header_sent: bool,
+    finished: bool,
+}
+
+impl Encryptor {
+    pub fn new(algorithm: &str, key: &[u8], segment_size: usize) -> Result<Self, String> {
+        let algorithm = Algorithm::parse(algorithm)?;
+        let mut prefix = vec![0u8; algorithm.prefix_len()];
+        getrandom(&mut prefix).expect("random nonce generation failed");
+        Self::with_nonce_prefix(algorithm, key, segment_size, &prefix)
+    }
+
+    pub fn with_nonce_prefix(algorithm: Algorithm, key: &[u8], segment_size: usize, prefix: &[u8]) -> Result<Self, String> {
+        check_segment_size(segment_size)?;
+        if prefix.len() != algorithm.prefix_len() {
+            return Err(format!("nonce prefix must be {} bytes", algorithm.prefix_len()));
+        }
+        let mut header = Vec::with_capacity(FIXED_HEADER_BYTES + prefix.len());
+        header.extend_from_slice(MAGIC);
+        header.push(algorithm.id());
+        header.extend_from_slice(&(segment_size as u32).to_be_bytes());
+        header.extend_from_slice(prefix);
+        Ok(Encryptor {
+            segments: Segments::new(algorithm, key, header)?,
+            segment_size,
+            buffer: Vec::with_capacity(segment_size),
+            header_sent: false,
+            finished: false,
+        })
+    }
+
+    fn take_header(&mut self, out: &mut Vec<u8>) {
+        if !self.header_sent {
+            out.extend_from_slice(&self.segments.header);
+            self.header_sent = true;
+        }
+    }
+
+    // Feed plaintext; returns the ciphertext that is ready (possibly empty).
+    // A full segment is held back until more data arrives, because it might be the last one.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        if self.finished {
+            return Err("stream already finished".to_string());
+        }
+        let mut out = Vec::new();
+        self.take_header(&mut out);
+
+        let mut input = chunk;
+        while !input.is_empty() {
+            if self.buffer.len() == self.segment_size {
+                let sealed = self.segments.seal(&self.buffer, false)?;
+                out.extend_from_slice(&sealed);
+                self.buffer.clear();
+            }
+            let take = (self.segment_size - self.buffer.len()).min(input.len());
+            self.buffer.extend_from_slice(&input[..take]);
+            input = &input[take..];
+        }
+        Ok(out)
+    }
+
+    // Seal the final segment
+    pub fn finish(&mut self) -> Result<Vec<u8>, String> {
+        if self.finished {
+            return Err("stream already finished".to_string());
+        }
+        let mut out = Vec::new();
+        self.take_header(&mut out);
+        let sealed = self.segments.seal(&self.buffer, true)?;
+        out.extend_from_slice(&sealed);
+        self.buffer.clear();
+        self.finished = true;
+        Ok(out)
+    }
+}
+
+pub struct Decryptor {
+    key: Vec<u8>,
+    segments: Option<Segments>,
+    segment_size: usize,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl Decryptor {
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        if key.len() != KEY_BYTES {
+            return Err("key must be 32 bytes".to_string());
+        }
+        Ok(Decryptor { key: key.to_vec(), segments: None, segment_size: 0, buffer: Vec::new(), finished: false })
+    }
+
+    // Parse the header once enough bytes have been buffered
+    fn read_header(&mut self) -> Result<bool, String> {
+        if self.buffer.len() < FIXED_HEADER_BYTES {
+            return Ok(false);
+        }
+        if &self.buffer[..4] != MAGIC {
+            return Err("not an encrypted stream".to_string());
+        }
+        let algorithm = Algorithm::from_id(self.buffer[4])?;
+        let header_len = FIXED_HEADER_BYTES + algorithm.prefix_len();
+        if self.buffer.len() < header_len {
+            return Ok(false);
+        }
+        let segment_size = u32::from_be_bytes([self.buffer[5], self.buffer[6], self.buffer[7], self.buffer[8]]) as usize;
+        check_segment_size(segment_size)?;
+
+        let header: Vec<u8> = self.buffer.drain(..header_len).collect();
+        self.segments = Some(Segments::new(algorithm, &self.key, header)?);
+        self.segment_size = segment_size;
+        Ok(true)
+    }
+
+    // Feed ciphertext; returns the plaintext that has been authenticated so far (possibly empty)
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, String> {
+        if self.finished {
+            return Err("stream already finished".to_string());
+        }
+        let mut out = Vec::new();
+        let mut input = chunk;
+        if self.segments.is_none() {
+            // Only buffer as much as the longest possible header before parsing it
+            let take = (FIXED_HEADER_BYTES + 19 - self.buffer.len()).min(input.len());
+            self.buffer.extend_from_slice(&input[..take]);
+            input = &input[take..];
+            if !self.read_header()? {
+                return Ok(out);
+            }
+        }
+
+        let sealed_size = self.segment_size + TAG_BYTES;
+        loop {
+            if self.buffer.len() == sealed_size && !input.is_empty() {
+                let segments = self.segments.as_mut().expect("header parsed");
+                out.extend_from_slice(&segments.open(&self.buffer, false)?);
+                self.buffer.clear();
+            }
+            if input.is_empty() {
+                break;
+            }
+            let take = (sealed_size - self.buffer.len()).min(input.len());
+            self.buffer.extend_from_slice(&input[..take]);
+            input = &input[take..];
+        }
+        Ok(out)
+    }
+
+    // Verify and decrypt the final segment; fails if the stream was truncated
+    pub fn finish(&mut self) -> Result<Vec<u8>, String> {
+        if self.finished {
+            return Err("stream already finished".to_string());
+        }
+        let segments = match self.segments.as_mut() {
+            Some(segments) => segments,
+            None => return Err("stream truncated".to_string()),
+        };
+        if self.buffer.len() < TAG_BYTES {
+            return Err("stream truncated".to_string());
+        }
+        let plaintext = segments.open(&self.buffer, true).map_err(|_| "stream truncated or corrupted".to_string())?;
+        self.buffer.clear();
+        self.finished = true;
+        Ok(plaintext)
+    }
+}
+
+// One-shot helpers over the streaming objects
+pub fn encrypt(algorithm: &str, key: &[u8], segment_size: usize, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encryptor = Encryptor::new(algorithm, key, segment_size)?;
+    let mut out = encryptor.push(plaintext)?;
+    out.extend_from_slice(&encryptor.finish()?);
+    Ok(out)
+}
+
+pub fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decryptor = Decryptor::new(key)?;
+    let mut out = decryptor.push(ciphertext)?;
+    out.extend_from_slice(&decryptor.finish()?);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Vec<u8> {
+        (0u8..32).collect()
+    }
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_roundtrip_lengths() {
+        for algorithm in ["aes256gcm", "xchacha20poly1305"] {
+            for len in [0, 1, 15, 16, 17, 32, 33, 100] {
+                let ciphertext = encrypt(algorithm, &key(), 16, &data(len)).unwrap();
+                assert_eq!(decrypt(&key(), &ciphertext).unwrap(), data(len), "{} {}", algorithm, len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_segment_layout() {
+        // 40 bytes in 16-byte segments: 16 + 16 + 8 (last)
+        let prefix = [9u8; 7];
+        let mut enc = Encryptor::with_nonce_prefix(Algorithm::Aes256Gcm, &key(), 16, &prefix).unwrap();
+        let mut ct = enc.push(&data(40)).unwrap();
+        ct.extend_from_slice(&enc.finish().unwrap());
+        assert_eq!(ct.len(), 16 + 3 * TAG_BYTES + 40);
+        assert_eq!(&ct[..9], b"HLS1\x01\x00\x00\x00\x10");
+
+        // First segment is plain AES-256-GCM with nonce prefix || 0u32 || 0 and the header as AAD
+        let mut nonce = prefix.to_vec();
+        nonce.extend_from_slice(&[0, 0, 0, 0, 0]);
+        let first = Aes256Gcm::new(key().as_slice().into())
+            .decrypt(nonce.as_slice().into(), Payload { msg: &ct[16..48], aad: &ct[..16] })
+            .unwrap();
+        assert_eq!(first, data(16));
+    }
+
+    #[test]
+    fn test_arbitrary_chunking() {
+        let plaintext = data(1000);
+        let mut enc = Encryptor::new("xchacha20poly1305", &key(), 64).unwrap();
+        let mut ct = Vec::new();
+        for chunk in plaintext.chunks(37) {
+            ct.extend_from_slice(&enc.push(chunk).unwrap());
+        }
+        ct.extend_from_slice(&enc.finish().unwrap());
+
+        let mut dec = Decryptor::new(&key()).unwrap();
+        let mut pt = Vec::new();
+        for chunk in ct.chunks(5) {
+            pt.extend_from_slice(&dec.push(chunk).unwrap());
+        }
+        pt.extend_from_slice(&dec.finish().unwrap());
+        assert_eq!(pt, plaintext);
+    }
+
+    #[test]
+    fn test_truncation_detected() {
+        let ct = encrypt("aes256gcm", &key(), 16, &data(64)).unwrap();
+        let header = 16;
+        let sealed = 16 + TAG_BYTES;
+        // Cut at a segment boundary: the remaining last segment was sealed as non-final
+        let truncated = &ct[..header + 2 * sealed];
+        assert!(decrypt(&key(), truncated).is_err());
+        assert_eq!(decrypt(&key(), &ct[..header]).unwrap_err(), "stream truncated");
+        assert!(decrypt(&key(), &ct[..ct.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_reordering_and_tampering_detected() {
+        let ct = encrypt("xchacha20poly1305", &key(), 16, &data(48)).unwrap();
+        let header = 28;
+        let sealed = 16 + TAG_BYTES;
+        let mut swapped = ct.clone();
+        swapped[header..header + sealed].copy_from_slice(&ct[header + sealed..header + 2 * sealed]);
+        swapped[header + sealed..header + 2 * sealed].copy_from_slice(&ct[header..header + sealed]);
+        assert_eq!(decrypt(&key(), &swapped).unwrap_err(), "authentication failed");
+
+        // The header is authenticated as well
+        let mut changed_size = ct.clone();
+        changed_size[8] = 17;
+        assert!(decrypt(&key(), &changed_size).is_err());
+        assert!(decrypt(&[1u8; 32], &ct).is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(Encryptor::new("aes128gcm", &key(), 16).is_err());
+        assert!(Encryptor::new("aes256gcm", &key()[..16], 16).is_err());
+        assert!(Encryptor::new("aes256gcm", &key(), 0).is_err());
+        assert_eq!(decrypt(&key(), b"not a stream at all").unwrap_err(), "not an encrypted stream");
+
+        let mut enc = Encryptor::new("aes256gcm", &key(), 16).unwrap();
+        enc.finish().unwrap();
+        assert!(enc.push(b"late").is_err());
+    }
+}
@@ -0,0 +1,471 @@
+// Generic ASN.1 BER/DER viewer: turns bytes into a JSON tree of tag, class, length, offset
+// and decoded value. Tolerates indefinite lengths and truncated input; problems are reported
+// per node instead of failing the whole decode.
+use std::borrow::Cow;
+
+use num_bigint::BigInt;
+use serde_json::{Map, Value, json};
+use x509_parser::objects::oid_registry;
+use x509_parser::oid_registry::Oid;
+
+use super::pem;
+
+const MAX_DEPTH: usize = 64;
+
+/// Names for common OIDs that are missing from the x509 registry (Kerberos, SPNEGO, SNMP MIB-2)
+const EXTRA_OID_NAMES: &[(&str, &str)] = &[
+    ("1.2.840.113554.1.2.2", "kerberos5"),
+    ("1.2.840.48018.1.2.2", "kerberos5-ms"),
+    ("1.3.6.1.5.2.5", "kerberos5-user-to-user"),
+    ("1.3.6.1.5.5.2", "spnego"),
+    ("1.3.6.1.4.1.311.2.2.10", "ntlmssp"),
+    ("1.3.6.1.2.1.1.1.0", "sysDescr.0"),
+    ("1.3.6.1.2.1.1.2.0", "sysObjectID.0"),
+    ("1.3.6.1.2.1.1.3.0", "sysUpTime.0"),
+    ("1.3.6.1.2.1.1.4.0", "sysContact.0"),
+    ("1.3.6.1.2.1.1.5.0", "sysName.0"),
+    ("1.3.6.1.2.1.1.6.0", "sysLocation.0"),
+];
+
+fn universal_name(tag: u64) -> &'static str {
+    match tag {
+        0 => "END-OF-CONTENTS",
+        1 => "BOOLEAN",
+        2 => "INTEGER",
+        3 => "BIT STRING",
+        4 => "OCTET STRING",
+        5 => "NULL",
+        6 => "OBJECT IDENTIFIER",
+        7 => "ObjectDescriptor",
+        8 => "EXTERNAL",
+        9 => "REAL",
+        10 => "ENUMERATED",
+        11 => "EMBEDDED PDV",
+        12 => "UTF8String",
+        13 => "RELATIVE-OID",
+        16 => "SEQUENCE",
+        17 => "SET",
+        18 => "NumericString",
+        19 => "PrintableString",
+        20 => "T61String",
+        21 => "VideotexString",
+        22 => "IA5String",
+        23 => "UTCTime",
+        24 => "GeneralizedTime",
+        25 => "GraphicString",
+        26 => "VisibleString",
+        27 => "GeneralString",
+        28 => "UniversalString",
+        30 => "BMPString",
+        _ => "UNKNOWN",
+    }
+}
+
+struct Header {
+    class: u8,
+    constructed: bool,
+    tag: u64,
+    /// None for the indefinite form
+    length: Option<usize>,
+    header_len: usize,
+}
+
+fn read_header(data: &[u8]) -> Result<Header, String> {
+    let first = *data.first().ok_or("truncated: missing identifier")?;
+    let mut pos = 1;
+    let mut tag = u64::from(first & 0x1f);
+    if tag == 0x1f {
+        tag = 0;
+        loop {
+            let b = *data.get(pos).ok_or("truncated: incomplete high tag number")?;
+            pos += 1;
+            if tag > u64::MAX >> 7 {
+                return Err("tag number too large".to_string());
+            }
+            tag = (tag << 7) | u64::from(b & 0x7f);
+            if b & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+    let len_byte = *data.get(pos).ok_or("truncated: missing length")?;
+    pos += 1;
+    let length = match len_byte {
+        0x80 => None,
+        0x00..=0x7f => Some(usize::from(len_byte)),
+        0xff => return Err("reserved length octet 0xff".to_string()),
+        _ => {
+            let n = usize::from(len_byte & 0x7f);
+            if n > 8 {
+                return Err(format!("length of {} octets is too long", n));
+            }
+            let bytes = data.get(pos..pos + n).ok_or("truncated: incomplete length")?;
+            pos += n;
+            let len = bytes.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+            Some(usize::try_from(len).map_err(|_| "length too large".to_string())?)
+        }
+    };
+    Ok(Header { class: first >> 6, constructed: first & 0x20 != 0, tag, length, header_len: pos })
+}
+
+/// Parse consecutive elements from `data`, which starts at absolute `offset`. With `until_eoc`
+/// parsing stops after an end-of-contents marker. Returns the nodes and the bytes consumed.
+fn parse_elements(data: &[u8], offset: usize, depth: usize, until_eoc: bool) -> (Vec<Value>, usize, bool) {
+    let mut nodes = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        if until_eoc && data[pos..].starts_with(&[0, 0]) {
+            return (nodes, pos + 2, true);
+        }
+        let (node, used) = parse_element(&data[pos..], offset + pos, depth);
+        nodes.push(node);
+        pos += used;
+    }
+    (nodes, pos, false)
+}
+
+fn parse_element(data: &[u8], offset: usize, depth: usize) -> (Value, usize) {
+    let header = match read_header(data) {
+        Ok(h) => h,
+        Err(e) => {
+            let node = json!({ "offset": offset, "error": e, "hex": hex::encode(data) });
+            return (node, data.len());
+        }
+    };
+    let class = ["universal", "application", "context", "private"][usize::from(header.class)];
+    let type_name = match header.class {
+        0 => universal_name(header.tag).to_string(),
+        1 => format!("[APPLICATION {}]", header.tag),
+        2 => format!("[{}]", header.tag),
+        _ => format!("[PRIVATE {}]", header.tag),
+    };
+    let mut node = Map::new();
+    node.insert("offset".into(), json!(offset));
+    node.insert("header_length".into(), json!(header.header_len));
+    node.insert("length".into(), json!(header.length));
+    node.insert("class".into(), json!(class));
+    node.insert("tag".into(), json!(header.tag));
+    node.insert("constructed".into(), json!(header.constructed));
+    node.insert("type".into(), json!(type_name));
+
+    let rest = &data[header.header_len..];
+    let used = match header.length {
+        None if !header.constructed => {
+            node.insert("error".into(), json!("indefinite length on a primitive encoding"));
+            data.len()
+        }
+        None => {
+            if depth >= MAX_DEPTH {
+                node.insert("error".into(), json!("nesting too deep"));
+                return (Value::Object(node), data.len());
+            }
+            let (children, consumed, terminated) = parse_elements(rest, offset + header.header_len, depth + 1, true);
+            if !terminated {
+                node.insert("error".into(), json!("truncated: missing end-of-contents"));
+            }
+            node.insert("children".into(), Value::Array(children));
+            header.header_len + consumed
+        }
+        Some(len) => {
+            let content = if len > rest.len() {
+                node.insert(
+                    "error".into(),
+                    json!(format!("truncated: declared {} bytes, {} available", len, rest.len())),
+                );
+                rest
+            } else {
+                &rest[..len]
+            };
+            let content_offset = offset + header.header_len;
+            if header.constructed {
+                if depth >= MAX_DEPTH {
+                    node.insert("error".into(), json!("nesting too deep"));
+                } else {
+                    let (children, _, _) = parse_elements(content, content_offset, depth + 1, false);
+                    node.insert("children".into(), Value::Array(children));
+                }
+            } else {
+                decode_primitive(&mut node, &header, content, content_offset, depth);
+            }
+            header.header_len + content.len()
+        }
+    };
+    (Value::Object(node), used)
+}
+
+// ----------------------------------------------------------------------------
+// Primitive values
+// ----------------------------------------------------------------------------
+
+fn decode_primitive(node: &mut Map<String, Value>, header: &Header, content: &[u8], offset: usize, depth: usize) {
+    node.insert("hex".into(), json!(hex::encode(content)));
+    if header.class != 0 {
+        if !content.is_empty() && content.iter().all(|b| (0x20..0x7f).contains(b)) {
+            node.insert("text".into(), json!(String::from_utf8_lossy(content)));
+        }
+        return;
+    }
+    match header.tag {
+        1 => {
+            node.insert("value".into(), json!(content.first().is_some_and(|b| *b != 0)));
+        }
+        2 | 10 => {
+            node.insert("value".into(), json!(BigInt::from_signed_bytes_be(content).to_string()));
+        }
+        3 => {
+            if let Some((unused, bits)) = content.split_first() {
+                node.insert("unused_bits".into(), json!(unused));
+                if *unused == 0 {
+                    encapsulated(node, bits, offset + 1, depth);
+                }
+            }
+        }
+        4 => encapsulated(node, content, offset, depth),
+        5 => {
+            node.insert("value".into(), Value::Null);
+        }
+        6 | 13 => match oid_arcs(content, header.tag == 6) {
+            Some(dotted) => {
+                if let Some(name) = oid_name(content, &dotted, header.tag == 6) {
+                    node.insert("name".into(), json!(name));
+                }
+                node.insert("value".into(), json!(dotted));
+            }
+            None => {
+                node.insert("error".into(), json!("malformed object identifier"));
+            }
+        },
+        23 | 24 => {
+            let text = String::from_utf8_lossy(content).into_owned();
+            if let Some(iso) = normalize_time(&text, header.tag == 23) {
+                node.insert("time".into(), json!(iso));
+            }
+            node.insert("value".into(), json!(text));
+        }
+        7 | 12 | 18 | 19 | 20 | 21 | 22 | 25 | 26 | 27 | 28 | 30 => {
+            let (value, encoding) = decode_string(header.tag, content);
+            node.insert("value".into(), json!(value));
+            node.insert("encoding".into(), json!(encoding));
+        }
+        _ => {}
+    }
+}
+
+/// OCTET / BIT STRING contents that are themselves a complete DER structure (as in SPKI
+/// keys or X.509 extensions) are shown as children
+fn encapsulated(node: &mut Map<String, Value>, content: &[u8], offset: usize, depth: usize) {
+    let constructed_start = content.first().is_some_and(|b| b & 0x20 != 0);
+    if !constructed_start || depth >= MAX_DEPTH {
+        return;
+    }
+    let (children, _, _) = parse_elements(content, offset, depth + 1, false);
+    if children.iter().all(|c| !has_error(c)) {
+        node.insert("encapsulates".into(), Value::Array(children));
+    }
+}
+
+fn has_error(node: &Value) -> bool {
+    node.get("error").is_some()
+        || ["children", "encapsulates"]
+            .iter()
+            .any(|k| node.get(*k).and_then(Value::as_array).is_some_and(|c| c.iter().any(has_error)))
+}
+
+/// Dotted form of an OBJECT IDENTIFIER (or RELATIVE-OID) body; None if malformed
+fn oid_arcs(content: &[u8], absolute: bool) -> Option<String> {
+    if content.is_empty() || content.last().is_some_and(|b| b & 0x80 != 0) {
+        return None;
+    }
+    let mut arcs: Vec<String> = Vec::new();
+    let mut value: u128 = 0;
+    for (i, b) in content.iter().enumerate() {
+        if value == 0 && *b == 0x80 && (i == 0 || content[i - 1] & 0x80 == 0) {
+            return None;
+        }
+        if value > u128::MAX >> 7 {
+            return None;
+        }
+        value = (value << 7) | u128::from(b & 0x7f);
+        if b & 0x80 == 0 {
+            if absolute && arcs.is_empty() {
+                let first = (value / 40).min(2);
+                arcs.push(first.to_string());
+                arcs.push((value - first * 40).to_string());
+            } else {
+                arcs.push(value.to_string());
+            }
+            value = 0;
+        }
+    }
+    Some(arcs.join("."))
+}
+
+fn oid_name(content: &[u8], dotted: &str, absolute: bool) -> Option<String> {
+    if !absolute {
+        return None;
+    }
+    if let Some((_, name)) = EXTRA_OID_NAMES.iter().find(|(oid, _)| *oid == dotted) {
+        return Some(name.to_string());
+    }
+    oid_registry().get(&Oid::new(Cow::Borrowed(content))).map(|entry| entry.sn().to_string())
+}
+
+/// Decode a string type; returns the text and the character encoding used
+fn decode_string(tag: u64, content: &[u8]) -> (String, &'static str) {
+    match tag {
+        30 => {
+            let units: Vec<u16> = content.chunks(2).map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])).collect();
+            (String::from_utf16_lossy(&units), "UTF-16BE")
+        }
+        28 => {
+            let text = content
+                .chunks(4)
+                .map(|c| {
+                    let mut unit = [0u8; 4];
+                    unit[..c.len()].copy_from_slice(c);
+                    char::from_u32(u32::from_be_bytes(unit)).unwrap_or(char::REPLACEMENT_CHARACTER)
+                })
+                .collect();
+            (text, "UTF-32BE")
+        }
+        12 => (String::from_utf8_lossy(content).into_owned(), "UTF-8"),
+        // T.61 / Videotex / General / Graphic strings are treated as Latin-1 as most decoders do
+        20 | 21 | 25 | 27 => (content.iter().map(|b| char::from(*b)).collect(), "ISO-8859-1"),
+        _ => (String::from_utf8_lossy(content).into_owned(), "ASCII"),
+    }
+}
+
+/// UTCTime (YYMMDDHHMM[SS]Z) or GeneralizedTime (YYYYMMDDHH[MM[SS[.fff]]][Z|+hhmm]) to
+/// RFC 3339; offsets are kept as given. None if the value is not well formed.
+fn normalize_time(text: &str, utc_time: bool) -> Option<String> {
+    let digits_end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let (digits, rest) = text.split_at(digits_end);
+    let (year, digits) = if utc_time {
+        let yy: u32 = digits.get(..2)?.parse().ok()?;
+        (if yy >= 50 { 1900 + yy } else { 2000 + yy }, &digits[2..])
+    } else {
+        (digits.get(..4)?.parse().ok()?, &digits[4..])
+    };
+    let field = |i: usize| digits.get(i..i + 2);
+    let (month, day, hour) = (field(0)?, field(2)?, field(4)?);
+    let minute = field(6).unwrap_or("00");
+    let second = field(8).unwrap_or("00");
+    if digits.len() > 10 || digits.len() % 2 != 0 {
+        return None;
+    }
+    let (fraction, zone) = match rest.strip_prefix(['.', ',']) {
+        Some(f) => {
+            let end = f.find(|c: char| !c.is_ascii_digit()).unwrap_or(f.len());
+            (format!(".{}", &f[..end]), &f[end..])
+        }
+        None => (String::new(), rest),
+    };
+    let zone = match zone {
+        "Z" => "Z".to_string(),
+        "" => String::new(),
+        // ASCII digits only, so the slices below stay on char boundaries
+        z if z.len() == 5 && z.starts_with(['+', '-']) && z[1..].bytes().all(|b| b.is_ascii_digit()) => {
+            format!("{}:{}", &z[..3], &z[3..])
+        }
+        _ => return None,
+    };
+    Some(format!("{:04}-{}-{}T{}:{}:{}{}{}", year, month, day, hour, minute, second, fraction, zone))
+}
+
+/// Decode BER/DER (or PEM, which is unwrapped first) to a JSON array of top-level elements
+pub fn decode(input: &[u8]) -> Result<String, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    let der = if text.starts_with("-----BEGIN") { pem::decode(text)?.1 } else { input.to_vec() };
+    if der.is_empty() {
+        return Err("no input".to_string());
+    }
+    let (nodes, _, _) = parse_elements(&der, 0, 0, false);
+    Ok(Value::Array(nodes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(bytes: &[u8]) -> Value {
+        serde_json::from_str(&decode(bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_der_values() {
+        // SEQUENCE { OID sha256WithRSAEncryption, NULL, INTEGER -129, UTCTime, BMPString "hé", BMPString "h", [0] "ok" }
+        let der = hex::decode(concat!(
+            "3037",
+            "06092a864886f70d01010b",
+            "0500",
+            "0202ff7f",
+            "170d3235303130313030303030305a",
+            "1e04006800e9",
+            "1e020068",
+            "8002",
+            "6f6b"
+        ))
+        .unwrap();
+        let root = &tree(&der)[0];
+        assert_eq!(root["type"], "SEQUENCE");
+        assert_eq!(root["length"], 55);
+        let c = root["children"].as_array().unwrap();
+        assert_eq!(c[0]["value"], "1.2.840.113549.1.1.11");
+        assert_eq!(c[0]["name"], "sha256WithRSAEncryption");
+        assert_eq!(c[0]["offset"], 2);
+        assert_eq!(c[1]["type"], "NULL");
+        assert_eq!(c[2]["value"], "-129");
+        assert_eq!(c[3]["time"], "2025-01-01T00:00:00Z");
+        assert_eq!(c[4]["value"], "hé");
+        assert_eq!(c[4]["encoding"], "UTF-16BE");
+        assert_eq!(c[6]["type"], "[0]");
+        assert_eq!(c[6]["class"], "context");
+        assert_eq!(c[6]["text"], "ok");
+    }
+
+    #[test]
+    fn test_encapsulated_and_high_tags() {
+        // OCTET STRING { SEQUENCE { BOOLEAN TRUE } }, then [APPLICATION 33] primitive, GeneralizedTime
+        let der = hex::decode("04053003010101").unwrap();
+        let root = &tree(&der)[0];
+        assert_eq!(root["encapsulates"][0]["children"][0]["value"], true);
+        let app = &tree(&hex::decode("5f210101").unwrap())[0];
+        assert_eq!(app["type"], "[APPLICATION 33]");
+        assert_eq!(app["header_length"], 3);
+        let time = &tree(b"\x18\x1520240229123456.5+0100")[0];
+        assert_eq!(time["time"], "2024-02-29T12:34:56.5+01:00");
+    }
+
+    #[test]
+    fn test_ber_tolerance() {
+        // Indefinite-length SEQUENCE with an end-of-contents marker
+        let ber = hex::decode("30800201050000").unwrap();
+        let root = &tree(&ber)[0];
+        assert_eq!(root["length"], Value::Null);
+        assert_eq!(root["children"][0]["value"], "5");
+        assert!(root.get("error").is_none());
+
+        // Missing end-of-contents, then a truncated definite length
+        let root = &tree(&hex::decode("3080020105").unwrap())[0];
+        assert_eq!(root["error"], "truncated: missing end-of-contents");
+        let root = &tree(&hex::decode("300a020105").unwrap())[0];
+        assert_eq!(root["error"], "truncated: declared 10 bytes, 3 available");
+        assert_eq!(root["children"][0]["value"], "5");
+
+        // Garbage never fails the whole decode
+        let nodes = tree(&hex::decode("02").unwrap());
+        assert_eq!(nodes[0]["error"], "truncated: missing length");
+        let nodes = tree(&hex::decode("0603ffffff").unwrap());
+        assert_eq!(nodes[0]["error"], "malformed object identifier");
+    }
+
+    #[test]
+    fn test_time_zone_offsets() {
+        let node = &tree(b"\x18\x0f2024022912+0130")[0];
+        assert_eq!(node["time"], "2024-02-29T12:00:00+01:30");
+        // A multi-byte character in the offset keeps the raw text instead of panicking
+        let node = &tree(b"\x18\x0f2024022912+1\xc3\xa91")[0];
+        assert_eq!(node["value"], "2024022912+1é1");
+        assert!(node.get("time").is_none());
+    }
+}
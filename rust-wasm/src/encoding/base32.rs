@@ -0,0 +1,65 @@
+// Base32 (RFC 4648, A-Z 2-7), as used by otpauth:// secrets. Decoding is case-insensitive
+// and ignores padding, spaces and hyphens so grouped secrets ("JBSW Y3DP ...") can be pasted.
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn encode(data: &[u8], padding: bool) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    for chunk in data.chunks(5) {
+        let mut block = [0u8; 5];
+        block[..chunk.len()].copy_from_slice(chunk);
+        let bits = block.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64);
+        // Characters needed for 1-5 input bytes
+        let used = (chunk.len() * 8).div_ceil(5);
+        out.extend((0..used).map(|i| ALPHABET[((bits >> (35 - 5 * i)) & 0x1f) as usize] as char));
+        if padding {
+            out.extend(std::iter::repeat_n('=', 8 - used));
+        }
+    }
+    out
+}
+
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits, mut count) = (0u32, 0u32, 0usize);
+    for c in text.trim_end_matches(['=', ' ', '\n', '\r', '\t']).bytes() {
+        if c.is_ascii_whitespace() || c == b'-' {
+            continue;
+        }
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())
+            .ok_or_else(|| format!("invalid Base32 character: {}", c as char))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        count += 1;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    // 1, 3 or 6 characters of a final block cannot come from whole bytes
+    if matches!(count % 8, 1 | 3 | 6) {
+        return Err("invalid Base32 length".to_string());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648 section 10
+    #[test]
+    fn test_base32() {
+        let vectors = [("", ""), ("f", "MY======"), ("fo", "MZXQ===="), ("foo", "MZXW6==="), ("foob", "MZXW6YQ="), ("fooba", "MZXW6YTB"), ("foobar", "MZXW6YTBOI======")];
+        for (plain, encoded) in vectors {
+            assert_eq!(encode(plain.as_bytes(), true), encoded);
+            assert_eq!(decode(encoded).unwrap(), plain.as_bytes());
+        }
+        assert_eq!(encode(b"foobar", false), "MZXW6YTBOI");
+        assert_eq!(decode("mzxw 6ytb-oi").unwrap(), b"foobar");
+        assert!(decode("MZXW6YTB1").is_err());
+        assert!(decode("M").is_err());
+    }
+}
@@ -0,0 +1,94 @@
+// Base58 (Bitcoin alphabet) big-integer encoding and Base58Check (payload followed by the
+// first four bytes of its double SHA-256). Leading zero bytes are kept as leading '1' characters.
+
+use crate::hash::sha256;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base-58 digits of the big-endian number in `data`
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.extend(std::iter::repeat_n('1', zeros));
+    out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    out
+}
+
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let zeros = text.bytes().take_while(|&c| c == b'1').count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    for c in text.bytes().skip(zeros) {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| format!("invalid Base58 character: {}", c as char))?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut out = vec![0u8; zeros];
+    out.extend(bytes.iter().rev());
+    Ok(out)
+}
+
+fn checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = sha256::digest(&sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Base58Check: payload (version bytes included) followed by its 4-byte checksum
+pub fn check_encode(payload: &[u8]) -> String {
+    encode(&[payload, &checksum(payload)].concat())
+}
+
+/// Decode Base58Check and verify the checksum; returns the payload
+pub fn check_decode(text: &str) -> Result<Vec<u8>, String> {
+    let mut data = decode(text.trim())?;
+    if data.len() < 4 {
+        return Err("Base58Check data is too short".to_string());
+    }
+    let sum = data.split_off(data.len() - 4);
+    if sum != checksum(&data) {
+        return Err("invalid Base58Check checksum".to_string());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base58() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(&[0, 0, 57]), "11z");
+        assert_eq!(encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(decode("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert!(decode("0OIl").is_err());
+        // Version 0x00 + HASH160 of the genesis block coinbase key
+        let address = check_encode(&hex::decode("0062e907b15cbf27d5425399ebf6f0fb50ebb88f18").unwrap());
+        assert_eq!(address, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa");
+        assert_eq!(hex::encode(check_decode(&address).unwrap()), "0062e907b15cbf27d5425399ebf6f0fb50ebb88f18");
+        assert!(check_decode("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").is_err());
+    }
+}
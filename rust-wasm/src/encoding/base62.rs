@@ -0,0 +1,66 @@
+// Base62 (0-9, A-Z, a-z) big-integer encoding, as used by Branca tokens.
+// Leading zero bytes are kept as leading '0' characters.
+
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+pub fn encode(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base-62 digits of the big-endian number in `data`
+    let mut digits: Vec<u8> = Vec::with_capacity(data.len() * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 62) as u8;
+            carry /= 62;
+        }
+        while carry > 0 {
+            digits.push((carry % 62) as u8);
+            carry /= 62;
+        }
+    }
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.extend(std::iter::repeat_n('0', zeros));
+    out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    out
+}
+
+pub fn decode(text: &str) -> Result<Vec<u8>, String> {
+    let zeros = text.bytes().take_while(|&c| c == b'0').count();
+    let mut bytes: Vec<u8> = Vec::with_capacity(text.len());
+    for c in text.bytes().skip(zeros) {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| "Invalid Base62 input".to_string())?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 62;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut out = vec![0u8; zeros];
+    out.extend(bytes.iter().rev());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base62() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(&[61]), "z");
+        assert_eq!(encode(&[0, 0, 62]), "0010");
+        assert_eq!(encode(b"Hello"), "5TP3P3v");
+        assert_eq!(decode("5TP3P3v").unwrap(), b"Hello");
+        assert_eq!(decode("0010").unwrap(), [0, 0, 62]);
+        assert!(decode("not-base62").is_err());
+    }
+}
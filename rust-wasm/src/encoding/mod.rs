@@ -3,3 +3,15 @@ pub mod base64_simple;
 pub mod hex_simple;
 pub mod url_simple;
 pub mod ascii_hex;
+pub mod base62;
+pub mod base58;
+pub mod base32;
+pub mod pem;
+// Key container parsing and conversion (PKCS#1, PKCS#8, SPKI, SEC1, OpenSSH, JWK)
+pub mod key_container;
+// X.509 certificate and CSR decoding, fingerprints and chain verification
+pub mod x509;
+// Generic ASN.1 BER/DER structure viewer
+pub mod asn1;
+// OpenSSH fingerprints, randomart, authorized_keys / known_hosts and SSHSIG verification
+pub mod ssh;
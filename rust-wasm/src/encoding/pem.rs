@@ -0,0 +1,66 @@
+use base64::Engine;
+
+/// Wrap DER bytes in a PEM block (RFC 7468, 64-column Base64)
+pub fn encode(label: &str, der: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+    let mut out = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ascii"));
+        out.push('\n');
+    }
+    out.push_str(&format!("-----END {}-----\n", label));
+    out
+}
+
+/// Decode every PEM block in `text`, returning (label, DER) pairs in order
+pub fn decode_all(text: &str) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut blocks = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("-----BEGIN ") {
+        let after = &rest[start + 11..];
+        let label_end = after.find("-----").ok_or("malformed PEM header")?;
+        let label = &after[..label_end];
+        let body = &after[label_end + 5..];
+        let end_marker = format!("-----END {}-----", label);
+        let end = body.find(&end_marker).ok_or("missing PEM footer")?;
+        // Skip RFC 1421 style headers (Proc-Type, DEK-Info) before the Base64 body
+        let payload: String = body[..end]
+            .lines()
+            .filter(|l| !l.contains(':'))
+            .flat_map(|l| l.chars().filter(|c| !c.is_whitespace()))
+            .collect();
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|_| "invalid base64 in PEM body".to_string())?;
+        blocks.push((label.to_string(), der));
+        rest = &body[end + end_marker.len()..];
+    }
+    if blocks.is_empty() {
+        return Err("no PEM block found".to_string());
+    }
+    Ok(blocks)
+}
+
+/// Decode the first PEM block in `text`
+pub fn decode(text: &str) -> Result<(String, Vec<u8>), String> {
+    decode_all(text).map(|mut blocks| blocks.swap_remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pem_roundtrip() {
+        let der: Vec<u8> = (0u8..100).collect();
+        let pem = encode("PUBLIC KEY", &der);
+        assert!(pem.lines().all(|l| l.len() <= 64));
+        assert_eq!(decode(&pem).unwrap(), ("PUBLIC KEY".to_string(), der.clone()));
+
+        let chain = format!("junk\n{}{}", pem, encode("CERTIFICATE", &der[..10]));
+        let blocks = decode_all(&chain).unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].0, "CERTIFICATE");
+        assert!(decode("nothing here").is_err());
+    }
+}
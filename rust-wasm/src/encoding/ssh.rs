@@ -0,0 +1,334 @@
+// OpenSSH key tooling: fingerprints and randomart as `ssh-keygen -l` shows them,
+// authorized_keys and (hashed) known_hosts files, SSHSIG (`ssh-keygen -Y sign`)
+// verification and conversion of key lines to and from PEM / PKCS#8.
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+use ssh_key::known_hosts::{self, HostPatterns, Marker};
+use ssh_key::public::KeyData;
+use ssh_key::{EcdsaCurve, HashAlg, Mpint, PublicKey, SshSig};
+
+use super::key_container;
+use crate::hash::{hmac, md5};
+
+/// A public key line with any leading authorized_keys options (or allowed_signers principals)
+struct KeyLine {
+    options: Vec<String>,
+    key: PublicKey,
+}
+
+/// Split on `separator` (space or comma) outside double-quoted option values
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn parse_key_line(line: &str) -> Result<KeyLine, String> {
+    let line = line.trim();
+    if let Ok(key) = PublicKey::from_openssh(line) {
+        return Ok(KeyLine { options: Vec::new(), key });
+    }
+    // Options may contain quoted spaces (command="..."), so split outside quotes
+    let mut fields = split_unquoted(line, ' ');
+    if fields.len() < 3 {
+        return Err("invalid OpenSSH public key line".to_string());
+    }
+    let options = fields.remove(0);
+    let key = PublicKey::from_openssh(&fields.join(" ")).map_err(|e| format!("invalid OpenSSH public key: {}", e))?;
+    let options = split_unquoted(options, ',').into_iter().map(str::to_string).collect();
+    Ok(KeyLine { options, key })
+}
+
+fn content_lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Public key from an OpenSSH / authorized_keys / allowed_signers line, or anything
+/// `key_container::parse` reads (PEM, DER, OpenSSH private keys, JWK)
+fn read_public_key(input: &[u8], password: &str) -> Result<PublicKey, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    if !text.starts_with("-----BEGIN") && !text.starts_with('{')
+        && let Some(Ok(parsed)) = content_lines(text).next().map(parse_key_line)
+    {
+        return Ok(parsed.key);
+    }
+    let container = key_container::parse(input, password)?;
+    Ok(PublicKey::new(container.key.ssh_public()?, container.comment.as_str()))
+}
+
+fn mpint_bits(n: &Mpint) -> usize {
+    match n.as_positive_bytes() {
+        Some([first, rest @ ..]) => rest.len() * 8 + 8 - first.leading_zeros() as usize,
+        _ => 0,
+    }
+}
+
+/// Key size and the type label `ssh-keygen -l` prints
+fn size_and_label(data: &KeyData) -> (usize, String) {
+    let ecdsa_bits = |curve: EcdsaCurve| match curve {
+        EcdsaCurve::NistP256 => 256,
+        EcdsaCurve::NistP384 => 384,
+        EcdsaCurve::NistP521 => 521,
+    };
+    match data {
+        KeyData::Rsa(k) => (mpint_bits(&k.n), "RSA".to_string()),
+        KeyData::Dsa(k) => (mpint_bits(&k.p), "DSA".to_string()),
+        KeyData::Ecdsa(k) => (ecdsa_bits(k.curve()), "ECDSA".to_string()),
+        KeyData::Ed25519(_) => (256, "ED25519".to_string()),
+        KeyData::SkEcdsaSha2NistP256(_) => (256, "ECDSA-SK".to_string()),
+        KeyData::SkEd25519(_) => (256, "ED25519-SK".to_string()),
+        other => (0, other.algorithm().to_string().to_uppercase()),
+    }
+}
+
+/// MD5 fingerprint of the key blob in the legacy colon-separated form
+fn md5_fingerprint(key: &PublicKey) -> Result<String, String> {
+    let blob = key.to_bytes().map_err(|e| e.to_string())?;
+    let hex = md5::hash(&blob);
+    let pairs: Vec<&str> = (0..hex.len()).step_by(2).map(|i| &hex[i..i + 2]).collect();
+    Ok(format!("MD5:{}", pairs.join(":")))
+}
+
+fn key_info(key: &PublicKey) -> Result<Value, String> {
+    let (bits, label) = size_and_label(key.key_data());
+    let sha256 = key.fingerprint(HashAlg::Sha256);
+    let comment = if key.comment().is_empty() { "no comment" } else { key.comment() };
+    Ok(json!({
+        "type": key.algorithm().to_string(),
+        "bits": bits,
+        "comment": key.comment(),
+        "fingerprints": {
+            "sha256": sha256.to_string(),
+            "sha512": key.fingerprint(HashAlg::Sha512).to_string(),
+            "md5": md5_fingerprint(key)?,
+        },
+        "ssh_keygen": format!("{} {} {} ({})", bits, sha256, comment, label),
+        "randomart": sha256.to_randomart(&format!("[{} {}]", label, bits)),
+        "public_key": key.to_openssh().map_err(|e| e.to_string())?,
+    }))
+}
+
+/// Fingerprints (SHA256 / SHA512 / MD5), the `ssh-keygen -l` line and randomart of a key.
+/// Accepts OpenSSH public key lines (with authorized_keys options), OpenSSH private keys,
+/// PEM, DER and JWK; `password` decrypts encrypted private keys.
+pub fn fingerprint(input: &[u8], password: &str) -> Result<String, String> {
+    key_info(&read_public_key(input, password)?).map(|info| info.to_string())
+}
+
+/// Convert an SSH key (line, authorized_keys entry or private key) with the key_container
+/// formats, e.g. spki-pem, pkcs8-pem or jwk; PEM input converts to openssh-public / openssh
+pub fn convert(input: &[u8], password: &str, format: &str) -> Result<Vec<u8>, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    let key_line = content_lines(text).next().filter(|_| !text.starts_with("-----BEGIN")).and_then(|l| parse_key_line(l).ok());
+    let container = match key_line {
+        Some(line) => key_container::parse(line.key.to_openssh().map_err(|e| e.to_string())?.as_bytes(), "")?,
+        None => key_container::parse(input, password)?,
+    };
+    key_container::convert(&container, format, password)
+}
+
+/// Inspect every entry of an authorized_keys file: options and key details per line
+pub fn authorized_keys(input: &str) -> Result<String, String> {
+    let entries: Vec<Value> = content_lines(input)
+        .map(|line| match parse_key_line(line).and_then(|entry| Ok((key_info(&entry.key)?, entry.options))) {
+            Ok((key, options)) => json!({ "options": options, "key": key }),
+            Err(e) => json!({ "line": line, "error": e }),
+        })
+        .collect();
+    Ok(Value::Array(entries).to_string())
+}
+
+/// Match a hostname against a `*` / `?` glob, case-insensitively
+fn glob_match(pattern: &[u8], host: &[u8]) -> bool {
+    match (pattern.split_first(), host.split_first()) {
+        (None, _) => host.is_empty(),
+        (Some((b'*', rest)), _) => (0..=host.len()).any(|skip| glob_match(rest, &host[skip..])),
+        (Some((b'?', rest)), Some((_, host_rest))) => glob_match(rest, host_rest),
+        (Some((p, rest)), Some((h, host_rest))) => p.eq_ignore_ascii_case(h) && glob_match(rest, host_rest),
+        (Some(_), None) => false,
+    }
+}
+
+/// Host name as written in known_hosts: `host`, or `[host]:port` for ports other than 22
+fn known_hosts_name(host: &str) -> String {
+    let host = host.trim().to_lowercase();
+    match host.rsplit_once(':') {
+        Some((name, "22")) if !name.contains(':') || name.starts_with('[') => {
+            name.trim_start_matches('[').trim_end_matches(']').to_string()
+        }
+        Some((name, port)) if !name.contains(':') && !name.starts_with('[') => format!("[{}]:{}", name, port),
+        _ => host,
+    }
+}
+
+fn host_matches(patterns: &HostPatterns, host: &str) -> Result<bool, String> {
+    match patterns {
+        HostPatterns::HashedName { salt, hash } => Ok(hmac::verify("sha1", salt, host.as_bytes(), hash)?),
+        HostPatterns::Patterns(patterns) => {
+            let mut matched = false;
+            for pattern in patterns {
+                match pattern.strip_prefix('!') {
+                    Some(negated) if glob_match(negated.as_bytes(), host.as_bytes()) => return Ok(false),
+                    Some(_) => {}
+                    None => matched |= glob_match(pattern.as_bytes(), host.as_bytes()),
+                }
+            }
+            Ok(matched)
+        }
+    }
+}
+
+/// Inspect a known_hosts file; hashed (`|1|salt|hash`) entries are matched against `host`
+/// (`name` or `name:port`) when it is given. Returns JSON with the entries and the matching keys.
+pub fn known_hosts(input: &str, host: &str) -> Result<String, String> {
+    let name = (!host.trim().is_empty()).then(|| known_hosts_name(host));
+    let mut entries = Vec::new();
+    for line in content_lines(input) {
+        let entry: known_hosts::Entry = match line.parse() {
+            Ok(entry) => entry,
+            Err(e) => {
+                entries.push(json!({ "line": line, "error": format!("invalid known_hosts entry: {}", e) }));
+                continue;
+            }
+        };
+        let hosts = match entry.host_patterns() {
+            HostPatterns::Patterns(patterns) => json!(patterns),
+            HostPatterns::HashedName { salt, hash } => json!({ "salt": STANDARD.encode(salt), "hash": STANDARD.encode(hash) }),
+        };
+        let matches = name.as_deref().map(|name| host_matches(entry.host_patterns(), name)).transpose()?;
+        entries.push(json!({
+            "marker": entry.marker().map(Marker::as_str),
+            "hashed": matches!(entry.host_patterns(), HostPatterns::HashedName { .. }),
+            "hosts": hosts,
+            "matches": matches,
+            "key": key_info(entry.public_key())?,
+        }));
+    }
+    let matching: Vec<&Value> = entries.iter().filter(|e| e["matches"] == true).map(|e| &e["key"]["fingerprints"]["sha256"]).collect();
+    Ok(json!({ "host": name, "matching_keys": matching, "entries": entries }).to_string())
+}
+
+/// Verify an armored SSHSIG (`ssh-keygen -Y sign`) over `message` with the signer's public key
+/// (OpenSSH line, allowed_signers line, PEM or JWK) and the expected namespace (e.g. "file", "git")
+pub fn verify_signature(key: &[u8], message: &[u8], signature: &str, namespace: &str) -> Result<String, String> {
+    if namespace.is_empty() {
+        return Err("a namespace is required (e.g. file, git)".to_string());
+    }
+    let public = read_public_key(key, "")?;
+    let signature = SshSig::from_pem(signature.trim()).map_err(|e| format!("invalid SSH signature: {}", e))?;
+    let signer = PublicKey::new(signature.public_key().clone(), "");
+    let error = if public.key_data() != signature.public_key() {
+        Some("signature was made with a different key".to_string())
+    } else if signature.namespace() != namespace {
+        Some(format!("namespace mismatch: signature is for \"{}\"", signature.namespace()))
+    } else {
+        public.verify(namespace, message, &signature).err().map(|_| "signature verification failed".to_string())
+    };
+    Ok(json!({
+        "valid": error.is_none(),
+        "error": error,
+        "namespace": signature.namespace(),
+        "hash_algorithm": signature.hash_alg().as_str(),
+        "signature_algorithm": signature.algorithm().as_str(),
+        "signer": signer.fingerprint(HashAlg::Sha256).to_string(),
+    })
+    .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `ssh-keygen -t ed25519 -C test@hashlab`
+    const ED25519: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIFQepZhyJiBBRI/fIo5B9mnx+8YrjFvnOIntYzG87aJS test@hashlab";
+    const ECDSA_P384: &str = "ecdsa-sha2-nistp384 AAAAE2VjZHNhLXNoYTItbmlzdHAzODQAAAAIbmlzdHAzODQAAABhBIfbQepGyTnN+j/WrtH902Th/K9LLYHWwuO+Z7N43h2j4oU9ZFWq4lXojZLISj6AVwSDEXGBdv9lfjr/usjoDM05rxP7Foimz1/drx5YIuK35b8Wf5wztsUuVBiTehj5Cg== ec@hashlab";
+
+    #[test]
+    fn test_fingerprints_and_conversion() {
+        let info: Value = serde_json::from_str(&fingerprint(ED25519.as_bytes(), "").unwrap()).unwrap();
+        // `ssh-keygen -l`, `ssh-keygen -l -E md5` and `ssh-keygen -lv`
+        assert_eq!(info["ssh_keygen"], "256 SHA256:WuPp5bqxhlkF53cxe1h5GDzvHCFMlVq5xL1FCBGjPS4 test@hashlab (ED25519)");
+        assert_eq!(info["fingerprints"]["md5"], "MD5:29:a2:a3:1f:cb:90:02:cf:64:f2:fa:c3:d3:be:7a:f3");
+        assert_eq!(
+            info["randomart"],
+            "+--[ED25519 256]--+\n|            B*+**|\n|        . .o +BO=|\n|         +. o =O*|\n|          o..o++o|\n\
+             |        S.E... +.|\n|       +.o .    o|\n|      .++ .      |\n|      o..=       |\n|       .=o.      |\n+----[SHA256]-----+"
+        );
+        let info: Value = serde_json::from_str(&fingerprint(ECDSA_P384.as_bytes(), "").unwrap()).unwrap();
+        assert_eq!(info["ssh_keygen"], "384 SHA256:bGrtyGyivyPX4660tn+1Ao/Dw0NyC2BwWknDOMFWJ5A ec@hashlab (ECDSA)");
+        assert_eq!(info["fingerprints"]["md5"], "MD5:10:09:fa:d5:c7:39:a7:ce:a4:d4:ea:2a:18:a6:fb:82");
+
+        // Through SPKI PEM and back; PEM carries no comment
+        let pem = convert(format!("no-pty,command=\"echo a b\" {}", ED25519).as_bytes(), "", "spki-pem").unwrap();
+        assert!(pem.starts_with(b"-----BEGIN PUBLIC KEY-----"));
+        let info: Value = serde_json::from_str(&fingerprint(&pem, "").unwrap()).unwrap();
+        assert_eq!(info["fingerprints"]["sha256"], "SHA256:WuPp5bqxhlkF53cxe1h5GDzvHCFMlVq5xL1FCBGjPS4");
+        let line = convert(&pem, "", "openssh-public").unwrap();
+        assert!(ED25519.starts_with(std::str::from_utf8(&line).unwrap().trim()));
+    }
+
+    #[test]
+    fn test_authorized_and_known_hosts() {
+        let file = format!("# keys\nno-pty,command=\"echo a b\" {}\nfrom=\"10.0.0.0/8\" {}\nssh-ed25519 AAAA broken\n", ED25519, ECDSA_P384);
+        let entries: Value = serde_json::from_str(&authorized_keys(&file).unwrap()).unwrap();
+        assert_eq!(entries[0]["options"], json!(["no-pty", "command=\"echo a b\""]));
+        assert_eq!(entries[1]["key"]["bits"], 384);
+        assert!(entries[2]["error"].is_string());
+
+        // `ssh-keygen -H` over github.com, [git.example.org]:2222 (ed25519) and example.com (P-384)
+        let ed = ED25519.rsplit_once(' ').unwrap().0;
+        let ec = ECDSA_P384.rsplit_once(' ').unwrap().0;
+        let file = format!(
+            "|1|Z4cWS+F8vseYWIzlqD06s7R7lyc=|2ymihb6Cd89jkPZTY3/HlhkTcUw= {ed}\n\
+             |1|tknAeznmNldGwRjePtpwPN8zVE0=|PWqU0AlNZU4L5LaxC4fqare64Fo= {ed}\n\
+             |1|HqON5b7zVv0wjIzjuQ5/JsoyXY0=|Qm0jUcaHoZHgZTs7gkInMZ0ysNg= {ec}\n\
+             *.internal,!bad.internal {ec}\n"
+        );
+        let result = |host: &str| -> Value { serde_json::from_str(&known_hosts(&file, host).unwrap()).unwrap() };
+        let matches = |host: &str| -> Vec<bool> {
+            result(host)["entries"].as_array().unwrap().iter().map(|e| e["matches"] == true).collect()
+        };
+        assert_eq!(matches("GitHub.com"), [true, false, false, false]);
+        assert_eq!(matches("git.example.org:2222"), [false, true, false, false]);
+        assert_eq!(matches("example.com:22"), [false, false, true, false]);
+        assert_eq!(matches("db.internal"), [false, false, false, true]);
+        assert_eq!(matches("bad.internal"), [false, false, false, false]);
+        assert_eq!(result("github.com")["matching_keys"], json!(["SHA256:WuPp5bqxhlkF53cxe1h5GDzvHCFMlVq5xL1FCBGjPS4"]));
+        assert_eq!(result("")["entries"][0]["hashed"], true);
+    }
+
+    #[test]
+    fn test_sshsig() {
+        // printf 'hello hashlab\n' | ssh-keygen -Y sign -f key -n file
+        let signature = "-----BEGIN SSH SIGNATURE-----
+U1NIU0lHAAAAAQAAADMAAAALc3NoLWVkMjU1MTkAAAAgVB6lmHImIEFEj98ijkH2afH7xi
+uMW+c4ie1jMbztolIAAAAEZmlsZQAAAAAAAAAGc2hhNTEyAAAAUwAAAAtzc2gtZWQyNTUx
+OQAAAEBlUzfeVN2/wNVNHjK+lp2OZsdpPnjiMo8H0DBcQ+mtDkENcFm6Uqww62czXrdhMg
+aJ/oW3yKWkDR2E9AMYqTQE
+-----END SSH SIGNATURE-----";
+        let check = |key: &str, message: &[u8], namespace: &str| -> Value {
+            serde_json::from_str(&verify_signature(key.as_bytes(), message, signature, namespace).unwrap()).unwrap()
+        };
+        let result = check(&format!("test@hashlab {}", ED25519), b"hello hashlab\n", "file");
+        assert_eq!(result["valid"], true);
+        assert_eq!(result["hash_algorithm"], "sha512");
+        assert_eq!(check(ED25519, b"hello hashlab", "file")["valid"], false);
+        assert_eq!(check(ED25519, b"hello hashlab\n", "git")["valid"], false);
+        assert_eq!(check(ECDSA_P384, b"hello hashlab\n", "file")["error"], "signature was made with a different key");
+        assert!(verify_signature(ED25519.as_bytes(), b"", signature, "").is_err());
+    }
+}
@@ -0,0 +1,542 @@
+// X.509 certificate and CSR decoding to JSON (RFC 5280 / RFC 2986), fingerprints and
+// signature verification within a supplied chain. Everything runs locally; the caller
+// supplies the clock for validity checks.
+use serde_json::{Value, json};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::certification_request::X509CertificationRequest;
+use x509_parser::extensions::{DistributionPointName, GeneralName, ParsedExtension, X509Extension};
+use x509_parser::objects::{oid2sn, oid_registry};
+use x509_parser::oid_registry::Oid;
+use x509_parser::prelude::FromDer;
+use x509_parser::signature_algorithm::SignatureAlgorithm;
+use x509_parser::x509::{AlgorithmIdentifier, SubjectPublicKeyInfo};
+
+use super::key_container::{self, Key};
+use super::pem;
+use crate::crypto::{ecdsa, eddsa, rsa};
+use crate::hash::{sha1, sha256};
+
+/// A certificate or CSR found in the input, as DER
+enum Item {
+    Certificate(Vec<u8>),
+    Request(Vec<u8>),
+}
+
+/// Split PEM (any number of CERTIFICATE / CERTIFICATE REQUEST blocks) or a single DER object
+fn read_items(input: &[u8]) -> Result<Vec<Item>, String> {
+    let text = std::str::from_utf8(input).unwrap_or("").trim();
+    if text.starts_with("-----BEGIN") {
+        let items: Vec<Item> = pem::decode_all(text)?
+            .into_iter()
+            .filter_map(|(label, der)| match label.as_str() {
+                "CERTIFICATE" | "X509 CERTIFICATE" | "TRUSTED CERTIFICATE" => Some(Item::Certificate(der)),
+                "CERTIFICATE REQUEST" | "NEW CERTIFICATE REQUEST" => Some(Item::Request(der)),
+                _ => None,
+            })
+            .collect();
+        if items.is_empty() {
+            return Err("no certificate or certificate request found in PEM".to_string());
+        }
+        return Ok(items);
+    }
+    if X509Certificate::from_der(input).is_ok() {
+        Ok(vec![Item::Certificate(input.to_vec())])
+    } else if X509CertificationRequest::from_der(input).is_ok() {
+        Ok(vec![Item::Request(input.to_vec())])
+    } else {
+        Err("not a PEM or DER certificate or certificate request".to_string())
+    }
+}
+
+fn parse_certificate(der: &[u8]) -> Result<X509Certificate<'_>, String> {
+    X509Certificate::from_der(der)
+        .map(|(_, cert)| cert)
+        .map_err(|e| format!("invalid certificate: {}", e))
+}
+
+// ----------------------------------------------------------------------------
+// Formatting helpers
+// ----------------------------------------------------------------------------
+
+fn oid_name(oid: &Oid) -> String {
+    oid2sn(oid, oid_registry()).map(str::to_string).unwrap_or_else(|_| oid.to_id_string())
+}
+
+/// Colon-separated uppercase hex, as printed by `openssl x509 -fingerprint`
+fn colon_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// Unix seconds to RFC 3339 UTC (days-to-civil conversion, proleptic Gregorian calendar)
+pub fn format_time(ts: i64) -> String {
+    let (days, secs) = (ts.div_euclid(86_400), ts.rem_euclid(86_400));
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// OpenSSL-style GeneralName (DNS:, IP Address:, email:, URI:, DirName:)
+fn general_name(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DNSName(s) => format!("DNS:{}", s),
+        GeneralName::RFC822Name(s) => format!("email:{}", s),
+        GeneralName::URI(s) => format!("URI:{}", s),
+        GeneralName::DirectoryName(dn) => format!("DirName:{}", dn),
+        GeneralName::IPAddress(ip) => match ip.len() {
+            4 => format!("IP Address:{}", std::net::Ipv4Addr::from(<[u8; 4]>::try_from(*ip).expect("4 bytes"))),
+            16 => format!("IP Address:{}", std::net::Ipv6Addr::from(<[u8; 16]>::try_from(*ip).expect("16 bytes"))),
+            _ => format!("IP Address:{}", colon_hex(ip)),
+        },
+        GeneralName::RegisteredID(oid) => format!("Registered ID:{}", oid_name(oid)),
+        GeneralName::OtherName(oid, _) => format!("othername:{}", oid_name(oid)),
+        other => other.to_string(),
+    }
+}
+
+fn general_names(names: &[GeneralName]) -> Vec<String> {
+    names.iter().map(general_name).collect()
+}
+
+/// Human-readable extension value; unknown extensions fall back to hex
+fn extension_value(ext: &X509Extension) -> Value {
+    match ext.parsed_extension() {
+        ParsedExtension::SubjectAlternativeName(san) => json!(general_names(&san.general_names)),
+        ParsedExtension::IssuerAlternativeName(ian) => json!(general_names(&ian.general_names)),
+        ParsedExtension::KeyUsage(ku) => json!(ku.to_string().split(", ").collect::<Vec<_>>()),
+        ParsedExtension::ExtendedKeyUsage(eku) => json!(extended_key_usage(eku)),
+        ParsedExtension::BasicConstraints(bc) => json!({ "ca": bc.ca, "path_len": bc.path_len_constraint }),
+        ParsedExtension::SubjectKeyIdentifier(id) => json!(colon_hex(id.0)),
+        ParsedExtension::AuthorityKeyIdentifier(aki) => json!({
+            "key_id": aki.key_identifier.as_ref().map(|id| colon_hex(id.0)),
+            "issuer": aki.authority_cert_issuer.as_deref().map(general_names),
+            "serial": aki.authority_cert_serial.map(colon_hex),
+        }),
+        ParsedExtension::CRLDistributionPoints(points) => json!(
+            points
+                .points
+                .iter()
+                .filter_map(|p| match &p.distribution_point {
+                    Some(DistributionPointName::FullName(names)) => Some(general_names(names)),
+                    _ => None,
+                })
+                .flatten()
+                .collect::<Vec<_>>()
+        ),
+        ParsedExtension::AuthorityInfoAccess(aia) => json!(
+            aia.accessdescs
+                .iter()
+                .map(|d| format!("{} - {}", oid_name(&d.access_method), general_name(&d.access_location)))
+                .collect::<Vec<_>>()
+        ),
+        ParsedExtension::CertificatePolicies(policies) => {
+            json!(policies.iter().map(|p| oid_name(&p.policy_id)).collect::<Vec<_>>())
+        }
+        ParsedExtension::SCT(scts) => json!(format!("{} signed certificate timestamps", scts.len())),
+        ParsedExtension::InhibitAnyPolicy(iap) => json!(iap.skip_certs),
+        _ => json!(hex::encode(ext.value)),
+    }
+}
+
+fn extended_key_usage(eku: &x509_parser::extensions::ExtendedKeyUsage) -> Vec<String> {
+    let flags = [
+        (eku.any, "anyExtendedKeyUsage"),
+        (eku.server_auth, "serverAuth"),
+        (eku.client_auth, "clientAuth"),
+        (eku.code_signing, "codeSigning"),
+        (eku.email_protection, "emailProtection"),
+        (eku.time_stamping, "timeStamping"),
+        (eku.ocsp_signing, "OCSPSigning"),
+    ];
+    let mut out: Vec<String> = flags.iter().filter(|(set, _)| *set).map(|(_, name)| name.to_string()).collect();
+    out.extend(eku.other.iter().map(oid_name));
+    out
+}
+
+fn extensions_json(extensions: &[X509Extension]) -> Vec<Value> {
+    extensions
+        .iter()
+        .map(|ext| {
+            json!({
+                "oid": ext.oid.to_id_string(),
+                "name": oid_name(&ext.oid),
+                "critical": ext.critical,
+                "value": extension_value(ext),
+            })
+        })
+        .collect()
+}
+
+/// Key summary from `key_container`, or just the algorithm for unsupported key types
+fn public_key_json(spki: &SubjectPublicKeyInfo) -> Value {
+    let parsed = key_container::parse(spki.raw, "").and_then(|c| key_container::summary(&c));
+    match parsed {
+        Ok(mut summary) => {
+            if let Some(fields) = summary.as_object_mut() {
+                for redundant in ["private", "encrypted", "format", "comment"] {
+                    fields.remove(redundant);
+                }
+            }
+            summary
+        }
+        Err(_) => json!({ "type": oid_name(&spki.algorithm.algorithm) }),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Signatures
+// ----------------------------------------------------------------------------
+
+fn hash_from_oid(oid: &str) -> Result<&'static str, String> {
+    match oid {
+        "1.3.14.3.2.26" => Ok("sha1"),
+        "2.16.840.1.101.3.4.2.1" => Ok("sha256"),
+        "2.16.840.1.101.3.4.2.2" => Ok("sha384"),
+        "2.16.840.1.101.3.4.2.3" => Ok("sha512"),
+        _ => Err(format!("unsupported hash algorithm: {}", oid)),
+    }
+}
+
+/// Verify `signature` over `tbs` with the SubjectPublicKeyInfo `spki`
+fn verify_signature(algorithm: &AlgorithmIdentifier, tbs: &[u8], signature: &[u8], spki: &[u8]) -> Result<bool, String> {
+    let key = key_container::parse(spki, "")?.key;
+    let oid = algorithm.algorithm.to_id_string();
+    match (oid.as_str(), &key) {
+        ("1.2.840.113549.1.1.5", Key::Rsa(k)) => rsa::verify(k, tbs, signature, "pkcs1", "sha1", None),
+        ("1.2.840.113549.1.1.11", Key::Rsa(k)) => rsa::verify(k, tbs, signature, "pkcs1", "sha256", None),
+        ("1.2.840.113549.1.1.12", Key::Rsa(k)) => rsa::verify(k, tbs, signature, "pkcs1", "sha384", None),
+        ("1.2.840.113549.1.1.13", Key::Rsa(k)) => rsa::verify(k, tbs, signature, "pkcs1", "sha512", None),
+        ("1.2.840.113549.1.1.10", Key::Rsa(k)) => match SignatureAlgorithm::try_from(algorithm) {
+            Ok(SignatureAlgorithm::RSASSA_PSS(params)) => {
+                let hash = hash_from_oid(&params.hash_algorithm_oid().to_id_string())?;
+                rsa::verify(k, tbs, signature, "pss", hash, Some(params.salt_length() as usize))
+            }
+            _ => Err("invalid RSASSA-PSS parameters".to_string()),
+        },
+        ("1.2.840.10045.4.1", Key::Ec { curve, public, .. }) => ecdsa::verify(*curve, public, tbs, "sha1", signature),
+        ("1.2.840.10045.4.3.2", Key::Ec { curve, public, .. }) => ecdsa::verify(*curve, public, tbs, "sha256", signature),
+        ("1.2.840.10045.4.3.3", Key::Ec { curve, public, .. }) => ecdsa::verify(*curve, public, tbs, "sha384", signature),
+        ("1.2.840.10045.4.3.4", Key::Ec { curve, public, .. }) => ecdsa::verify(*curve, public, tbs, "sha512", signature),
+        ("1.3.101.112" | "1.3.101.113", Key::Ed(k)) => {
+            eddsa::verify(&k.curve.name().to_ascii_lowercase(), &k.public, tbs, signature, b"")
+        }
+        _ => Err(format!(
+            "unsupported signature algorithm {} for this issuer key",
+            oid_name(&algorithm.algorithm)
+        )),
+    }
+}
+
+fn verify_issued(cert: &X509Certificate, issuer: &X509Certificate) -> Result<bool, String> {
+    verify_signature(
+        &cert.signature_algorithm,
+        cert.tbs_certificate.as_ref(),
+        &cert.signature_value.data,
+        issuer.public_key().raw,
+    )
+}
+
+// ----------------------------------------------------------------------------
+// Decoding
+// ----------------------------------------------------------------------------
+
+fn certificate_json(der: &[u8]) -> Result<Value, String> {
+    let cert = parse_certificate(der)?;
+    let validity = cert.validity();
+    let find = |wanted: fn(&ParsedExtension) -> Option<Value>| {
+        cert.extensions().iter().find_map(|ext| wanted(ext.parsed_extension()))
+    };
+    Ok(json!({
+        "kind": "certificate",
+        "version": cert.version().0 + 1,
+        "serial": cert.raw_serial_as_string(),
+        "subject": cert.subject().to_string(),
+        "issuer": cert.issuer().to_string(),
+        "self_issued": cert.subject().as_raw() == cert.issuer().as_raw(),
+        "validity": {
+            "not_before": format_time(validity.not_before.timestamp()),
+            "not_after": format_time(validity.not_after.timestamp()),
+            "not_before_unix": validity.not_before.timestamp(),
+            "not_after_unix": validity.not_after.timestamp(),
+        },
+        "public_key": public_key_json(cert.public_key()),
+        "signature_algorithm": oid_name(&cert.signature_algorithm.algorithm),
+        "is_ca": cert.is_ca(),
+        "subject_alt_names": find(|e| match e {
+            ParsedExtension::SubjectAlternativeName(san) => Some(json!(general_names(&san.general_names))),
+            _ => None,
+        }),
+        "key_usage": find(|e| match e {
+            ParsedExtension::KeyUsage(ku) => Some(json!(ku.to_string().split(", ").collect::<Vec<_>>())),
+            _ => None,
+        }),
+        "extended_key_usage": find(|e| match e {
+            ParsedExtension::ExtendedKeyUsage(eku) => Some(json!(extended_key_usage(eku))),
+            _ => None,
+        }),
+        "extensions": extensions_json(cert.extensions()),
+        "fingerprints": {
+            "sha1": colon_hex(&sha1::digest(der)),
+            "sha256": colon_hex(&sha256::digest(der)),
+        },
+    }))
+}
+
+fn request_json(der: &[u8]) -> Result<Value, String> {
+    let (_, csr) = X509CertificationRequest::from_der(der).map_err(|e| format!("invalid certificate request: {}", e))?;
+    let info = &csr.certification_request_info;
+    let extensions: Vec<X509Extension> = info
+        .iter_attributes()
+        .filter_map(|attr| match attr.parsed_attribute() {
+            x509_parser::cri_attributes::ParsedCriAttribute::ExtensionRequest(req) => Some(req.extensions.clone()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let signature_valid = verify_signature(&csr.signature_algorithm, info.raw, &csr.signature_value.data, info.subject_pki.raw);
+    Ok(json!({
+        "kind": "certificate_request",
+        "version": info.version.0 + 1,
+        "subject": info.subject.to_string(),
+        "public_key": public_key_json(&info.subject_pki),
+        "signature_algorithm": oid_name(&csr.signature_algorithm.algorithm),
+        "signature_valid": signature_valid.as_ref().ok(),
+        "signature_error": signature_valid.err(),
+        "extensions": extensions_json(&extensions),
+        "fingerprints": {
+            "sha1": colon_hex(&sha1::digest(der)),
+            "sha256": colon_hex(&sha256::digest(der)),
+        },
+    }))
+}
+
+/// Decode every certificate / CSR in the input to a JSON array
+pub fn decode(input: &[u8]) -> Result<String, String> {
+    let decoded = read_items(input)?
+        .iter()
+        .map(|item| match item {
+            Item::Certificate(der) => certificate_json(der),
+            Item::Request(der) => request_json(der),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Value::Array(decoded).to_string())
+}
+
+/// Verify each certificate against its issuer within the supplied set (preferring the next
+/// certificate in order, as in a served chain). `now` (Unix seconds) also checks validity periods.
+/// The chain is valid when every signature verifies, every issuer is a CA and all are in date.
+pub fn verify_chain(input: &[u8], now: Option<i64>) -> Result<String, String> {
+    let ders: Vec<Vec<u8>> = read_items(input)?
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::Certificate(der) => Some(der),
+            Item::Request(_) => None,
+        })
+        .collect();
+    if ders.is_empty() {
+        return Err("no certificates to verify".to_string());
+    }
+    let certs = ders.iter().map(|der| parse_certificate(der)).collect::<Result<Vec<_>, _>>()?;
+    let mut valid = true;
+    let mut results = Vec::new();
+    for (i, cert) in certs.iter().enumerate() {
+        let issued_by = |j: &usize| certs[*j].subject().as_raw() == cert.issuer().as_raw();
+        let issuer_index = if cert.subject().as_raw() == cert.issuer().as_raw() {
+            Some(i)
+        } else {
+            Some(i + 1).filter(|j| *j < certs.len() && issued_by(j)).or_else(|| (0..certs.len()).find(issued_by))
+        };
+        let (signature_valid, error) = match issuer_index {
+            Some(j) if j != i && !certs[j].is_ca() => (Some(false), Some("issuer is not a CA".to_string())),
+            Some(j) => match verify_issued(cert, &certs[j]) {
+                Ok(ok) => (Some(ok), None),
+                Err(e) => (None, Some(e)),
+            },
+            None => (None, Some("issuer certificate not supplied".to_string())),
+        };
+        let time_valid = now.map(|t| {
+            let validity = cert.validity();
+            validity.not_before.timestamp() <= t && t <= validity.not_after.timestamp()
+        });
+        valid &= signature_valid == Some(true) && time_valid != Some(false);
+        results.push(json!({
+            "subject": cert.subject().to_string(),
+            "issuer": cert.issuer().to_string(),
+            "issuer_index": issuer_index,
+            "signature_valid": signature_valid,
+            "time_valid": time_valid,
+            "error": error,
+        }));
+    }
+    Ok(json!({ "valid": valid, "certificates": results }).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test PKI made with openssl: RSA root (self-signed with RSASSA-PSS), P-256 intermediate
+    // (sha256WithRSAEncryption), Ed25519 leaf (ecdsa-with-SHA256) valid 2025-06-01..2026-06-01
+    const ROOT: &str = "-----BEGIN CERTIFICATE-----
+MIID1TCCAomgAwIBAgIUB3LiaRFPURiERDa3tQnXh4hI2iowQQYJKoZIhvcNAQEK
+MDSgDzANBglghkgBZQMEAgEFAKEcMBoGCSqGSIb3DQEBCDANBglghkgBZQMEAgEF
+AKIDAgEgMD4xCzAJBgNVBAYTAkdCMRUwEwYDVQQKDAxIYXNobGFiIFRlc3QxGDAW
+BgNVBAMMD0hhc2hsYWIgUm9vdCBDQTAeFw0yNTAxMDEwMDAwMDBaFw0zNTAxMDEw
+MDAwMDBaMD4xCzAJBgNVBAYTAkdCMRUwEwYDVQQKDAxIYXNobGFiIFRlc3QxGDAW
+BgNVBAMMD0hhc2hsYWIgUm9vdCBDQTCCASIwDQYJKoZIhvcNAQEBBQADggEPADCC
+AQoCggEBAMad51UU2tI+Si0vn1jxvtfqpPXjf+sYb0frJXSqeLkPESZn157M8+A0
+kRP269hGoBt9NqR7I2Lgujxh6K1+QJa2ayeZGTPbrjJPI3iylP90z61/LCSXcq5w
+4xciOF/PkoKZM39gUNDtavtxsaseOv1QLSJDoFD17fr4B7Y5/8rVljx1Qx8RwgrW
+/rSDUf0V2JChe2aTUGCydGGWyhAlgfYnkaZJFCrPQ96k2JujU9oc84j/B+PHXS5/
+ICmb+VV6eCG+ZaPo0CB0gfPKEw5JvXx/XrmSgTt/I5GiJO8yvQuTr9iWfARTzmAI
+ukCvpJ0aJF3Wrh/L81cQcdZjHaWCX50CAwEAAaNjMGEwHQYDVR0OBBYEFKChbWcN
+ulbn7WzU6IflgA5VzJKuMB8GA1UdIwQYMBaAFKChbWcNulbn7WzU6IflgA5VzJKu
+MA8GA1UdEwEB/wQFMAMBAf8wDgYDVR0PAQH/BAQDAgEGMEEGCSqGSIb3DQEBCjA0
+oA8wDQYJYIZIAWUDBAIBBQChHDAaBgkqhkiG9w0BAQgwDQYJYIZIAWUDBAIBBQCi
+AwIBIAOCAQEARM8WH/PzqdUxPDqh1mR5xIy+UAvBJr3MPQ0/jW6Vf8/STRutoO8J
+IGft6qWBE4Ty0hUwWTExBC5/YpIFNH0UKon9+tsJkVXtSMiPNT1GYnLSHwfU3i+V
+KDiOceq9ETzgZ84rU0uxTkCPC/ssZlj66ijHpBv6iDKgLn+Yq5cFPSmWUlkEYutp
+EVz9VhXPgu2cENtCnoiLwrGNMp5oq9kHFBt98IaYWriJth7ltGnvF0U65wbEHUki
+xEjpI0wTNTQPTmcbIQLKfL0Y8qIne3uaSfa4Fe/HTnr1/IjRnlWPwS1kVXLfb8Zk
+Urah3heLDS5dwjDEnUcKT3P8nj1IafSgZg==
+-----END CERTIFICATE-----
+";
+    const INTERMEDIATE: &str = "-----BEGIN CERTIFICATE-----
+MIICizCCAXOgAwIBAgICEAEwDQYJKoZIhvcNAQELBQAwPjELMAkGA1UEBhMCR0Ix
+FTATBgNVBAoMDEhhc2hsYWIgVGVzdDEYMBYGA1UEAwwPSGFzaGxhYiBSb290IENB
+MB4XDTI1MDEwMTAwMDAwMFoXDTMwMDEwMTAwMDAwMFowNjEVMBMGA1UECgwMSGFz
+aGxhYiBUZXN0MR0wGwYDVQQDDBRIYXNobGFiIEludGVybWVkaWF0ZTBZMBMGByqG
+SM49AgEGCCqGSM49AwEHA0IABGtzYTOxhwqGS21W0YhZoIzP3Oq0qn5iYkk+GYKU
+fpQxbjNJKnWw1Zi7bcnR0nhx0xqLmdWFDMF7qAC/zHjNIKWjZjBkMBIGA1UdEwEB
+/wQIMAYBAf8CAQAwDgYDVR0PAQH/BAQDAgIEMB0GA1UdDgQWBBTbf+7IHYZbcpY+
+96dNP2pjEWG7/zAfBgNVHSMEGDAWgBSgoW1nDbpW5+1s1OiH5YAOVcySrjANBgkq
+hkiG9w0BAQsFAAOCAQEADjNKLNuvDgFh5xLBY+U0lm3mg4PBM6voO3L56RQPoy1s
+NK7Kv3bYHNPx+dcjVr1RE17TshgSVEs5BrI+3qM7jdUPzRN9e0du1gsV54fJRdWa
+xDQwiqSiHkQahaEEIf7s2uAHeGmkganucd5hcULnIY3hUGl1Z5EZTbshHpjZLOxP
+vGmTOLdUCVap/0XdYWdV/gdQGWc8xaLU9m18Cp7vkpoECia1QyGt44a6s71zQG2M
+MYcThEEaf/7o179GLmzlhkcQVZ17O8ycg8N4fIqHAayV0/gM2hYeBA//TokRRfvP
+ImxIxUEfNR5f5qsyLyxuRFF4a00rpnQ7zaFSsoIs9g==
+-----END CERTIFICATE-----
+";
+    const LEAF: &str = "-----BEGIN CERTIFICATE-----
+MIICKjCCAdGgAwIBAgICIAIwCgYIKoZIzj0EAwIwNjEVMBMGA1UECgwMSGFzaGxh
+YiBUZXN0MR0wGwYDVQQDDBRIYXNobGFiIEludGVybWVkaWF0ZTAeFw0yNTA2MDEw
+MDAwMDBaFw0yNjA2MDEwMDAwMDBaMBsxGTAXBgNVBAMMEHd3dy5leGFtcGxlLnRl
+c3QwKjAFBgMrZXADIQD6FRTQZlvOl51tvudSmkSWl+/ZXJeJz/0u2fwLQNSw5qOC
+ARcwggETMAkGA1UdEwQCMAAwDgYDVR0PAQH/BAQDAgeAMB0GA1UdJQQWMBQGCCsG
+AQUFBwMBBggrBgEFBQcDAjAvBgNVHREEKDAmghB3d3cuZXhhbXBsZS50ZXN0ggxl
+eGFtcGxlLnRlc3SHBMAAAgcwMAYDVR0fBCkwJzAloCOgIYYfaHR0cDovL2NybC5l
+eGFtcGxlLnRlc3QvaW50LmNybDA0BggrBgEFBQcBAQQoMCYwJAYIKwYBBQUHMAGG
+GGh0dHA6Ly9vY3NwLmV4YW1wbGUudGVzdDAdBgNVHQ4EFgQU6ximbtWuCN823H2+
+3YXFtwIz3FMwHwYDVR0jBBgwFoAU23/uyB2GW3KWPvenTT9qYxFhu/8wCgYIKoZI
+zj0EAwIDRwAwRAIgD0WrYCPCC3USH786KVp7IZmqEINZhaI8q7qtMWjAjD4CIHsj
+bjV+lbnUK2At+6g/prWaxmqYfVylk5BKrFT7mt5d
+-----END CERTIFICATE-----
+";
+    const CSR: &str = "-----BEGIN CERTIFICATE REQUEST-----
+MIHdMIGQAgEAMBsxGTAXBgNVBAMMEHd3dy5leGFtcGxlLnRlc3QwKjAFBgMrZXAD
+IQD6FRTQZlvOl51tvudSmkSWl+/ZXJeJz/0u2fwLQNSw5qBCMEAGCSqGSIb3DQEJ
+DjEzMDEwLwYDVR0RBCgwJoIQd3d3LmV4YW1wbGUudGVzdIIMZXhhbXBsZS50ZXN0
+hwTAAAIHMAUGAytlcANBACdjR6cveLDxTbNqHRuyqD5YA9C6+ZCX4SVEmhDMgpec
+tFTIr/Qnl6yvlpH4GLbjmbmyk00HQCZdfc3+vfs8mgU=
+-----END CERTIFICATE REQUEST-----
+";
+
+    const JUNE_2025: i64 = 1_750_000_000;
+
+    fn decoded(input: &str) -> Vec<Value> {
+        serde_json::from_str::<Value>(&decode(input.as_bytes()).unwrap()).unwrap().as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_time(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_time(JUNE_2025), "2025-06-15T15:06:40Z");
+    }
+
+    #[test]
+    fn test_decode_certificate() {
+        let leaf = &decoded(LEAF)[0];
+        assert_eq!(leaf["kind"], "certificate");
+        assert_eq!(leaf["version"], 3);
+        assert_eq!(leaf["serial"], "20:02");
+        assert_eq!(leaf["subject"], "CN=www.example.test");
+        assert_eq!(leaf["issuer"], "O=Hashlab Test, CN=Hashlab Intermediate");
+        assert_eq!(leaf["validity"]["not_before"], "2025-06-01T00:00:00Z");
+        assert_eq!(leaf["validity"]["not_after"], "2026-06-01T00:00:00Z");
+        assert_eq!(leaf["public_key"]["type"], "Ed25519");
+        assert_eq!(leaf["signature_algorithm"], "ecdsa-with-SHA256");
+        assert_eq!(leaf["is_ca"], false);
+        assert_eq!(
+            leaf["subject_alt_names"],
+            json!(["DNS:www.example.test", "DNS:example.test", "IP Address:192.0.2.7"])
+        );
+        assert_eq!(leaf["key_usage"], json!(["Digital Signature"]));
+        assert_eq!(leaf["extended_key_usage"], json!(["serverAuth", "clientAuth"]));
+        assert_eq!(leaf["fingerprints"]["sha1"], "28:E3:48:CC:A3:D8:11:AE:15:3F:75:1D:70:10:CC:EC:31:5C:E6:B8");
+        assert_eq!(
+            leaf["fingerprints"]["sha256"],
+            "65:B4:FE:C0:FD:F2:7C:94:52:6C:0C:15:23:2E:A5:25:23:4D:1C:25:23:FA:44:D0:86:9D:06:58:83:0D:AB:81"
+        );
+        let crl = leaf["extensions"].as_array().unwrap().iter().find(|e| e["oid"] == "2.5.29.31").unwrap();
+        assert_eq!(crl["value"], json!(["URI:http://crl.example.test/int.crl"]));
+
+        let intermediate = &decoded(INTERMEDIATE)[0];
+        assert_eq!(intermediate["public_key"]["curve"], "P-256");
+        let aki = intermediate["extensions"].as_array().unwrap().iter().find(|e| e["oid"] == "2.5.29.35").unwrap();
+        assert_eq!(aki["value"]["key_id"], "A0:A1:6D:67:0D:BA:56:E7:ED:6C:D4:E8:87:E5:80:0E:55:CC:92:AE");
+        let root = &decoded(ROOT)[0];
+        assert_eq!(root["public_key"]["bits"], 2048);
+        assert_eq!(root["self_issued"], true);
+    }
+
+    #[test]
+    fn test_decode_request() {
+        let csr = &decoded(CSR)[0];
+        assert_eq!(csr["kind"], "certificate_request");
+        assert_eq!(csr["subject"], "CN=www.example.test");
+        assert_eq!(csr["signature_valid"], true);
+        assert_eq!(csr["extensions"][0]["value"][1], "DNS:example.test");
+    }
+
+    #[test]
+    fn test_verify_chain() {
+        let chain = format!("{}{}{}", LEAF, INTERMEDIATE, ROOT);
+        let result: Value = serde_json::from_str(&verify_chain(chain.as_bytes(), Some(JUNE_2025)).unwrap()).unwrap();
+        assert_eq!(result["valid"], true, "{}", result);
+        assert_eq!(result["certificates"][0]["issuer_index"], 1);
+        assert_eq!(result["certificates"][2]["issuer_index"], 2);
+
+        // Leaf has expired by 2027 and the chain is incomplete without the root
+        let result: Value = serde_json::from_str(&verify_chain(chain.as_bytes(), Some(1_800_000_000)).unwrap()).unwrap();
+        assert_eq!(result["valid"], false);
+        assert_eq!(result["certificates"][0]["time_valid"], false);
+        let partial = format!("{}{}", LEAF, INTERMEDIATE);
+        let result: Value = serde_json::from_str(&verify_chain(partial.as_bytes(), None).unwrap()).unwrap();
+        assert_eq!(result["valid"], false);
+        assert_eq!(result["certificates"][0]["signature_valid"], true);
+        assert_eq!(result["certificates"][1]["error"], "issuer certificate not supplied");
+
+        // Corrupted signature
+        let leaf = pem::decode(LEAF).unwrap().1;
+        let mut tampered = parse_certificate(&leaf).unwrap();
+        tampered.signature_value.data = vec![0u8; 70].into();
+        let intermediate = pem::decode(INTERMEDIATE).unwrap().1;
+        assert!(!verify_issued(&tampered, &parse_certificate(&intermediate).unwrap()).unwrap());
+    }
+}
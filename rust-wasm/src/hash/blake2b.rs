@@ -1,6 +1,8 @@
 // BLAKE2b hashing - produces a 512-bit (64-byte) hash
 
-use blake2::{Blake2b512, Digest};
+use blake2::digest::consts::{U16, U20, U28, U32, U48, U56, U64};
+use blake2::digest::{Mac, VariableOutput};
+use blake2::{Blake2b512, Blake2bMac, Blake2bVar, Digest};
 
 pub fn hash(text: &str) -> String {
     // Create a new BLAKE2b-512 hasher
@@ -16,6 +18,39 @@ pub fn hash(text: &str) -> String {
     hex::encode(result)
 }
 
+/// BLAKE2b of raw bytes with a 1..=64 byte output
+pub fn digest_var(data: &[u8], out_len: usize) -> Result<Vec<u8>, String> {
+    let mut hasher = Blake2bVar::new(out_len).map_err(|_| "BLAKE2b output must be 1 to 64 bytes".to_string())?;
+    blake2::digest::Update::update(&mut hasher, data);
+    let mut out = vec![0u8; out_len];
+    hasher.finalize_variable(&mut out).expect("output length checked");
+    Ok(out)
+}
+
+/// Keyed BLAKE2b (BLAKE2b-MAC, RFC 7693); key of 1..=64 bytes, output of 16, 20, 28, 32, 48, 56 or 64 bytes
+pub fn mac(key: &[u8], data: &[u8], out_len: usize) -> Result<Vec<u8>, String> {
+    if key.is_empty() || key.len() > 64 {
+        return Err("BLAKE2b key must be 1 to 64 bytes".to_string());
+    }
+    macro_rules! keyed {
+        ($size:ty) => {{
+            let mut mac = Blake2bMac::<$size>::new_with_salt_and_personal(key, &[], &[]).expect("key length checked");
+            Mac::update(&mut mac, data);
+            mac.finalize().into_bytes().to_vec()
+        }};
+    }
+    Ok(match out_len {
+        16 => keyed!(U16),
+        20 => keyed!(U20),
+        28 => keyed!(U28),
+        32 => keyed!(U32),
+        48 => keyed!(U48),
+        56 => keyed!(U56),
+        64 => keyed!(U64),
+        _ => return Err(format!("unsupported BLAKE2b-MAC output length: {}", out_len)),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,5 +69,17 @@ mod tests {
         // This will verify the hash is 128 hex chars (64 bytes)
         assert_eq!(hash.len(), 128);
     }
-}
 
+    // RFC 7693 appendix A (BLAKE2b-512 of "abc") and a keyed value from Python hashlib
+    #[test]
+    fn test_blake2b_var_and_mac() {
+        assert_eq!(hex::encode(digest_var(b"abc", 64).unwrap()), hash("abc"));
+        assert!(hash("abc").starts_with("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1"));
+        assert_eq!(
+            hex::encode(mac(b"key", b"abc", 32).unwrap()),
+            "0330531d097355a3f72e80d55c1245ccf79f1704431c6e3887938320442c23c0"
+        );
+        assert!(digest_var(b"abc", 65).is_err());
+        assert!(mac(b"key", b"abc", 33).is_err());
+    }
+}
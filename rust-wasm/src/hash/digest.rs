@@ -0,0 +1,14 @@
+use super::{keccak256, sha1, sha256, sha384, sha3_256, sha512};
+
+/// Raw digest of `input`; algorithm: sha1 | sha256 | sha384 | sha512 | sha3-256 | keccak256
+pub fn digest(algorithm: &str, input: &[u8]) -> Result<Vec<u8>, String> {
+    match algorithm.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+        "sha1" => Ok(sha1::digest(input).to_vec()),
+        "sha256" => Ok(sha256::digest(input).to_vec()),
+        "sha384" => Ok(sha384::digest(input).to_vec()),
+        "sha512" => Ok(sha512::digest(input).to_vec()),
+        "sha3256" => Ok(sha3_256::digest(input).to_vec()),
+        "keccak256" => Ok(keccak256::digest(input).to_vec()),
+        _ => Err(format!("unknown hash algorithm: {}", algorithm)),
+    }
+}
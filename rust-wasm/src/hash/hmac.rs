@@ -0,0 +1,53 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+
+/// HMAC of raw bytes; algorithm: sha1 | sha256 | sha384 | sha512
+pub fn mac(algorithm: &str, key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    match algorithm {
+        "sha1" => Ok(compute::<Hmac<Sha1>>(key, data)),
+        "sha256" => Ok(compute::<Hmac<Sha256>>(key, data)),
+        "sha384" => Ok(compute::<Hmac<Sha384>>(key, data)),
+        "sha512" => Ok(compute::<Hmac<Sha512>>(key, data)),
+        _ => Err(format!("unknown HMAC algorithm: {}", algorithm)),
+    }
+}
+
+fn compute<M: Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// HMAC-SHA256 of raw bytes
+pub fn sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    compute::<Hmac<Sha256>>(key, data)
+}
+
+/// Constant-time comparison of a received tag with the expected one
+pub fn verify(algorithm: &str, key: &[u8], data: &[u8], tag: &[u8]) -> Result<bool, String> {
+    let expected = mac(algorithm, key, data)?;
+    Ok(expected.len() == tag.len() && expected.iter().zip(tag).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4231 test case 2
+    #[test]
+    fn test_rfc4231() {
+        let key = b"Jefe";
+        let data = b"what do ya want for nothing?";
+        assert_eq!(
+            hex::encode(mac("sha256", key, data).unwrap()),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex::encode(mac("sha512", key, data).unwrap()),
+            "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"
+        );
+        assert!(verify("sha256", key, data, &sha256(key, data)).unwrap());
+        assert!(!verify("sha256", key, data, &[0u8; 32]).unwrap());
+    }
+}
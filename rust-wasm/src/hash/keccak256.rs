@@ -2,6 +2,13 @@
 
 use sha3::{Keccak256, Digest};
 
+/// Hash raw bytes and return the raw digest (Keccak-256)
+pub fn digest(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
 pub fn hash(text: &str) -> String {
     // Create a new Keccak-256 hasher
     let mut hasher = Keccak256::new();
@@ -2,8 +2,13 @@
 pub mod md5;
 pub mod sha1;
 pub mod sha256;
+pub mod sha384;
 pub mod sha512;
 pub mod sha3_256;
 pub mod keccak256;
 pub mod blake2b;
-pub mod blake3_hash;
\ No newline at end of file
+pub mod blake3_hash;
+pub mod ripemd160;
+pub mod hmac;
+// Raw digests selected by algorithm name (used by signature schemes)
+pub mod digest;
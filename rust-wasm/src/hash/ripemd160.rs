@@ -0,0 +1,32 @@
+use ripemd::{Digest, Ripemd160};
+
+use super::sha256;
+
+/// Hash raw bytes and return the raw digest (RIPEMD-160)
+pub fn digest(input: &[u8]) -> [u8; 20] {
+    let mut hasher = Ripemd160::new();
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
+/// Hash raw bytes and return hex string (RIPEMD-160)
+pub fn hash(input: &[u8]) -> String {
+    hex::encode(digest(input))
+}
+
+/// HASH160: RIPEMD-160(SHA-256(input)), the Bitcoin key and script hash
+pub fn hash160(input: &[u8]) -> [u8; 20] {
+    digest(&sha256::digest(input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ripemd160() {
+        assert_eq!(hash(b""), "9c1185a5c5e9fc54612808977ee8f548b2258d31");
+        assert_eq!(hash(b"abc"), "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc");
+        assert_eq!(hex::encode(hash160(b"hello")), "b6a9c8c230722b7c748331a8b450f05566dc7d0f");
+    }
+}
@@ -1,11 +1,15 @@
 use sha1::{Digest, Sha1};
 
-/// Hash raw bytes and return hex string (SHA-1)
-pub fn hash(input: &[u8]) -> String {
+/// Hash raw bytes and return the raw digest (SHA-1)
+pub fn digest(input: &[u8]) -> [u8; 20] {
     let mut hasher = Sha1::new();
     hasher.update(input);
-    let result = hasher.finalize();
-    hex::encode(result)
+    hasher.finalize().into()
+}
+
+/// Hash raw bytes and return hex string (SHA-1)
+pub fn hash(input: &[u8]) -> String {
+    hex::encode(digest(input))
 }
 
 /// Hash a string and return hex string (SHA-1)
@@ -0,0 +1,35 @@
+use sha2::{Digest, Sha384};
+
+/// Hash raw bytes and return the raw digest (SHA-384)
+pub fn digest(input: &[u8]) -> [u8; 48] {
+    let mut hasher = Sha384::new();
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
+/// Hash raw bytes and return hex string (SHA-384)
+pub fn hash(input: &[u8]) -> String {
+    hex::encode(digest(input))
+}
+
+/// Hash a string and return hex string (SHA-384)
+pub fn hash_string(input: &str) -> String {
+    hash(input.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha384_empty() {
+        assert_eq!(hash_string(""), "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b");
+    }
+
+    #[test]
+    fn test_sha384_hello() {
+        assert_eq!(hash_string("hello"), "59e1748777448c69de6b800d7a33bbfb9ff1b463e44354c3553bcdb9c666fa90125a3c79f90397bdf5f6a13de828684f");
+    }
+}
+
+
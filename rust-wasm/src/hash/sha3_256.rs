@@ -1,6 +1,13 @@
 // SHA3-256 hashing - produces a 256-bit (32-byte) hash using the Keccak algorithm
 use sha3::{Sha3_256, Digest};
 
+/// Hash raw bytes and return the raw digest (SHA3-256)
+pub fn digest(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
 pub fn hash(text: &str) -> String {
     // Create a new SHA3-256 hasher
     let mut hasher = Sha3_256::new();
@@ -59,6 +59,25 @@ pub fn hash_blake3(input: &str) -> String {
     hash::blake3_hash::hash(input)
 }
 
+#[wasm_bindgen]
+pub fn hash_sha384(input: &str) -> String {
+    hash::sha384::hash_string(input)
+}
+
+/// RIPEMD-160 of raw bytes, hex output
+#[wasm_bindgen]
+pub fn hash_ripemd160(input: &[u8]) -> String {
+    hash::ripemd160::hash(input)
+}
+
+/// HMAC of raw bytes, hex output; algorithm: sha1 | sha256 | sha384 | sha512
+#[wasm_bindgen]
+pub fn hmac(algorithm: &str, key: &[u8], data: &[u8]) -> Result<String, JsValue> {
+    hash::hmac::mac(algorithm, key, data)
+        .map(hex::encode)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
 // ============================================================================
 // CRYPTO FUNCTIONS 
 // ============================================================================
@@ -335,6 +354,82 @@ pub fn aes256_ctr_decrypt_auto_nonce(combined: &[u8], key: &str) -> Result<Strin
     crypto::aes256_ctr::decrypt_auto_nonce(combined, key).map_err(|e| JsValue::from_str(&e))
 }
 
+// ============================================================================
+// GENERIC BLOCK CIPHER FUNCTIONS (ANY CIPHER / MODE / PADDING)
+// ============================================================================
+
+/// Comma-separated list of supported block cipher names
+#[wasm_bindgen]
+pub fn block_cipher_list() -> String {
+    crypto::block_cipher::CIPHERS.join(",")
+}
+
+#[wasm_bindgen]
+pub fn block_cipher_block_size(cipher: &str) -> Result<usize, JsValue> {
+    crypto::block_cipher::block_size(cipher).map_err(|e| JsValue::from_str(&e))
+}
+
+/// cipher: aes128..aes256, camellia128..256, aria128..256, sm4, twofish, serpent, des, tdes, blowfish, cast5, idea
+/// mode: ecb | cbc | ctr | cfb | ofb
+/// padding: pkcs7 | iso7816 | ansix923 | iso10126 | zero | none
+#[wasm_bindgen]
+pub fn block_encrypt(cipher: &str, mode: &str, padding: &str, key: &[u8], iv: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::block_modes::encrypt(cipher, mode, padding, key, iv, plaintext).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn block_decrypt(cipher: &str, mode: &str, padding: &str, key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::block_modes::decrypt(cipher, mode, padding, key, iv, ciphertext).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn block_encrypt_auto_iv(cipher: &str, mode: &str, padding: &str, key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::block_modes::encrypt_auto_iv(cipher, mode, padding, key, plaintext).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn block_decrypt_auto_iv(cipher: &str, mode: &str, padding: &str, key: &[u8], combined: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::block_modes::decrypt_auto_iv(cipher, mode, padding, key, combined).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// CONFIGURABLE CTR FUNCTIONS
+// ============================================================================
+
+/// Encrypt or decrypt with a custom CTR layout.
+/// cipher: any block cipher name (aes128, camellia256, sm4, des, tdes, blowfish, ...; raw key bytes)
+/// layout: ctr128be | ctr128le | ctr64be | ctr64le | ctr32be | ctr32le
+/// nonce: the non-counter part of the block, or a full counter block
+/// initial_counter: starting block counter; offset: byte offset into the keystream
+#[wasm_bindgen]
+pub fn ctr_apply_keystream(
+    cipher: &str,
+    key: &[u8],
+    layout: &str,
+    nonce: &[u8],
+    initial_counter: u64,
+    offset: u64,
+    data: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    crypto::ctr_layout::process_bytes(cipher, key, layout, nonce, initial_counter, offset, data)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Raw keystream bytes for a custom CTR layout, starting at the given position
+#[wasm_bindgen]
+pub fn ctr_keystream(
+    cipher: &str,
+    key: &[u8],
+    layout: &str,
+    nonce: &[u8],
+    initial_counter: u64,
+    offset: u64,
+    len: usize,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::ctr_layout::keystream(cipher, key, layout, nonce, initial_counter, offset, len)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
 // ============================================================================
 // PBKDF2 KEY DERIVATION FUNCTIONS
 // ============================================================================
@@ -345,103 +440,1248 @@ pub fn derive_aes192_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
 }
 
 // ============================================================================
-// ENCODING FUNCTIONS (Future)
+// HKDF AND KEY-EXPANSION KDFS (RFC 5869, TLS 1.3, SP 800-108, X9.63, CONCATKDF)
 // ============================================================================
 
+/// HKDF-Extract; hash: sha256 | sha384 | sha512 (empty salt = HashLen zero bytes)
 #[wasm_bindgen]
-pub fn encode_base64(input: &str) -> String {
-    encoding::base64_simple::encode(input)
+pub fn hkdf_extract(hash: &str, salt: &[u8], ikm: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::hkdf_extract(hash, salt, ikm).map_err(|e| JsValue::from_str(&e))
 }
 
+/// HKDF-Expand of a PRK to `length` bytes (at most 255 * HashLen)
 #[wasm_bindgen]
-pub fn decode_base64(input: &str) -> Result<String, JsValue> {
-    encoding::base64_simple::decode(input)
-        .map_err(|e| JsValue::from_str(&e.to_string()))
+pub fn hkdf_expand(hash: &str, prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::hkdf_expand(hash, prk, info, length).map_err(|e| JsValue::from_str(&e))
 }
 
+/// Full HKDF (Extract then Expand)
 #[wasm_bindgen]
-pub fn encode_hex(input: &str) -> String {
-    encoding::hex_simple::encode(input)
+pub fn hkdf_derive(hash: &str, ikm: &[u8], salt: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::hkdf(hash, ikm, salt, info, length).map_err(|e| JsValue::from_str(&e))
 }
 
+/// TLS 1.3 HKDF-Expand-Label; the "tls13 " prefix is added to `label`
 #[wasm_bindgen]
-pub fn decode_hex(input: &str) -> Result<String, JsValue> {
-    encoding::hex_simple::decode(input)
-        .map_err(|e| JsValue::from_str(&e.to_string()))
+pub fn tls13_expand_label(
+    hash: &str,
+    secret: &[u8],
+    label: &str,
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::tls13_expand_label(hash, secret, label, context, length).map_err(|e| JsValue::from_str(&e))
 }
 
+/// TLS 1.3 Derive-Secret over the raw transcript messages
 #[wasm_bindgen]
-pub fn encode_url(input: &str) -> String {
-    encoding::url_simple::encode(input)
+pub fn tls13_derive_secret(hash: &str, secret: &[u8], label: &str, messages: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::tls13_derive_secret(hash, secret, label, messages).map_err(|e| JsValue::from_str(&e))
 }
 
+/// NIST SP 800-108 counter-mode KDF with HMAC; hash: sha1 | sha256 | sha384 | sha512
+/// length: at most 255 times the hash output size (as for HKDF and the two KDFs below)
 #[wasm_bindgen]
-pub fn decode_url(input: &str) -> Result<String, JsValue> {
-    encoding::url_simple::decode(input)
-        .map_err(|e| JsValue::from_str(&e.to_string()))
+pub fn kdf_sp800_108_counter(
+    hash: &str,
+    key: &[u8],
+    label: &[u8],
+    context: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::sp800_108_counter(hash, key, label, context, length).map_err(|e| JsValue::from_str(&e))
 }
 
+/// ANSI X9.63 KDF; hash: sha1 | sha224 | sha256 | sha384 | sha512
 #[wasm_bindgen]
-pub fn ascii_to_hex(input: &str) -> String {
-    encoding::ascii_hex::ascii_to_hex(input)
+pub fn kdf_x963(hash: &str, shared_secret: &[u8], shared_info: &[u8], length: usize) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::x963(hash, shared_secret, shared_info, length).map_err(|e| JsValue::from_str(&e))
 }
 
+/// ConcatKDF (NIST SP 800-56A one-step, hash variant)
 #[wasm_bindgen]
-pub fn hex_to_ascii(input: &str) -> Result<String, JsValue> {
-    encoding::ascii_hex::hex_to_ascii(input)
-        .map_err(|e| JsValue::from_str(&e.to_string()))
+pub fn kdf_concat(hash: &str, z: &[u8], other_info: &[u8], length: usize) -> Result<Vec<u8>, JsValue> {
+    crypto::kdf::concat_kdf(hash, z, other_info, length).map_err(|e| JsValue::from_str(&e))
 }
 
 // ============================================================================
-// KEY DERIVATION FUNCTIONS
+// AES KEY WRAP FUNCTIONS (RFC 3394 / RFC 5649)
 // ============================================================================
 
+/// Wrap key data (multiple of 8 bytes, at least 16) with a 16/24/32-byte KEK
 #[wasm_bindgen]
-pub fn derive_aes128_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
-    crypto::pbkdf2_key::derive_aes128_key(passphrase, salt).to_vec()
+pub fn key_wrap(kek: &[u8], key_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::aes_kw::wrap(kek, key_data).map_err(|e| JsValue::from_str(&e))
 }
 
+/// Unwrap an RFC 3394 wrapped key; fails with "integrity check failed" on a bad KEK or tampered input
 #[wasm_bindgen]
-pub fn derive_aes256_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
-    crypto::pbkdf2_key::derive_aes256_key(passphrase, salt).to_vec()
+pub fn key_unwrap(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::aes_kw::unwrap(kek, wrapped).map_err(|e| JsValue::from_str(&e))
 }
 
+/// Wrap key data of any length with padding (RFC 5649 / KWP)
 #[wasm_bindgen]
-pub fn derive_des_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
-    crypto::pbkdf2_key::derive_des_key(passphrase, salt).to_vec()
+pub fn key_wrap_pad(kek: &[u8], key_data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::aes_kw::wrap_with_padding(kek, key_data).map_err(|e| JsValue::from_str(&e))
 }
 
+/// Unwrap an RFC 5649 wrapped key
 #[wasm_bindgen]
-pub fn derive_tdes_key(passphrase: &str, salt: &[u8], three_key: bool) -> Vec<u8> {
-    crypto::pbkdf2_key::derive_tdes_key(passphrase, salt, three_key)
+pub fn key_unwrap_pad(kek: &[u8], wrapped: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::aes_kw::unwrap_with_padding(kek, wrapped).map_err(|e| JsValue::from_str(&e))
 }
 
 // ============================================================================
-// RC4 STREAM CIPHER (LEGACY - FOR EDUCATIONAL PURPOSES ONLY)
+// OPENSSL ENC COMPATIBLE FUNCTIONS ("Salted__" FORMAT)
 // ============================================================================
 
+/// Same output as `openssl enc -<cipher> -md <md> [-iter N]`
+/// cipher: OpenSSL name (aes-256-cbc, camellia-128-ctr, des-ede3-cbc, bf-cbc, chacha20, rc4, ...)
+/// md: md5 | sha1 | sha256 | sha384 | sha512; iterations: 0 = legacy EVP_BytesToKey, otherwise PBKDF2
 #[wasm_bindgen]
-pub fn rc4_encrypt(plaintext: &str, key: &str) -> Vec<u8> {
-    crypto::rc4_cipher::encrypt(plaintext, key)
+pub fn openssl_enc_encrypt(cipher: &str, md: &str, iterations: u32, passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::openssl_enc::encrypt(cipher, md, iterations, passphrase.as_bytes(), plaintext).map_err(|e| JsValue::from_str(&e))
 }
 
+/// Same as openssl_enc_encrypt with Base64 armor (`-a`)
 #[wasm_bindgen]
-pub fn rc4_decrypt(ciphertext: &[u8], key: &str) -> Result<String, JsValue> {
-    crypto::rc4_cipher::decrypt(ciphertext, key).map_err(|e| JsValue::from_str(&e))
+pub fn openssl_enc_encrypt_base64(cipher: &str, md: &str, iterations: u32, passphrase: &str, plaintext: &[u8]) -> Result<String, JsValue> {
+    crypto::openssl_enc::encrypt(cipher, md, iterations, passphrase.as_bytes(), plaintext)
+        .map(|blob| crypto::openssl_enc::armor(&blob))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decrypt a binary or Base64 armored "Salted__" blob (`openssl enc -d [-a]`)
+#[wasm_bindgen]
+pub fn openssl_enc_decrypt(cipher: &str, md: &str, iterations: u32, passphrase: &str, data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::openssl_enc::decrypt(cipher, md, iterations, passphrase.as_bytes(), data).map_err(|e| JsValue::from_str(&e))
 }
 
 // ============================================================================
-// CHACHA20 STREAM CIPHER (MODERN & SECURE)
+// AGE FILE ENCRYPTION (age-encryption.org/v1)
 // ============================================================================
 
+/// Returns [identity "AGE-SECRET-KEY-1...", recipient "age1..."]
 #[wasm_bindgen]
-pub fn chacha20_encrypt_auto_nonce(plaintext: &str, key: &str) -> Vec<u8> {
-    crypto::chacha20_cipher::encrypt_auto_nonce(plaintext, key)
+pub fn age_keygen() -> Vec<String> {
+    let (identity, recipient) = crypto::age::keygen();
+    vec![identity, recipient]
 }
 
 #[wasm_bindgen]
-pub fn chacha20_decrypt_auto_nonce(combined: &[u8], key: &str) -> Result<String, JsValue> {
-    crypto::chacha20_cipher::decrypt_auto_nonce(combined, key).map_err(|e| JsValue::from_str(&e))
+pub fn age_recipient_from_identity(identity: &str) -> Result<String, JsValue> {
+    crypto::age::recipient_from_identity(identity).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Encrypt to one or more X25519 recipients ("age1..."); armor = true gives PEM-style text
+#[wasm_bindgen]
+pub fn age_encrypt(plaintext: &[u8], recipients: Vec<String>, armor: bool) -> Result<Vec<u8>, JsValue> {
+    let recipients: Vec<&str> = recipients.iter().map(String::as_str).collect();
+    crypto::age::encrypt_to_recipients(plaintext, &recipients, armor).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decrypt a binary or armored file with any of the given identities ("AGE-SECRET-KEY-1...")
+#[wasm_bindgen]
+pub fn age_decrypt(data: &[u8], identities: Vec<String>) -> Result<Vec<u8>, JsValue> {
+    let identities: Vec<&str> = identities.iter().map(String::as_str).collect();
+    crypto::age::decrypt_with_identities(data, &identities).map_err(|e| JsValue::from_str(&e))
+}
+
+/// work_factor: log2 of the scrypt cost, 0 for the age default (18)
+#[wasm_bindgen]
+pub fn age_encrypt_passphrase(plaintext: &[u8], passphrase: &str, work_factor: u8, armor: bool) -> Result<Vec<u8>, JsValue> {
+    let work_factor = if work_factor == 0 { crypto::age::DEFAULT_WORK_FACTOR } else { work_factor };
+    crypto::age::encrypt_with_passphrase(plaintext, passphrase, work_factor, armor).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn age_decrypt_passphrase(data: &[u8], passphrase: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::age::decrypt_with_passphrase(data, passphrase, crypto::age::MAX_WORK_FACTOR).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// OPENPGP SYMMETRIC ENCRYPTION (RFC 4880 / RFC 9580)
+// ============================================================================
+
+/// cipher: aes128 | aes192 | aes256 | camellia128..256 | twofish | cast5 | blowfish | tdes | idea
+/// aead: "" for SEIPD v1 (readable by `gpg -d`), "ocb", "eax" or "gcm" for RFC 9580 SEIPD v2 (AES only)
+#[wasm_bindgen]
+pub fn pgp_encrypt_symmetric(data: &[u8], passphrase: &str, cipher: &str, aead: &str, armor: bool) -> Result<Vec<u8>, JsValue> {
+    crypto::openpgp::encrypt(data, passphrase, cipher, aead, armor).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decrypt a binary or armored message such as `gpg --symmetric` output; returns the literal data
+#[wasm_bindgen]
+pub fn pgp_decrypt_symmetric(message: &[u8], passphrase: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::openpgp::decrypt(message, passphrase).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn pgp_armor(data: &[u8]) -> String {
+    crypto::openpgp::armor(data)
+}
+
+#[wasm_bindgen]
+pub fn pgp_dearmor(text: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::openpgp::dearmor(text).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// FERNET AND BRANCA TOKENS
+// ============================================================================
+// `now` is the current Unix time in seconds (e.g. Math.floor(Date.now() / 1000));
+// `ttl` is the maximum token age in seconds, 0 to skip the timestamp checks.
+
+#[wasm_bindgen]
+pub fn fernet_generate_key() -> String {
+    crypto::fernet::generate_key()
+}
+
+#[wasm_bindgen]
+pub fn fernet_encrypt(key: &str, data: &[u8], now: u64) -> Result<String, JsValue> {
+    crypto::fernet::encrypt(key, data, now).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn fernet_decrypt(key: &str, token: &str, ttl: u64, now: u64) -> Result<Vec<u8>, JsValue> {
+    crypto::fernet::decrypt(key, token, ttl, now).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Creation time of a token (unauthenticated, for inspection)
+#[wasm_bindgen]
+pub fn fernet_timestamp(token: &str) -> Result<u64, JsValue> {
+    crypto::fernet::timestamp(token).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn branca_generate_key() -> Vec<u8> {
+    crypto::branca::generate_key()
+}
+
+#[wasm_bindgen]
+pub fn branca_encode(key: &[u8], payload: &[u8], now: u32) -> Result<String, JsValue> {
+    crypto::branca::encode(key, payload, now).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn branca_decode(key: &[u8], token: &str, ttl: u64, now: u64) -> Result<Vec<u8>, JsValue> {
+    crypto::branca::decode(key, token, ttl, now).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Creation time of a token (unauthenticated, for inspection)
+#[wasm_bindgen]
+pub fn branca_timestamp(token: &str) -> Result<u32, JsValue> {
+    crypto::branca::timestamp(token).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// EDDSA SIGNATURES (ED25519 / ED448, RFC 8032)
+// ============================================================================
+
+/// Random private key (seed) for curve "ed25519" (32 bytes) or "ed448" (57 bytes)
+#[wasm_bindgen]
+pub fn eddsa_generate_key(curve: &str) -> Result<Vec<u8>, JsValue> {
+    let curve = crypto::eddsa::Curve::parse(curve).map_err(|e| JsValue::from_str(&e))?;
+    Ok(crypto::eddsa::generate(curve))
+}
+
+/// Public key for an imported or generated seed
+#[wasm_bindgen]
+pub fn eddsa_public_key(curve: &str, seed: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::eddsa::Curve::parse(curve)
+        .and_then(|c| crypto::eddsa::public_key(c, seed))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Sign; scheme: ed25519 | ed25519ph | ed25519ctx | ed448 | ed448ph (context empty for plain Ed25519)
+#[wasm_bindgen]
+pub fn eddsa_sign(scheme: &str, seed: &[u8], message: &[u8], context: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::eddsa::sign(scheme, seed, message, context).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn eddsa_verify(
+    scheme: &str,
+    public_key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+    context: &[u8],
+) -> Result<bool, JsValue> {
+    crypto::eddsa::verify(scheme, public_key, message, signature, context).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Export a private seed as a PKCS#8 PEM
+#[wasm_bindgen]
+pub fn eddsa_private_key_to_pem(curve: &str, seed: &[u8]) -> Result<String, JsValue> {
+    crypto::eddsa::Curve::parse(curve)
+        .and_then(|c| crypto::eddsa::private_to_pem(c, seed))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Export a public key as an SPKI PEM
+#[wasm_bindgen]
+pub fn eddsa_public_key_to_pem(curve: &str, public_key: &[u8]) -> Result<String, JsValue> {
+    crypto::eddsa::Curve::parse(curve)
+        .and_then(|c| crypto::eddsa::public_to_pem(c, public_key))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Export as an OKP JWK; `private` selects whether `key` is a seed (adds "d") or a public key
+#[wasm_bindgen]
+pub fn eddsa_key_to_jwk(curve: &str, key: &[u8], private: bool) -> Result<String, JsValue> {
+    crypto::eddsa::Curve::parse(curve)
+        .and_then(|c| crypto::eddsa::to_jwk(c, key, private))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Import a PKCS#8/SPKI key (PEM or DER) or an OKP JWK.
+/// Returns [curve, private seed hex (empty for public keys), public key hex].
+#[wasm_bindgen]
+pub fn eddsa_import_key(input: &[u8]) -> Result<Vec<String>, JsValue> {
+    let key = crypto::eddsa::import(input).map_err(|e| JsValue::from_str(&e))?;
+    Ok(vec![
+        key.curve.name().to_string(),
+        key.private.map(hex::encode).unwrap_or_default(),
+        hex::encode(key.public),
+    ])
+}
+
+// ============================================================================
+// KEY AGREEMENT (X25519 / X448 / ECDH)
+// ============================================================================
+
+/// Random private key; curve: x25519 | x448 | p256 | p384 | p521 | secp256k1
+#[wasm_bindgen]
+pub fn ecdh_generate_key(curve: &str) -> Result<Vec<u8>, JsValue> {
+    let curve = crypto::ecdh::Curve::parse(curve).map_err(|e| JsValue::from_str(&e))?;
+    Ok(crypto::ecdh::generate(curve))
+}
+
+/// Public key (raw for X25519/X448, SEC1 point for EC curves)
+#[wasm_bindgen]
+pub fn ecdh_public_key(curve: &str, private_key: &[u8], compressed: bool) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdh::public_key(c, private_key, compressed))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Raw shared secret with a peer's public key
+#[wasm_bindgen]
+pub fn ecdh_shared_secret(curve: &str, private_key: &[u8], peer_public_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdh::shared_secret(c, private_key, peer_public_key))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Shared secret post-processed with HKDF (hash: sha256 | sha384 | sha512), ready to use as a cipher key
+#[wasm_bindgen]
+pub fn ecdh_derive_key(
+    curve: &str,
+    private_key: &[u8],
+    peer_public_key: &[u8],
+    hash: &str,
+    salt: &[u8],
+    info: &[u8],
+    length: usize,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdh::derive_key(c, private_key, peer_public_key, hash, salt, info, length))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// ECDSA SIGNATURES (P-256 / P-384 / P-521 / SECP256K1)
+// ============================================================================
+
+/// Random private key; curve: p256 | p384 | p521 | secp256k1
+#[wasm_bindgen]
+pub fn ecdsa_generate_key(curve: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(crypto::ecdsa::generate)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// SEC1 public key (compressed or uncompressed)
+#[wasm_bindgen]
+pub fn ecdsa_public_key(curve: &str, private_key: &[u8], compressed: bool) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdsa::public_key(c, private_key, compressed))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Deterministic (RFC 6979) signature over hash(message).
+/// hash: sha256 | sha384 | sha512 | sha3-256 | keccak256 | none (message is already a digest)
+#[wasm_bindgen]
+pub fn ecdsa_sign(
+    curve: &str,
+    private_key: &[u8],
+    message: &[u8],
+    hash: &str,
+    der: bool,
+    low_s: bool,
+) -> Result<Vec<u8>, JsValue> {
+    let curve = crypto::ecdh::Curve::parse(curve).map_err(|e| JsValue::from_str(&e))?;
+    let raw = crypto::ecdsa::sign(curve, private_key, message, hash, low_s).map_err(|e| JsValue::from_str(&e))?;
+    if der {
+        crypto::ecdsa::raw_to_der(curve, &raw).map_err(|e| JsValue::from_str(&e))
+    } else {
+        Ok(raw)
+    }
+}
+
+/// Verify a raw r||s or DER signature
+#[wasm_bindgen]
+pub fn ecdsa_verify(
+    curve: &str,
+    public_key: &[u8],
+    message: &[u8],
+    hash: &str,
+    signature: &[u8],
+) -> Result<bool, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdsa::verify(c, public_key, message, hash, signature))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn ecdsa_signature_to_der(curve: &str, raw: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdsa::raw_to_der(c, raw))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn ecdsa_signature_to_raw(curve: &str, der: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdsa::der_to_raw(c, der))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Low-S normalisation of a raw r||s signature
+#[wasm_bindgen]
+pub fn ecdsa_normalize_s(curve: &str, raw: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdh::Curve::parse(curve)
+        .and_then(|c| crypto::ecdsa::normalize_s(c, raw))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// secp256k1 recoverable signature r||s||v (v = 0 or 1)
+#[wasm_bindgen]
+pub fn secp256k1_sign_recoverable(private_key: &[u8], message: &[u8], hash: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdsa::sign_recoverable(private_key, message, hash).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Recover the signer's public key from r||s||v (v = 0/1 or 27/28)
+#[wasm_bindgen]
+pub fn secp256k1_recover_public_key(
+    message: &[u8],
+    hash: &str,
+    signature: &[u8],
+    compressed: bool,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::ecdsa::recover_public_key(message, hash, signature, compressed).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// RSA (KEYGEN, OAEP / PKCS#1 V1.5 ENCRYPTION, PSS / PKCS#1 V1.5 SIGNATURES)
+// ============================================================================
+
+/// Generate a 2048, 3072 or 4096-bit RSA key (e = 65537) and return it as PKCS#8 PEM. Keygen
+/// can take seconds in wasm, so `progress(prime_index, candidates_tested)` is invoked for every
+/// prime candidate.
+#[wasm_bindgen]
+pub fn rsa_generate_key(bits: usize, progress: Option<js_sys::Function>) -> Result<String, JsValue> {
+    let key = crypto::rsa::generate(bits, |prime, attempts| {
+        if let Some(f) = &progress {
+            let _ = f.call2(&JsValue::NULL, &JsValue::from(prime), &JsValue::from(attempts));
+        }
+    })
+    .map_err(|e| JsValue::from_str(&e))?;
+    let pem = crypto::rsa::export(&crypto::rsa::Key::Private(Box::new(key)), "pkcs8-pem").map_err(|e| JsValue::from_str(&e))?;
+    String::from_utf8(pem).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Convert a key (PKCS#1, PKCS#8, SPKI as PEM or DER, or JWK) to another format:
+/// pkcs1-pem | pkcs1-der | pkcs1-public-pem | pkcs1-public-der | pkcs8-pem | pkcs8-der |
+/// spki-pem | spki-der | jwk | jwk-public
+#[wasm_bindgen]
+pub fn rsa_export_key(key: &[u8], format: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::rsa::parse_key(key)
+        .and_then(|k| crypto::rsa::export(&k, format))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// padding: oaep | pkcs1; hash and mgf1_hash (OAEP only): sha1 | sha256 | sha384 | sha512
+#[wasm_bindgen]
+pub fn rsa_encrypt(
+    key: &[u8],
+    plaintext: &[u8],
+    padding: &str,
+    hash: &str,
+    mgf1_hash: &str,
+    label: &str,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::rsa::parse_key(key)
+        .and_then(|k| crypto::rsa::encrypt(&k, plaintext, padding, hash, mgf1_hash, label))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn rsa_decrypt(
+    key: &[u8],
+    ciphertext: &[u8],
+    padding: &str,
+    hash: &str,
+    mgf1_hash: &str,
+    label: &str,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::rsa::parse_key(key)
+        .and_then(|k| crypto::rsa::decrypt(&k, ciphertext, padding, hash, mgf1_hash, label))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// scheme: pss | pkcs1; salt_len (PSS only) defaults to the hash length
+#[wasm_bindgen]
+pub fn rsa_sign(
+    key: &[u8],
+    message: &[u8],
+    scheme: &str,
+    hash: &str,
+    salt_len: Option<usize>,
+) -> Result<Vec<u8>, JsValue> {
+    crypto::rsa::parse_key(key)
+        .and_then(|k| crypto::rsa::sign(&k, message, scheme, hash, salt_len))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn rsa_verify(
+    key: &[u8],
+    message: &[u8],
+    signature: &[u8],
+    scheme: &str,
+    hash: &str,
+    salt_len: Option<usize>,
+) -> Result<bool, JsValue> {
+    crypto::rsa::parse_key(key)
+        .and_then(|k| crypto::rsa::verify(&k, message, signature, scheme, hash, salt_len))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// JSON WEB TOKENS (JWS / JWK / JWKS)
+// ============================================================================
+
+/// Decode a JWT's header and payload without verifying it (JSON with `times` for exp / nbf / iat)
+#[wasm_bindgen]
+pub fn jwt_decode(token: &str) -> Result<String, JsValue> {
+    crypto::jwt::decode(token).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Verify a JWT with an HMAC secret, PEM / DER key, JWK or JWKS and validate its claims.
+/// `now` is the current Unix time in seconds (0 skips exp / nbf / iat), `leeway` the allowed
+/// clock skew and `audience` the required `aud` ("" skips). Returns a JSON report.
+#[wasm_bindgen]
+pub fn jwt_verify(token: &str, key: &[u8], now: u64, leeway: u32, audience: &str) -> Result<String, JsValue> {
+    let validation = crypto::jwt::Validation {
+        now: (now > 0).then_some(now as i64),
+        leeway: i64::from(leeway),
+        audience: (!audience.is_empty()).then(|| audience.to_string()),
+    };
+    crypto::jwt::verify(token, key, &validation).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Sign a JWT; alg: HS256/384/512, RS256/384/512, PS256/384/512, ES256/384/512, ES256K, EdDSA.
+/// `header` is an optional JSON object of extra header parameters (e.g. kid).
+#[wasm_bindgen]
+pub fn jwt_sign(payload: &str, key: &[u8], alg: &str, header: &str) -> Result<String, JsValue> {
+    crypto::jwt::sign(payload, key, alg, header).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Describe the keys of a JWK or JWKS (kid, alg, use, type, size, curve, thumbprint) as JSON
+#[wasm_bindgen]
+pub fn jwks_inspect(input: &str) -> Result<String, JsValue> {
+    crypto::jwt::jwks_info(input).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// JSON WEB ENCRYPTION (JWE)
+// ============================================================================
+
+/// Encrypt to a secret / password, PEM key, JWK or JWKS. alg: dir, A128KW / A192KW / A256KW,
+/// A128GCMKW / A192GCMKW / A256GCMKW, PBES2-HS256+A128KW (and HS384 / HS512), RSA-OAEP(-256),
+/// ECDH-ES(+A128KW / A192KW / A256KW); enc: A128CBC-HS256, A192CBC-HS384, A256CBC-HS512,
+/// A128GCM, A192GCM, A256GCM. `header` adds protected header parameters (e.g. {"zip":"DEF"});
+/// serialization: compact | json
+#[wasm_bindgen]
+pub fn jwe_encrypt(plaintext: &[u8], key: &[u8], alg: &str, enc: &str, header: &str, serialization: &str) -> Result<String, JsValue> {
+    crypto::jwe::encrypt(plaintext, key, alg, enc, header, serialization).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decrypt a compact or JSON serialized JWE; returns the plaintext
+#[wasm_bindgen]
+pub fn jwe_decrypt(token: &str, key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::jwe::decrypt(token, key).map(|(_, plaintext)| plaintext).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Headers, recipients and part sizes of a JWE as JSON, without decrypting
+#[wasm_bindgen]
+pub fn jwe_inspect(token: &str) -> Result<String, JsValue> {
+    crypto::jwe::inspect(token).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// PASETO (V3 / V4) AND PASERK
+// ============================================================================
+
+/// Encrypt a v3.local / v4.local token; key is 32 raw bytes or a k3/k4.local PASERK
+#[wasm_bindgen]
+pub fn paseto_encrypt(version: &str, key: &[u8], message: &[u8], footer: &[u8], implicit: &[u8]) -> Result<String, JsValue> {
+    crypto::paseto::encrypt(version, key, message, footer, implicit).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decrypt a local token; returns JSON with the message and the authenticated footer
+/// (each as text, or base64url when not UTF-8)
+#[wasm_bindgen]
+pub fn paseto_decrypt(key: &[u8], token: &str, implicit: &[u8]) -> Result<String, JsValue> {
+    crypto::paseto::decrypt(key, token, implicit).map(crypto::paseto::report).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Sign a public token with an Ed25519 (v4) or P-384 (v3) secret key as PASERK, PEM or JWK
+#[wasm_bindgen]
+pub fn paseto_sign(key: &[u8], message: &[u8], footer: &[u8], implicit: &[u8]) -> Result<String, JsValue> {
+    crypto::paseto::sign(key, message, footer, implicit).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Verify a public token; returns JSON with the message and the authenticated footer, as
+/// paseto_decrypt does
+#[wasm_bindgen]
+pub fn paseto_verify(key: &[u8], token: &str, implicit: &[u8]) -> Result<String, JsValue> {
+    crypto::paseto::verify(key, token, implicit).map(crypto::paseto::report).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Version, purpose, footer and (unverified) public message of a token as JSON
+#[wasm_bindgen]
+pub fn paseto_inspect(token: &str) -> Result<String, JsValue> {
+    crypto::paseto::inspect(token).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Serialize a key as PASERK; kind: local | public | secret | lid | pid | sid.
+/// version (v3 | v4) is only used for raw local keys
+#[wasm_bindgen]
+pub fn paserk_encode(key: &[u8], version: &str, kind: &str) -> Result<String, JsValue> {
+    crypto::paseto::paserk(key, version, kind).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// SHAMIR SECRET SHARING (GF(256) / SLIP-39)
+// ============================================================================
+
+/// Split a secret into `shares` shares with the given threshold. format: hex | base64 (raw
+/// GF(256) shares, Vault layout) | slip39 (mnemonic words, passphrase-protected; the secret must
+/// be an even number of bytes, at least 16)
+#[wasm_bindgen]
+pub fn shamir_split(secret: &[u8], threshold: u8, shares: u8, format: &str, passphrase: &str) -> Result<Vec<String>, JsValue> {
+    crypto::shamir::split(secret, threshold as usize, shares as usize, format, passphrase).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Recover a secret from hex, Base64 or SLIP-39 shares (format detected)
+#[wasm_bindgen]
+pub fn shamir_combine(shares: Vec<String>, passphrase: &str) -> Result<Vec<u8>, JsValue> {
+    let shares: Vec<&str> = shares.iter().map(String::as_str).collect();
+    crypto::shamir::combine(&shares, passphrase).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// BIP-39 MNEMONICS AND BIP-32 / BIP-44 HD KEYS
+// ============================================================================
+
+/// Random English mnemonic of 12, 15, 18, 21 or 24 words
+#[wasm_bindgen]
+pub fn bip39_generate(words: u8) -> Result<String, JsValue> {
+    crypto::bip39::generate(words as usize).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Mnemonic for 16-32 bytes of entropy
+#[wasm_bindgen]
+pub fn bip39_from_entropy(entropy: &[u8]) -> Result<String, JsValue> {
+    crypto::bip39::entropy_to_mnemonic(entropy).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Check words and checksum; returns JSON with valid, word count and entropy or the error
+#[wasm_bindgen]
+pub fn bip39_validate(mnemonic: &str) -> String {
+    crypto::bip39::inspect(mnemonic)
+}
+
+/// 64-byte BIP-39 seed (PBKDF2-HMAC-SHA512) from a mnemonic and optional passphrase
+#[wasm_bindgen]
+pub fn bip39_seed(mnemonic: &str, passphrase: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::bip39::to_seed(mnemonic, passphrase).map_err(|e| JsValue::from_str(&e))
+}
+
+/// BIP-32 master key from a seed; version: xprv | tprv | yprv | zprv
+#[wasm_bindgen]
+pub fn bip32_master(seed: &[u8], version: &str) -> Result<String, JsValue> {
+    crypto::bip32::ExtendedKey::master(seed, version)
+        .map(|key| crypto::bip32::describe(&key).to_string())
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Derive a path (e.g. m/44'/60'/0'/0/0, or relative 0/1) from an xprv / xpub; returns JSON
+/// with the extended keys, raw keys, fingerprints and chain code
+#[wasm_bindgen]
+pub fn bip32_derive(extended_key: &str, path: &str) -> Result<String, JsValue> {
+    crypto::bip32::derive(extended_key, path).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Mnemonic + passphrase -> seed -> master key -> path, in one step
+#[wasm_bindgen]
+pub fn bip44_derive(mnemonic: &str, passphrase: &str, version: &str, path: &str) -> Result<String, JsValue> {
+    crypto::bip32::derive_from_mnemonic(mnemonic, passphrase, version, path).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// CRYPTOCURRENCY ADDRESSES (BITCOIN / ETHEREUM)
+// ============================================================================
+
+/// All address types for a secp256k1 private key (32 bytes) or public key (33, 64 or 65 bytes);
+/// network: mainnet | testnet | regtest. Returns JSON with Ethereum (EIP-55), P2PKH,
+/// P2SH-P2WPKH, P2WPKH and P2TR addresses, plus WIF for private keys
+#[wasm_bindgen]
+pub fn crypto_addresses(key: &[u8], network: &str) -> Result<String, JsValue> {
+    crypto::address::addresses(key, network).map_err(|e| JsValue::from_str(&e))
+}
+
+/// P2SH, P2WSH and P2SH-P2WSH addresses of a redeem / witness script; returns JSON
+#[wasm_bindgen]
+pub fn bitcoin_script_address(script: &[u8], network: &str) -> Result<String, JsValue> {
+    crypto::address::script_addresses(script, network).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Wallet Import Format for a 32-byte private key
+#[wasm_bindgen]
+pub fn wif_encode(private_key: &[u8], compressed: bool, network: &str) -> Result<String, JsValue> {
+    crypto::address::wif_encode(private_key, compressed, network).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Decode a WIF key; returns JSON with the private key, compression flag, network and P2PKH address
+#[wasm_bindgen]
+pub fn wif_decode(wif: &str) -> Result<String, JsValue> {
+    crypto::address::wif_decode(wif).map_err(|e| JsValue::from_str(&e))
+}
+
+/// EIP-55 checksum casing of an Ethereum address
+#[wasm_bindgen]
+pub fn eth_checksum_address(address: &str) -> Result<String, JsValue> {
+    crypto::address::eip55(address).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Function selector and event topic of a signature such as `transfer(address,uint256)`;
+/// returns JSON with the canonical signature, selector and topic
+#[wasm_bindgen]
+pub fn eth_abi_signature(signature: &str) -> Result<String, JsValue> {
+    crypto::address::abi_signature(signature).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Detect and validate an Ethereum, Base58Check (P2PKH / P2SH) or SegWit (Bech32 / Bech32m) address;
+/// returns JSON with valid, chain, type, network and payload, or the error
+#[wasm_bindgen]
+pub fn validate_address(address: &str) -> String {
+    crypto::address::validate(address)
+}
+
+// ============================================================================
+// ONE-TIME PASSWORDS (HOTP / TOTP / OTPAUTH URIS)
+// ============================================================================
+// Secrets are Base32 text as shown by authenticator apps; algorithm: SHA1 | SHA256 | SHA512;
+// `now` is the current Unix time in seconds and `period` the TOTP step (usually 30).
+
+/// Random Base32 secret of `bytes` bytes (20 is the usual size for SHA-1)
+#[wasm_bindgen]
+pub fn otp_generate_secret(bytes: usize) -> Result<String, JsValue> {
+    crypto::otp::generate_secret(bytes).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn hotp_generate(secret: &str, counter: u64, digits: u32, algorithm: &str) -> Result<String, JsValue> {
+    encoding::base32::decode(secret)
+        .and_then(|key| crypto::otp::hotp(&key, counter, digits, algorithm))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Check a code against counters counter..=counter + window; returns JSON with valid,
+/// the matching counter and next_counter
+#[wasm_bindgen]
+pub fn hotp_verify(secret: &str, code: &str, counter: u64, window: u32, digits: u32, algorithm: &str) -> Result<String, JsValue> {
+    encoding::base32::decode(secret)
+        .and_then(|key| crypto::otp::verify_hotp(&key, code, counter, window, digits, algorithm))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn totp_generate(secret: &str, now: u64, period: u64, digits: u32, algorithm: &str) -> Result<String, JsValue> {
+    encoding::base32::decode(secret)
+        .and_then(|key| crypto::otp::totp(&key, now, period, digits, algorithm))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Check a code within `window` periods of `now`; returns JSON with valid and the drift in periods
+#[wasm_bindgen]
+pub fn totp_verify(secret: &str, code: &str, now: u64, period: u64, digits: u32, algorithm: &str, window: u32) -> Result<String, JsValue> {
+    encoding::base32::decode(secret)
+        .and_then(|key| crypto::otp::verify_totp(&key, code, now, period, digits, algorithm, window))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Parse an otpauth:// URI; returns JSON with type, issuer, account, secret, algorithm, digits
+/// and period or counter
+#[wasm_bindgen]
+pub fn otpauth_parse(uri: &str) -> Result<String, JsValue> {
+    crypto::otp::describe_uri(uri).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Current code for an otpauth:// URI (TOTP at `now`, HOTP at the URI's counter)
+#[wasm_bindgen]
+pub fn otpauth_code(uri: &str, now: u64) -> Result<String, JsValue> {
+    crypto::otp::uri_code(uri, now).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Build an otpauth:// URI; kind: totp | hotp, `period_or_counter` is the TOTP period
+/// (0 for the default 30 s) or the initial HOTP counter
+#[wasm_bindgen]
+pub fn otpauth_build(kind: &str, secret: &str, account: &str, issuer: &str, algorithm: &str, digits: u32, period_or_counter: u64) -> Result<String, JsValue> {
+    encoding::base32::decode(secret)
+        .and_then(|key| crypto::otp::build_uri(kind, &key, account, issuer, algorithm, digits, period_or_counter))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// ENCODING FUNCTIONS (Future)
+// ============================================================================
+
+#[wasm_bindgen]
+pub fn encode_base64(input: &str) -> String {
+    encoding::base64_simple::encode(input)
+}
+
+#[wasm_bindgen]
+pub fn decode_base64(input: &str) -> Result<String, JsValue> {
+    encoding::base64_simple::decode(input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn encode_hex(input: &str) -> String {
+    encoding::hex_simple::encode(input)
+}
+
+#[wasm_bindgen]
+pub fn decode_hex(input: &str) -> Result<String, JsValue> {
+    encoding::hex_simple::decode(input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn encode_url(input: &str) -> String {
+    encoding::url_simple::encode(input)
+}
+
+#[wasm_bindgen]
+pub fn decode_url(input: &str) -> Result<String, JsValue> {
+    encoding::url_simple::decode(input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn ascii_to_hex(input: &str) -> String {
+    encoding::ascii_hex::ascii_to_hex(input)
+}
+
+#[wasm_bindgen]
+pub fn hex_to_ascii(input: &str) -> Result<String, JsValue> {
+    encoding::ascii_hex::hex_to_ascii(input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn encode_base62(input: &[u8]) -> String {
+    encoding::base62::encode(input)
+}
+
+#[wasm_bindgen]
+pub fn decode_base62(input: &str) -> Result<Vec<u8>, JsValue> {
+    encoding::base62::decode(input).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn encode_base32(input: &[u8], padding: bool) -> String {
+    encoding::base32::encode(input, padding)
+}
+
+#[wasm_bindgen]
+pub fn decode_base32(input: &str) -> Result<Vec<u8>, JsValue> {
+    encoding::base32::decode(input).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// KEY CONTAINERS (PKCS#1 / PKCS#8 / SPKI / SEC1 / OPENSSH / JWK)
+// ============================================================================
+
+/// Inspect a PEM / DER / OpenSSH / JWK key; returns JSON with type, bits, curve, flags and fingerprints.
+/// `password` is only needed for encrypted PKCS#8 and OpenSSH private keys.
+#[wasm_bindgen]
+pub fn key_inspect(input: &[u8], password: &str) -> Result<String, JsValue> {
+    encoding::key_container::parse(input, password)
+        .and_then(|c| encoding::key_container::info(&c))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Convert a key to: pkcs8-pem | pkcs8-der | spki-pem | spki-der | pkcs1-pem | pkcs1-der |
+/// sec1-pem | sec1-der | openssh | openssh-public | jwk | jwk-public.
+/// A non-empty `output_password` encrypts pkcs8 (PBES2) and openssh output.
+#[wasm_bindgen]
+pub fn key_convert(input: &[u8], password: &str, format: &str, output_password: &str) -> Result<Vec<u8>, JsValue> {
+    encoding::key_container::parse(input, password)
+        .and_then(|c| encoding::key_container::convert(&c, format, output_password))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// SSH KEYS (FINGERPRINTS / AUTHORIZED_KEYS / KNOWN_HOSTS / SSHSIG)
+// ============================================================================
+
+/// SHA256 / SHA512 / MD5 fingerprints, `ssh-keygen -l` line and randomart of an OpenSSH key line,
+/// authorized_keys entry, OpenSSH private key, PEM, DER or JWK key, as JSON
+#[wasm_bindgen]
+pub fn ssh_fingerprint(input: &[u8], password: &str) -> Result<String, JsValue> {
+    encoding::ssh::fingerprint(input, password).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Convert an SSH key line or private key to a key_convert format (e.g. spki-pem, pkcs8-pem),
+/// or PEM / JWK input to openssh-public / openssh
+#[wasm_bindgen]
+pub fn ssh_convert(input: &[u8], password: &str, format: &str) -> Result<Vec<u8>, JsValue> {
+    encoding::ssh::convert(input, password, format).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Options and key details of every authorized_keys entry as a JSON array
+#[wasm_bindgen]
+pub fn ssh_authorized_keys(input: &str) -> Result<String, JsValue> {
+    encoding::ssh::authorized_keys(input).map_err(|e| JsValue::from_str(&e))
+}
+
+/// known_hosts entries as JSON; plain and hashed entries are matched against `host`
+/// (`name` or `name:port`, empty to skip matching)
+#[wasm_bindgen]
+pub fn ssh_known_hosts(input: &str, host: &str) -> Result<String, JsValue> {
+    encoding::ssh::known_hosts(input, host).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Verify an `ssh-keygen -Y sign` signature; returns JSON with valid, namespace and signer fingerprint
+#[wasm_bindgen]
+pub fn ssh_verify_signature(key: &[u8], message: &[u8], signature: &str, namespace: &str) -> Result<String, JsValue> {
+    encoding::ssh::verify_signature(key, message, signature, namespace).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// X.509 CERTIFICATES AND CSRS
+// ============================================================================
+
+/// Decode PEM (certificates, chains, CSRs) or a DER certificate / CSR to a JSON array
+#[wasm_bindgen]
+pub fn x509_decode(input: &[u8]) -> Result<String, JsValue> {
+    encoding::x509::decode(input).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Verify signatures within the supplied chain (leaf first). `now` is the current Unix time in
+/// seconds for validity period checks; 0 skips them. Returns JSON with per-certificate results.
+#[wasm_bindgen]
+pub fn x509_verify_chain(input: &[u8], now: u64) -> Result<String, JsValue> {
+    encoding::x509::verify_chain(input, (now > 0).then_some(now as i64)).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// ASN.1 STRUCTURE VIEWER
+// ============================================================================
+
+/// Decode BER / DER (or PEM) to a JSON tree of tag, class, length, offset and decoded value.
+/// Truncated or malformed input is reported per node instead of failing.
+#[wasm_bindgen]
+pub fn asn1_decode(input: &[u8]) -> Result<String, JsValue> {
+    encoding::asn1::decode(input).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// KEY DERIVATION FUNCTIONS
+// ============================================================================
+
+#[wasm_bindgen]
+pub fn derive_aes128_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
+    crypto::pbkdf2_key::derive_aes128_key(passphrase, salt).to_vec()
+}
+
+#[wasm_bindgen]
+pub fn derive_aes256_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
+    crypto::pbkdf2_key::derive_aes256_key(passphrase, salt).to_vec()
+}
+
+#[wasm_bindgen]
+pub fn derive_des_key(passphrase: &str, salt: &[u8]) -> Vec<u8> {
+    crypto::pbkdf2_key::derive_des_key(passphrase, salt).to_vec()
+}
+
+#[wasm_bindgen]
+pub fn derive_tdes_key(passphrase: &str, salt: &[u8], three_key: bool) -> Vec<u8> {
+    crypto::pbkdf2_key::derive_tdes_key(passphrase, salt, three_key)
+}
+
+// ============================================================================
+// RC4 STREAM CIPHER (LEGACY - FOR EDUCATIONAL PURPOSES ONLY)
+// ============================================================================
+
+#[wasm_bindgen]
+pub fn rc4_encrypt(plaintext: &str, key: &str) -> Vec<u8> {
+    crypto::rc4_cipher::encrypt(plaintext, key)
+}
+
+#[wasm_bindgen]
+pub fn rc4_decrypt(ciphertext: &[u8], key: &str) -> Result<String, JsValue> {
+    crypto::rc4_cipher::decrypt(ciphertext, key).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// CHACHA20 STREAM CIPHER (MODERN & SECURE)
+// ============================================================================
+
+#[wasm_bindgen]
+pub fn chacha20_encrypt_auto_nonce(plaintext: &str, key: &str) -> Vec<u8> {
+    crypto::chacha20_cipher::encrypt_auto_nonce(plaintext, key)
+}
+
+#[wasm_bindgen]
+pub fn chacha20_decrypt_auto_nonce(combined: &[u8], key: &str) -> Result<String, JsValue> {
+    crypto::chacha20_cipher::decrypt_auto_nonce(combined, key).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// SALSA20 FAMILY STREAM CIPHERS
+// ============================================================================
+
+/// Salsa20 keystream XOR with a raw 32-byte key.
+/// variant: salsa20 | salsa20/12 | salsa20/8 (8-byte nonce) | xsalsa20 (24-byte nonce)
+#[wasm_bindgen]
+pub fn salsa20_apply_keystream(variant: &str, key: &[u8], nonce: &[u8], data: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::salsa20_cipher::process(variant, key, nonce, data).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn salsa20_encrypt_auto_nonce(variant: &str, plaintext: &str, key: &str) -> Result<Vec<u8>, JsValue> {
+    crypto::salsa20_cipher::encrypt_auto_nonce(variant, plaintext, key).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn salsa20_decrypt_auto_nonce(variant: &str, combined: &[u8], key: &str) -> Result<String, JsValue> {
+    crypto::salsa20_cipher::decrypt_auto_nonce(variant, combined, key).map_err(|e| JsValue::from_str(&e))
+}
+
+// ============================================================================
+// NACL / LIBSODIUM COMPATIBLE FUNCTIONS
+// ============================================================================
+
+#[wasm_bindgen]
+pub fn nacl_random_nonce() -> Vec<u8> {
+    crypto::nacl_box::random_nonce().to_vec()
+}
+
+/// crypto_secretbox_easy: returns [MAC16][CIPHERTEXT]
+#[wasm_bindgen]
+pub fn secretbox_encrypt(message: &[u8], nonce: &[u8], key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::secretbox_encrypt(message, nonce, key).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_secretbox_open_easy
+#[wasm_bindgen]
+pub fn secretbox_decrypt(boxed: &[u8], nonce: &[u8], key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::secretbox_decrypt(boxed, nonce, key).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_box_keypair: returns [SECRET_KEY32][PUBLIC_KEY32]
+#[wasm_bindgen]
+pub fn box_keypair() -> Vec<u8> {
+    crypto::nacl_box::box_keypair()
+}
+
+#[wasm_bindgen]
+pub fn box_public_key(secret_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::box_public_key(secret_key).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_box_easy: returns [MAC16][CIPHERTEXT]
+#[wasm_bindgen]
+pub fn box_encrypt(message: &[u8], nonce: &[u8], recipient_pk: &[u8], sender_sk: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::box_encrypt(message, nonce, recipient_pk, sender_sk).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_box_open_easy
+#[wasm_bindgen]
+pub fn box_decrypt(boxed: &[u8], nonce: &[u8], sender_pk: &[u8], recipient_sk: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::box_decrypt(boxed, nonce, sender_pk, recipient_sk).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_box_seal: anonymous sender, returns [EPHEMERAL_PK32][MAC16][CIPHERTEXT]
+#[wasm_bindgen]
+pub fn box_seal(message: &[u8], recipient_pk: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::box_seal(message, recipient_pk).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_box_seal_open
+#[wasm_bindgen]
+pub fn box_seal_open(sealed: &[u8], recipient_sk: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::nacl_box::box_seal_open(sealed, recipient_sk).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn secretstream_keygen() -> Vec<u8> {
+    crypto::secretstream::keygen()
+}
+
+/// Tag value for "message", "push", "rekey" or "final"
+#[wasm_bindgen]
+pub fn secretstream_tag(name: &str) -> Result<u8, JsValue> {
+    crypto::secretstream::tag(name).map_err(|e| JsValue::from_str(&e))
+}
+
+/// crypto_secretstream_xchacha20poly1305 encrypting state (init_push / push)
+#[wasm_bindgen]
+pub struct SecretStreamPush {
+    inner: crypto::secretstream::Push,
+}
+
+#[wasm_bindgen]
+impl SecretStreamPush {
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<SecretStreamPush, JsValue> {
+        crypto::secretstream::Push::new(key)
+            .map(|inner| SecretStreamPush { inner })
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// The 24-byte header that must be sent ahead of the first message
+    pub fn header(&self) -> Vec<u8> {
+        self.inner.header()
+    }
+
+    /// tag: 0 = MESSAGE, 1 = PUSH, 2 = REKEY, 3 = FINAL
+    pub fn push(&mut self, message: &[u8], ad: &[u8], tag: u8) -> Vec<u8> {
+        self.inner.push(message, ad, tag)
+    }
+
+    pub fn rekey(&mut self) {
+        self.inner.rekey();
+    }
+}
+
+/// crypto_secretstream_xchacha20poly1305 decrypting state (init_pull / pull)
+#[wasm_bindgen]
+pub struct SecretStreamPull {
+    inner: crypto::secretstream::Pull,
+    last_tag: u8,
+}
+
+#[wasm_bindgen]
+impl SecretStreamPull {
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8], header: &[u8]) -> Result<SecretStreamPull, JsValue> {
+        crypto::secretstream::Pull::new(key, header)
+            .map(|inner| SecretStreamPull { inner, last_tag: 0 })
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Decrypt one message; its tag is available from `last_tag` afterwards
+    pub fn pull(&mut self, ciphertext: &[u8], ad: &[u8]) -> Result<Vec<u8>, JsValue> {
+        let (message, tag) = self.inner.pull(ciphertext, ad).map_err(|e| JsValue::from_str(&e))?;
+        self.last_tag = tag;
+        Ok(message)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn last_tag(&self) -> u8 {
+        self.last_tag
+    }
+
+    pub fn rekey(&mut self) {
+        self.inner.rekey();
+    }
+}
+
+// ============================================================================
+// STREAMING AEAD (CHUNKED ENCRYPTION OF LARGE FILES)
+// ============================================================================
+
+/// One-shot equivalent of StreamEncryptor for data already in memory
+#[wasm_bindgen]
+pub fn stream_encrypt(algorithm: &str, key: &[u8], segment_size: usize, plaintext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let segment_size = if segment_size == 0 { crypto::stream_aead::DEFAULT_SEGMENT_SIZE } else { segment_size };
+    crypto::stream_aead::encrypt(algorithm, key, segment_size, plaintext).map_err(|e| JsValue::from_str(&e))
+}
+
+#[wasm_bindgen]
+pub fn stream_decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crypto::stream_aead::decrypt(key, ciphertext).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Chunk-by-chunk encryptor; concatenating every push() output and finish() gives the ciphertext
+/// algorithm: aes256gcm | xchacha20poly1305, key: 32 bytes, segment_size: 0 for the 64 KiB default
+#[wasm_bindgen]
+pub struct StreamEncryptor {
+    inner: crypto::stream_aead::Encryptor,
+}
+
+#[wasm_bindgen]
+impl StreamEncryptor {
+    #[wasm_bindgen(constructor)]
+    pub fn new(algorithm: &str, key: &[u8], segment_size: usize) -> Result<StreamEncryptor, JsValue> {
+        let segment_size = if segment_size == 0 { crypto::stream_aead::DEFAULT_SEGMENT_SIZE } else { segment_size };
+        crypto::stream_aead::Encryptor::new(algorithm, key, segment_size)
+            .map(|inner| StreamEncryptor { inner })
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.inner.push(chunk).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Seals the last segment; must be called exactly once at the end
+    pub fn finish(&mut self) -> Result<Vec<u8>, JsValue> {
+        self.inner.finish().map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+/// Chunk-by-chunk decryptor; only authenticated plaintext is ever returned
+#[wasm_bindgen]
+pub struct StreamDecryptor {
+    inner: crypto::stream_aead::Decryptor,
+}
+
+#[wasm_bindgen]
+impl StreamDecryptor {
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8]) -> Result<StreamDecryptor, JsValue> {
+        crypto::stream_aead::Decryptor::new(key)
+            .map(|inner| StreamDecryptor { inner })
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.inner.push(chunk).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Fails if the stream was truncated
+    pub fn finish(&mut self) -> Result<Vec<u8>, JsValue> {
+        self.inner.finish().map_err(|e| JsValue::from_str(&e))
+    }
 }
 
 // ============================================================================
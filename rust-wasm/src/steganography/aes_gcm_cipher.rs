@@ -1,6 +1,6 @@
 use aes_gcm::{
-    aead::{Aead, KeyInit},
-    Aes256Gcm, Nonce
+    aead::{consts::U12, Aead, KeyInit, Payload},
+    Aes256Gcm, AesGcm, Nonce
 };
 use getrandom::getrandom;
 
@@ -11,11 +11,11 @@ pub fn encrypt(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
     // Generate random 12-byte nonce
     let mut nonce_bytes = [0u8; 12];
     getrandom(&mut nonce_bytes).map_err(|e| format!("Failed to generate nonce: {}", e))?;
-    let nonce = Nonce::from_slice(&nonce_bytes);
+    let nonce = Nonce::from(nonce_bytes);
     
     // Encrypt
     let ciphertext = cipher
-        .encrypt(nonce, plaintext)
+        .encrypt(&nonce, plaintext)
         .map_err(|e| format!("Encryption failed: {}", e))?;
     
     // Combine nonce + ciphertext (which includes the tag)
@@ -35,15 +35,48 @@ pub fn decrypt(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, String> {
     let cipher = Aes256Gcm::new(key.into());
     
     // Extract nonce and ciphertext
-    let nonce = Nonce::from_slice(&data[0..12]);
+    let mut nonce = Nonce::default();
+    nonce.copy_from_slice(&data[0..12]);
     let ciphertext = &data[12..];
     
     // Decrypt
     cipher
-        .decrypt(nonce, ciphertext)
+        .decrypt(&nonce, ciphertext)
         .map_err(|e| format!("Decryption failed: {}", e))
 }
 
+/// AES-GCM with the key size taken from the key (16, 24 or 32 bytes), a caller-supplied
+/// 96-bit nonce and associated data. Encrypting returns ciphertext || tag; decrypting
+/// expects the same layout.
+fn apply(key: &[u8], nonce: &[u8], aad: &[u8], data: &[u8], encrypt: bool) -> Result<Vec<u8>, String> {
+    if nonce.len() != 12 {
+        return Err("AES-GCM needs a 96-bit nonce".to_string());
+    }
+    let mut nonce_bytes = Nonce::default();
+    nonce_bytes.copy_from_slice(nonce);
+    let payload = Payload { msg: data, aad };
+    let result = match (key.len(), encrypt) {
+        (16, true) => AesGcm::<aes::Aes128, U12>::new(key.into()).encrypt(&nonce_bytes, payload),
+        (24, true) => AesGcm::<aes::Aes192, U12>::new(key.into()).encrypt(&nonce_bytes, payload),
+        (32, true) => AesGcm::<aes::Aes256, U12>::new(key.into()).encrypt(&nonce_bytes, payload),
+        (16, false) => AesGcm::<aes::Aes128, U12>::new(key.into()).decrypt(&nonce_bytes, payload),
+        (24, false) => AesGcm::<aes::Aes192, U12>::new(key.into()).decrypt(&nonce_bytes, payload),
+        (32, false) => AesGcm::<aes::Aes256, U12>::new(key.into()).decrypt(&nonce_bytes, payload),
+        (n, _) => return Err(format!("invalid AES key length: {} bytes", n)),
+    };
+    result.map_err(|_| "authentication failed".to_string())
+}
+
+/// Encrypt with an explicit key, nonce and associated data (AES-128/192/256-GCM)
+pub fn seal(key: &[u8], nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    apply(key, nonce, aad, plaintext, true)
+}
+
+/// Verify and decrypt the output of `seal`
+pub fn open(key: &[u8], nonce: &[u8], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    apply(key, nonce, aad, sealed, false)
+}
+
 /// Derive a 32-byte key from a passphrase using PBKDF2
 pub fn derive_key_from_passphrase(passphrase: &str, salt: &[u8]) -> [u8; 32] {
     use pbkdf2::pbkdf2_hmac;
@@ -69,5 +102,22 @@ mod tests {
         
         assert_eq!(plaintext, &decrypted[..]);
     }
+
+    // NIST GCM test case 4 (AES-128, with associated data)
+    #[test]
+    fn test_seal_open() {
+        let key = hex::decode("feffe9928665731c6d6a8f9467308308").unwrap();
+        let nonce = hex::decode("cafebabefacedbaddecaf888").unwrap();
+        let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+        let plaintext = hex::decode("d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a721c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39").unwrap();
+        let sealed = seal(&key, &nonce, &aad, &plaintext).unwrap();
+        assert_eq!(
+            hex::encode(&sealed),
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e0915bc94fbc3221a5db94fae95ae7121a47"
+        );
+        assert_eq!(open(&key, &nonce, &aad, &sealed).unwrap(), plaintext);
+        assert_eq!(open(&key, &nonce, b"", &sealed).unwrap_err(), "authentication failed");
+        assert!(seal(&key[..15], &nonce, b"", b"").is_err());
+    }
 }
 